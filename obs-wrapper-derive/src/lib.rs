@@ -0,0 +1,344 @@
+//! Derive macros for `obs-wrapper`, re-exported by the `obs_wrapper::source` module.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, ExprLit, Fields, Ident, Lit,
+    LitByteStr, Token,
+};
+
+/// Creates a `&'static CStr` expression from a string known at compile time.
+fn cstr_tokens(value: &str, span: Span) -> TokenStream2 {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.push(0);
+    let literal = LitByteStr::new(&bytes, span);
+
+    quote! {
+        unsafe { ::std::ffi::CStr::from_bytes_with_nul_unchecked(#literal) }
+    }
+}
+
+/// A single argument of an `#[obs(...)]` attribute, either a flag such as `slider`, or a
+/// `key = value` pair.
+struct ObsArg {
+    key: Ident,
+    value: Option<Expr>,
+}
+
+impl ObsArg {
+    fn string_value(&self) -> Result<String, Error> {
+        match &self.value {
+            Some(Expr::Lit(ExprLit { lit: Lit::Str(value), .. })) => Ok(value.value()),
+            _ => Err(Error::new_spanned(&self.key, format!("Expected `{} = \"...\"`.", self.key))),
+        }
+    }
+
+    fn expr_value(&self) -> Result<&Expr, Error> {
+        self.value.as_ref()
+            .ok_or_else(|| Error::new_spanned(&self.key, format!("Expected `{} = ...`.", self.key)))
+    }
+
+    fn flag(&self) -> Result<(), Error> {
+        match &self.value {
+            None => Ok(()),
+            Some(value) => Err(Error::new_spanned(value, format!("`{}` does not take a value.", self.key))),
+        }
+    }
+}
+
+impl Parse for ObsArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Self { key, value })
+    }
+}
+
+/// Collects the arguments of all `#[obs(...)]` attributes.
+fn obs_args(attrs: &[Attribute]) -> Result<Vec<ObsArg>, Error> {
+    let mut args = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("obs")) {
+        args.extend(attr.parse_args_with(Punctuated::<ObsArg, Token![,]>::parse_terminated)?);
+    }
+
+    Ok(args)
+}
+
+fn unknown_arg(arg: &ObsArg) -> Error {
+    Error::new_spanned(&arg.key, format!("Unknown attribute `{}`.", arg.key))
+}
+
+/// Derives `EnumProperty` for an enum with unit variants, so that it can be used as the value
+/// of a list property, with one item per variant.
+///
+/// The label of each item defaults to the name of the variant and may be overridden with
+/// `#[obs(label = "...")]`. The value stored in the settings defaults to the name of the variant
+/// as well and may be overridden with `#[obs(value = "...")]`.
+///
+/// ```rs
+/// #[derive(Clone, Copy, Debug, ObsEnumProperty)]
+/// enum Mode {
+///     Fast,
+///     #[obs(label = "High Quality")]
+///     Accurate,
+/// }
+/// ```
+#[proc_macro_derive(ObsEnumProperty, attributes(obs))]
+pub fn derive_enum_property(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match enum_property(input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn enum_property(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let name = &input.ident;
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => return Err(Error::new_spanned(&input.ident, "`ObsEnumProperty` can only be derived for enums.")),
+    };
+
+    let mut items = Vec::new();
+    let mut to_value_arms = Vec::new();
+    let mut from_value_arms = Vec::new();
+
+    for variant in variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(variant, "`ObsEnumProperty` variants must not have fields."));
+        }
+
+        let ident = &variant.ident;
+        let mut label = ident.to_string();
+        let mut value = ident.to_string();
+
+        for arg in obs_args(&variant.attrs)? {
+            match arg.key.to_string().as_str() {
+                "label" => label = arg.string_value()?,
+                "value" => value = arg.string_value()?,
+                _ => return Err(unknown_arg(&arg)),
+            }
+        }
+
+        let label = cstr_tokens(&label, ident.span());
+        let value_cstr = cstr_tokens(&value, ident.span());
+        let value_bytes = LitByteStr::new(value.as_bytes(), ident.span());
+
+        items.push(quote! { (#value_cstr, #label) });
+        to_value_arms.push(quote! { #name::#ident => #value_cstr });
+        from_value_arms.push(quote! { #value_bytes => ::std::option::Option::Some(#name::#ident) });
+    }
+
+    Ok(quote! {
+        impl ::obs_wrapper::source::properties::EnumProperty for #name {
+            fn items() -> ::std::vec::Vec<(&'static ::std::ffi::CStr, &'static ::std::ffi::CStr)> {
+                ::std::vec![#(#items),*]
+            }
+
+            fn to_value(&self) -> &'static ::std::ffi::CStr {
+                match self {
+                    #(#to_value_arms),*
+                }
+            }
+
+            fn from_value(value: &::std::ffi::CStr) -> ::std::option::Option<Self> {
+                match value.to_bytes() {
+                    #(#from_value_arms,)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+
+        impl ::obs_wrapper::source::properties::PropertyType for #name {
+            type Specialization = ::obs_wrapper::source::properties::PropertyDescriptorSpecializationEnum<Self>;
+
+            fn specialization(
+                _options: &::obs_wrapper::source::properties::PropertyOptions,
+            ) -> Self::Specialization {
+                ::obs_wrapper::source::properties::PropertyDescriptorSpecializationEnum::new()
+            }
+        }
+
+        impl ::std::str::FromStr for #name {
+            type Err = ::obs_wrapper::source::properties::ParseEnumPropertyError;
+
+            fn from_str(value: &str) -> ::std::result::Result<Self, Self::Err> {
+                ::std::ffi::CString::new(value)
+                    .ok()
+                    .and_then(|value| <Self as ::obs_wrapper::source::properties::EnumProperty>::from_value(&value))
+                    .ok_or_else(|| ::obs_wrapper::source::properties::ParseEnumPropertyError {
+                        value: value.to_string(),
+                    })
+            }
+        }
+    })
+}
+
+/// Derives `ObsProperties` for a struct, turning each of its fields into a property.
+///
+/// The field types must implement `PropertyType`, which is the case for `bool`, `i32`, `f64`,
+/// `CString`, `PathBuf`, `Color` and enums deriving `ObsEnumProperty`. Fields of structs
+/// deriving `ObsProperties` marked with `#[obs(group)]` become groups of properties. The
+/// default values of the properties are taken from the `Default` implementation of the struct.
+///
+/// The following attributes are supported on fields:
+/// * `name = "..."`: the name of the setting, defaults to the name of the field;
+/// * `description = "..."`: the label shown to the user, defaults to the name of the field;
+/// * `min = ...`, `max = ...`, `step = ...`: the range of numeric properties;
+/// * `slider`: displays numeric properties as sliders;
+/// * `group`: displays the properties of a nested struct as a group.
+///
+/// ```rs
+/// #[derive(ObsProperties)]
+/// struct Settings {
+///     #[obs(description = "Amount to zoom in", min = 1.0, max = 5.0, step = 0.001, slider)]
+///     zoom: f64,
+///     #[obs(description = "Mode")]
+///     mode: Mode,
+///     #[obs(description = "Region", group)]
+///     region: Region,
+/// }
+/// ```
+#[proc_macro_derive(ObsProperties, attributes(obs))]
+pub fn derive_properties(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match properties(input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn properties(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(&input.ident, "`ObsProperties` requires named fields.")),
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "`ObsProperties` can only be derived for structs.")),
+    };
+
+    let properties_path = quote! { ::obs_wrapper::source::properties };
+    let mut add_statements = Vec::new();
+    let mut default_statements = Vec::new();
+    let mut field_initializers = Vec::new();
+
+    for field in fields {
+        let ident = field.ident.as_ref().expect("Named fields have identifiers.");
+        let ty = &field.ty;
+        let mut setting_name = ident.to_string();
+        let mut description = ident.to_string();
+        let mut min = quote! { ::std::option::Option::None };
+        let mut max = quote! { ::std::option::Option::None };
+        let mut step = quote! { ::std::option::Option::None };
+        let mut slider = false;
+        let mut group = false;
+
+        for arg in obs_args(&field.attrs)? {
+            match arg.key.to_string().as_str() {
+                "name" => setting_name = arg.string_value()?,
+                "description" => description = arg.string_value()?,
+                "min" => {
+                    let value = arg.expr_value()?;
+                    min = quote! { ::std::option::Option::Some((#value) as f64) };
+                }
+                "max" => {
+                    let value = arg.expr_value()?;
+                    max = quote! { ::std::option::Option::Some((#value) as f64) };
+                }
+                "step" => {
+                    let value = arg.expr_value()?;
+                    step = quote! { ::std::option::Option::Some((#value) as f64) };
+                }
+                "slider" => {
+                    arg.flag()?;
+                    slider = true;
+                }
+                "group" => {
+                    arg.flag()?;
+                    group = true;
+                }
+                _ => return Err(unknown_arg(&arg)),
+            }
+        }
+
+        let setting_name = cstr_tokens(&setting_name, ident.span());
+        let description = cstr_tokens(&description, ident.span());
+
+        if group {
+            add_statements.push(quote! {
+                let mut group = #properties_path::Properties::new();
+                <#ty as #properties_path::ObsProperties>::add_properties(&mut group);
+                properties.add_group(#setting_name, #description, group);
+            });
+            default_statements.push(quote! {
+                <#ty as #properties_path::ObsProperties>::set_defaults(settings);
+            });
+            field_initializers.push(quote! {
+                #ident: <#ty as #properties_path::ObsProperties>::from_settings(settings)
+            });
+        } else {
+            let descriptor = quote! {
+                #properties_path::PropertyDescriptor {
+                    name: #setting_name.into(),
+                    description: #description.into(),
+                    specialization: <#ty as #properties_path::PropertyType>::specialization(
+                        &#properties_path::PropertyOptions {
+                            min: #min,
+                            max: #max,
+                            step: #step,
+                            slider: #slider,
+                        },
+                    ),
+                }
+            };
+
+            add_statements.push(quote! {
+                properties.add_property(&#descriptor);
+            });
+            default_statements.push(quote! {
+                settings.set_property_default_value(&#descriptor, defaults.#ident);
+            });
+            field_initializers.push(quote! {
+                #ident: settings.get_property_value(&#descriptor, &defaults.#ident)
+            });
+        }
+    }
+
+    Ok(quote! {
+        impl #properties_path::ObsProperties for #name {
+            fn add_properties(properties: &mut #properties_path::Properties) {
+                #(#add_statements)*
+            }
+
+            #[allow(unused_variables)]
+            fn set_defaults(settings: &mut #properties_path::SettingsContext) {
+                let defaults = <Self as ::std::default::Default>::default();
+                #(#default_statements)*
+            }
+
+            #[allow(unused_variables)]
+            fn from_settings(settings: &mut #properties_path::SettingsContext) -> Self {
+                let defaults = <Self as ::std::default::Default>::default();
+
+                Self {
+                    #(#field_initializers),*
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,155 @@
+use obs_wrapper::{audio::*, obs_register_module, prelude::*, source::*};
+use std::ffi::CStr;
+
+#[derive(ObsProperties)]
+struct Settings {
+    #[obs(description = "Gain (dB)", min = -30.0, max = 30.0, step = 0.1, slider)]
+    gain: f64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self { gain: 0.0 }
+    }
+}
+
+struct Data {
+    multiplier: f32,
+}
+
+impl Data {
+    fn from_settings(settings: &mut SettingsContext) -> Self {
+        Self {
+            multiplier: db_to_mul(Settings::from_settings(settings).gain as f32),
+        }
+    }
+}
+
+struct AudioGainFilter {
+    context: ModuleContext,
+}
+
+impl Sourceable for AudioGainFilter {
+    fn get_id() -> &'static CStr {
+        cstr!("rust_audio_gain_filter")
+    }
+    fn get_type() -> SourceType {
+        SourceType::FILTER
+    }
+}
+
+impl GetNameSource<Data> for AudioGainFilter {
+    fn get_name() -> &'static CStr {
+        cstr!("Rust Gain")
+    }
+}
+
+impl GetPropertiesSource<Data> for AudioGainFilter {
+    fn get_properties(_context: PluginContext<Data>) -> Properties {
+        Settings::properties()
+    }
+}
+
+impl GetDefaultsSource<Data> for AudioGainFilter {
+    fn get_defaults(settings: &mut SettingsContext) {
+        Settings::set_defaults(settings);
+    }
+}
+
+impl CreatableSource<Data> for AudioGainFilter {
+    fn create(settings: &mut SettingsContext, _source: SourceContext) -> Data {
+        Data::from_settings(settings)
+    }
+}
+
+impl UpdateSource<Data> for AudioGainFilter {
+    fn update(mut context: PluginContext<Data>) {
+        let (data, settings) = context.data_settings_mut();
+
+        if let Some(data) = data {
+            *data = Data::from_settings(settings);
+        }
+    }
+}
+
+impl FilterAudioSource<Data> for AudioGainFilter {
+    fn filter_audio(context: PluginContext<Data>, audio: &mut FilterAudioData) {
+        if let Some(data) = context.data() {
+            for channel in 0..audio.channels() {
+                if let Some(samples) = audio.channel_mut(channel) {
+                    samples.iter_mut().for_each(|sample| *sample *= data.multiplier);
+                }
+            }
+        }
+    }
+}
+
+impl Module for AudioGainFilter {
+    fn new(context: ModuleContext) -> Self {
+        Self { context }
+    }
+    fn get_ctx(&self) -> &ModuleContext {
+        &self.context
+    }
+
+    fn load(&mut self, load_context: &mut LoadContext) -> bool {
+        // Only audio callbacks are enabled, so the filter is registered as an audio filter.
+        let source = load_context
+            .create_source_builder::<AudioGainFilter, Data>()
+            .enable_get_name()
+            .enable_get_properties()
+            .enable_get_defaults()
+            .enable_create()
+            .enable_update()
+            .enable_filter_audio()
+            .build();
+
+        load_context.register_source(source);
+
+        true
+    }
+
+    fn description() -> &'static CStr {
+        cstr!("An audio filter amplifying or attenuating the audio of a source.")
+    }
+    fn name() -> &'static CStr {
+        cstr!("Audio Gain Filter")
+    }
+    fn author() -> &'static CStr {
+        cstr!("Bennett Hardwick")
+    }
+}
+
+obs_register_module!(AudioGainFilter);
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use obs_wrapper::testing::{MockPluginContext, MockSettings};
+
+    #[test]
+    fn update_applies_gain() {
+        let settings = MockSettings::new().with("gain", 6.0);
+        let mut mock = MockPluginContext::new(settings, Data { multiplier: 1.0 });
+
+        AudioGainFilter::update(mock.context());
+        assert!((mock.data().multiplier - db_to_mul(6.0)).abs() < 1e-6);
+
+        mock.settings_mut().set("gain", -12.0);
+        AudioGainFilter::update(mock.context());
+        assert!((mock.data().multiplier - db_to_mul(-12.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn defaults_are_used_for_missing_settings() {
+        let mut settings = MockSettings::new().into_settings();
+
+        AudioGainFilter::get_defaults(&mut settings);
+
+        let gain = settings.mock_mut().and_then(|mock| mock.get("gain")).and_then(|value| value.as_f64());
+        assert_eq!(gain, Some(0.0));
+
+        let data = Data::from_settings(&mut settings);
+        assert!((data.multiplier - 1.0).abs() < 1e-6);
+    }
+}
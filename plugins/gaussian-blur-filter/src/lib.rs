@@ -0,0 +1,185 @@
+use obs_wrapper::{graphics::*, obs_register_module, prelude::*, source::*};
+use std::ffi::{CStr, CString};
+
+#[derive(ObsProperties)]
+struct Settings {
+    #[obs(description = "Size", min = 0.5, max = 8.0, step = 0.1, slider)]
+    size: f64,
+    #[obs(description = "Passes", min = 1, max = 16, slider)]
+    passes: i32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self { size: 1.0, passes: 2 }
+    }
+}
+
+struct Data {
+    source: SourceContext,
+    effect: GraphicsContextDependentDisabled<GraphicsEffect>,
+    texel_step: GraphicsContextDependentDisabled<GraphicsEffectParamTyped<ShaderParamTypeVec2>>,
+    multi_pass: GraphicsContextDependentDisabled<MultiPassFilter>,
+
+    size: f32,
+    passes: usize,
+}
+
+impl Data {
+    fn apply_settings(&mut self, settings: &mut SettingsContext) {
+        let settings = Settings::from_settings(settings);
+
+        self.size = settings.size as f32;
+        self.passes = settings.passes.max(1) as usize;
+    }
+}
+
+struct GaussianBlurFilter {
+    context: ModuleContext,
+}
+
+impl Sourceable for GaussianBlurFilter {
+    fn get_id() -> &'static CStr {
+        cstr!("rust_gaussian_blur_filter")
+    }
+    fn get_type() -> SourceType {
+        SourceType::FILTER
+    }
+}
+
+impl GetNameSource<Data> for GaussianBlurFilter {
+    fn get_name() -> &'static CStr {
+        cstr!("Rust Gaussian Blur")
+    }
+}
+
+impl GetPropertiesSource<Data> for GaussianBlurFilter {
+    fn get_properties(_context: PluginContext<Data>) -> Properties {
+        Settings::properties()
+    }
+}
+
+impl GetDefaultsSource<Data> for GaussianBlurFilter {
+    fn get_defaults(settings: &mut SettingsContext) {
+        Settings::set_defaults(settings);
+    }
+}
+
+impl CreatableSource<Data> for GaussianBlurFilter {
+    fn create(settings: &mut SettingsContext, source: SourceContext) -> Data {
+        let graphics_context = GraphicsContext::try_enter()
+            .unwrap_or_else(|error| panic!("Could not create the Gaussian blur filter: {}", error));
+        let effect_string = CString::new(include_str!("./blur.effect")).unwrap();
+        let effect = match GraphicsEffect::from_effect_string(
+            effect_string.as_c_str(),
+            cstr!("blur.effect"),
+            &graphics_context,
+        ) {
+            Ok(effect) => effect,
+            Err(error) => panic!("Could not load the blur effect: {:?}", error),
+        };
+
+        let texel_step = effect
+            .require_param::<ShaderParamTypeVec2>(cstr!("texel_step"))
+            .unwrap_or_else(|error| panic!("{}", error));
+        let multi_pass = MultiPassFilter::new(ColorFormatKind::RGBA, &graphics_context)
+            .expect("Could not create the intermediate textures of the blur.");
+
+        let mut data = Data {
+            source,
+            effect: effect.disable(),
+            texel_step: texel_step.disable(),
+            multi_pass: multi_pass.disable(),
+
+            size: 1.0,
+            passes: 2,
+        };
+
+        data.apply_settings(settings);
+        data
+    }
+}
+
+impl UpdateSource<Data> for GaussianBlurFilter {
+    fn update(mut context: PluginContext<Data>) {
+        let (data, settings) = context.data_settings_mut();
+
+        if let Some(data) = data {
+            data.apply_settings(settings);
+        }
+    }
+}
+
+impl VideoRenderSource<Data> for GaussianBlurFilter {
+    fn video_render(mut context: PluginContext<Data>, graphics_context: &mut GraphicsContext) {
+        if let Some(data) = context.data_mut() {
+            let mut width = 0;
+            let mut height = 0;
+
+            data.source.do_with_target(|target| {
+                width = target.get_base_width();
+                height = target.get_base_height();
+            });
+
+            if width == 0 || height == 0 {
+                data.source.skip_video_filter();
+                return;
+            }
+
+            let size = data.size;
+            let passes = data.passes;
+            let mut effect = data.effect.as_enabled_mut(graphics_context);
+            let mut texel_step = data.texel_step.as_enabled_mut(graphics_context);
+            let mut multi_pass = data.multi_pass.as_enabled_mut(graphics_context);
+
+            // Even passes blur horizontally and odd passes vertically, so an odd number of
+            // passes blurs horizontally once more than vertically.
+            multi_pass.render(&mut data.source, &mut effect, passes, [width, height], |pass, context, _effect| {
+                let step = if pass % 2 == 0 {
+                    [size / width as f32, 0.0]
+                } else {
+                    [0.0, size / height as f32]
+                };
+
+                texel_step.set_param_value(&step, context);
+            });
+        }
+    }
+}
+
+impl Module for GaussianBlurFilter {
+    fn new(context: ModuleContext) -> Self {
+        Self { context }
+    }
+    fn get_ctx(&self) -> &ModuleContext {
+        &self.context
+    }
+
+    fn load(&mut self, load_context: &mut LoadContext) -> bool {
+        let source = load_context
+            .create_source_builder::<GaussianBlurFilter, Data>()
+            .enable_get_name()
+            .enable_get_properties()
+            .enable_get_defaults()
+            .enable_create()
+            .enable_update()
+            .enable_video_render()
+            .build();
+
+        load_context.register_source(source);
+
+        true
+    }
+
+    fn description() -> &'static CStr {
+        cstr!("A filter blurring a source, by applying a separable Gaussian blur in multiple passes.")
+    }
+    fn name() -> &'static CStr {
+        cstr!("Gaussian Blur Filter")
+    }
+    fn author() -> &'static CStr {
+        cstr!("Bennett Hardwick")
+    }
+}
+
+obs_register_module!(GaussianBlurFilter);
@@ -1,7 +1,7 @@
 mod server;
 
 use server::{Server, WindowSnapshot};
-use obs_wrapper::{context::*, graphics::*, obs_register_module, prelude::*, source::*};
+use obs_wrapper::{graphics::*, obs_register_module, prelude::*, source::*};
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use std::ffi::{CStr, CString};
 
@@ -20,15 +20,46 @@ const DEFAULT_SCREEN_WIDTH: i32 = 1920;
 const DEFAULT_SCREEN_HEIGHT: i32 = 1080;
 const DEFAULT_ANIMATION_TIME: f64 = 0.3;
 
+#[derive(ObsProperties)]
+struct Settings {
+    #[obs(description = "Amount to zoom in window", min = 1.0, max = 5.0, step = 0.001, slider)]
+    zoom: f64,
+    #[obs(description = "Offset relative to top left screen - x", min = 0, max = 3840 * 3)]
+    screen_x: i32,
+    #[obs(description = "Offset relative to top left screen - y", min = 0, max = 3840 * 3)]
+    screen_y: i32,
+    #[obs(description = "Screen width", min = 1, max = 3840 * 3)]
+    screen_width: i32,
+    #[obs(description = "Screen height", min = 1, max = 3840 * 3)]
+    screen_height: i32,
+    #[obs(description = "Animation Time (s)", min = 0.3, max = 10., step = 0.001)]
+    animation_time: f64,
+    #[obs(description = "Bypass")]
+    bypass: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            zoom: DEFAULT_ZOOM,
+            screen_x: DEFAULT_SCREEN_X,
+            screen_y: DEFAULT_SCREEN_Y,
+            screen_width: DEFAULT_SCREEN_WIDTH,
+            screen_height: DEFAULT_SCREEN_HEIGHT,
+            animation_time: DEFAULT_ANIMATION_TIME,
+            bypass: false,
+        }
+    }
+}
+
 struct Data {
     source: SourceContext,
     effect: GraphicsContextDependentDisabled<GraphicsEffect>,
 
-    mul_val: GraphicsContextDependentDisabled<GraphicsEffectParamTyped<ShaderParamTypeVec2>>,
-    add_val: GraphicsContextDependentDisabled<GraphicsEffectParamTyped<ShaderParamTypeVec2>>,
+    transform: GraphicsContextDependentDisabled<AffineTransformParams>,
     image: GraphicsContextDependentDisabled<GraphicsEffectParamTyped<ShaderParamTypeTexture>>,
 
-    sampler: GraphicsContextDependentDisabled<GraphicsSamplerState>,
+    sampler: GraphicsContextDependentDisabled<SharedGraphicsSamplerState>,
 
     send: Sender<FilterMessage>,
     receive: Receiver<ServerMessage>,
@@ -51,12 +82,7 @@ struct Data {
     screen_x: u32,
     screen_y: u32,
 
-    property_zoom: PropertyDescriptor<PropertyDescriptorSpecializationF64>,
-    property_screen_x: PropertyDescriptor<PropertyDescriptorSpecializationI32>,
-    property_screen_y: PropertyDescriptor<PropertyDescriptorSpecializationI32>,
-    property_screen_width: PropertyDescriptor<PropertyDescriptorSpecializationI32>,
-    property_screen_height: PropertyDescriptor<PropertyDescriptorSpecializationI32>,
-    property_animation_time: PropertyDescriptor<PropertyDescriptorSpecializationF64>,
+    bypass: bool,
 }
 
 impl Drop for Data {
@@ -85,18 +111,8 @@ impl GetNameSource<Data> for ScrollFocusFilter {
 }
 
 impl GetPropertiesSource<Data> for ScrollFocusFilter {
-    fn get_properties(context: PluginContext<Data>) -> Properties {
-        let data = context.data().as_ref().unwrap();
-        let mut properties = Properties::new();
-
-        properties.add_property(&data.property_zoom);
-        properties.add_property(&data.property_screen_x);
-        properties.add_property(&data.property_screen_y);
-        properties.add_property(&data.property_screen_width);
-        properties.add_property(&data.property_screen_height);
-        properties.add_property(&data.property_animation_time);
-
-        properties
+    fn get_properties(_context: PluginContext<Data>) -> Properties {
+        Settings::properties()
     }
 }
 
@@ -186,16 +202,19 @@ impl VideoRenderSource<Data> for ScrollFocusFilter {
         graphics_context: &mut GraphicsContext,
     ) {
         if let Some(data) = context.data_mut() {
+            if data.bypass {
+                data.source.skip_video_filter();
+                return;
+            }
+
             let effect = &mut data.effect.as_enabled_mut(graphics_context);
             let source = &mut data.source;
-            let param_add = &mut data.add_val.as_enabled_mut(graphics_context);
-            let param_mul = &mut data.mul_val.as_enabled_mut(graphics_context);
+            let transform_params = &mut data.transform.as_enabled_mut(graphics_context);
             let image = &mut data.image.as_enabled_mut(graphics_context);
-            let sampler = &mut data.sampler.as_enabled_mut(graphics_context);
-
-            let current = &mut data.current;
+            let sampler = &data.sampler.as_enabled(graphics_context);
 
             let zoom = data.current_zoom as f32;
+            let transform = AffineTransform2D::new(data.current, [zoom, zoom]);
 
             let mut cx: u32 = 1;
             let mut cy: u32 = 1;
@@ -211,8 +230,7 @@ impl VideoRenderSource<Data> for ScrollFocusFilter {
                 ColorFormatKind::RGBA,
                 GraphicsAllowDirectRendering::NoDirectRendering,
                 |context, _effect| {
-                    param_add.set_param_value(current, &context);
-                    param_mul.set_param_value(&[zoom, zoom], &context);
+                    transform.apply_to(transform_params, context);
                     image.set_next_sampler(context, sampler);
                 },
             );
@@ -222,32 +240,26 @@ impl VideoRenderSource<Data> for ScrollFocusFilter {
 
 impl CreatableSource<Data> for ScrollFocusFilter {
     fn create(settings: &mut SettingsContext, mut source: SourceContext) -> Data {
-        let graphics_context = GraphicsContext::enter()
-            .expect("Could not enter the graphics context during the creation of the plugin.");
+        let graphics_context = GraphicsContext::try_enter()
+            .unwrap_or_else(|error| panic!("Could not create the scroll focus filter: {}", error));
         let effect_string = CString::new(include_str!("./crop_filter.effect")).unwrap();
-        let effect = if let Some(effect) = GraphicsEffect::from_effect_string(
+        let effect = match GraphicsEffect::from_effect_string(
             effect_string.as_c_str(),
             cstr!("crop_filter.effect"),
             &graphics_context,
         ) {
-            effect
-        } else {
-            panic!("Could not load crop filter effect!");
+            Ok(effect) => effect,
+            Err(error) => panic!("Could not load crop filter effect: {:?}", error),
         };
 
-        let param_image = effect.get_param_by_name(cstr!("image"));
-        let param_add_val = effect.get_param_by_name(cstr!("add_val"));
-        let param_mul_val = effect.get_param_by_name(cstr!("mul_val"));
-
-        if param_image.is_none() || param_add_val.is_none() || param_mul_val.is_none() {
-            panic!("Failed to find correct effect params!");
-        }
-
-        let param_image = param_image.unwrap().downcast::<ShaderParamTypeTexture>().unwrap();
-        let param_add_val = param_add_val.unwrap().downcast::<ShaderParamTypeVec2>().unwrap();
-        let param_mul_val = param_mul_val.unwrap().downcast::<ShaderParamTypeVec2>().unwrap();
+        let param_image = effect
+            .require_param::<ShaderParamTypeTexture>(cstr!("image"))
+            .unwrap_or_else(|error| panic!("{}", error));
+        let transform = AffineTransformParams::from_effect(&effect)
+            .unwrap_or_else(|error| panic!("{}", error));
 
-        let sampler = GraphicsContextDependentEnabled::<GraphicsSamplerState>::from(GraphicsSamplerInfo::new(&graphics_context));
+        let sampler = SamplerCache::global()
+            .get(&GraphicsSamplerInfo::linear_clamp(&graphics_context), &graphics_context);
 
         let (send_filter, receive_filter) = unbounded::<FilterMessage>();
         let (send_server, receive_server) = unbounded::<ServerMessage>();
@@ -277,8 +289,7 @@ impl CreatableSource<Data> for ScrollFocusFilter {
         Data {
             source,
             effect: effect.disable(),
-            add_val: param_add_val.disable(),
-            mul_val: param_mul_val.disable(),
+            transform: transform.disable(),
             image: param_image.disable(),
 
             sampler: sampler.disable(),
@@ -304,66 +315,7 @@ impl CreatableSource<Data> for ScrollFocusFilter {
             screen_x: DEFAULT_SCREEN_X as u32,
             screen_y: DEFAULT_SCREEN_Y as u32,
 
-            property_zoom: PropertyDescriptor {
-                name: CString::new("zoom").unwrap(),
-                description: CString::new("Amount to zoom in window").unwrap(),
-                specialization: PropertyDescriptorSpecializationF64 {
-                    min: 1.0,
-                    max: 5.0,
-                    step: 0.001,
-                    slider: true,
-                },
-            },
-            property_screen_x: PropertyDescriptor {
-                name: CString::new("screen_x").unwrap(),
-                description: CString::new("Offset relative to top left screen - x").unwrap(),
-                specialization: PropertyDescriptorSpecializationI32 {
-                    min: 0,
-                    max: 3840 * 3,
-                    step: 1,
-                    slider: false,
-                },
-            },
-            property_screen_y: PropertyDescriptor {
-                name: CString::new("screen_y").unwrap(),
-                description: CString::new("Offset relative to top left screen - y").unwrap(),
-                specialization: PropertyDescriptorSpecializationI32 {
-                    min: 0,
-                    max: 3840 * 3,
-                    step: 1,
-                    slider: false,
-                },
-            },
-            property_screen_width: PropertyDescriptor {
-                name: CString::new("screen_width").unwrap(),
-                description: CString::new("Screen width").unwrap(),
-                specialization: PropertyDescriptorSpecializationI32 {
-                    min: 1,
-                    max: 3840 * 3,
-                    step: 1,
-                    slider: false,
-                },
-            },
-            property_screen_height: PropertyDescriptor {
-                name: CString::new("screen_height").unwrap(),
-                description: CString::new("Screen height").unwrap(),
-                specialization: PropertyDescriptorSpecializationI32 {
-                    min: 1,
-                    max: 3840 * 3,
-                    step: 1,
-                    slider: false,
-                },
-            },
-            property_animation_time: PropertyDescriptor {
-                name: CString::new("animation_time").unwrap(),
-                description: CString::new("Animation Time (s)").unwrap(),
-                specialization: PropertyDescriptorSpecializationF64 {
-                    min: 0.3,
-                    max: 10.,
-                    step: 0.001,
-                    slider: false,
-                },
-            },
+            bypass: false,
         }
     }
 }
@@ -375,24 +327,19 @@ impl UpdateSource<Data> for ScrollFocusFilter {
         let (data, settings) = context.data_settings_mut();
 
         if let Some(data) = data {
-            let zoom = settings.get_property_value(&data.property_zoom, &DEFAULT_ZOOM);
-            data.from_zoom = data.current_zoom;
-            data.internal_zoom = 1. / zoom;
-            data.target_zoom = 1. / zoom;
+            let settings = Settings::from_settings(settings);
 
-            let screen_width = settings.get_property_value(&data.property_screen_width, &DEFAULT_SCREEN_WIDTH);
-            data.screen_width = screen_width as u32;
-
-            let screen_height = settings.get_property_value(&data.property_screen_height, &DEFAULT_SCREEN_HEIGHT);
-            data.screen_height = screen_height as u32;
-
-            let screen_x = settings.get_property_value(&data.property_screen_x, &DEFAULT_SCREEN_X);
-            data.screen_x = screen_x as u32;
+            data.from_zoom = data.current_zoom;
+            data.internal_zoom = 1. / settings.zoom;
+            data.target_zoom = 1. / settings.zoom;
 
-            let screen_y = settings.get_property_value(&data.property_screen_y, &DEFAULT_SCREEN_Y);
-            data.screen_y = screen_y as u32;
+            data.screen_width = settings.screen_width as u32;
+            data.screen_height = settings.screen_height as u32;
+            data.screen_x = settings.screen_x as u32;
+            data.screen_y = settings.screen_y as u32;
 
-            data.animation_time = settings.get_property_value(&data.property_animation_time, &DEFAULT_ANIMATION_TIME);
+            data.animation_time = settings.animation_time;
+            data.bypass = settings.bypass;
         }
     }
 }
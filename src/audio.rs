@@ -21,14 +21,52 @@ use obs_sys::{
     speaker_layout_SPEAKERS_4POINT1,
     speaker_layout_SPEAKERS_5POINT1,
     speaker_layout_SPEAKERS_7POINT1,
+    MAX_AUDIO_MIXES, MAX_AUDIO_CHANNELS,
+    obs_fader_type, obs_fader_type_OBS_FADER_CUBIC, obs_fader_type_OBS_FADER_IEC,
+    obs_fader_type_OBS_FADER_LOG, obs_peak_meter_type, obs_peak_meter_type_SAMPLE_PEAK_METER,
+    obs_peak_meter_type_TRUE_PEAK_METER, obs_volmeter_t, obs_volmeter_create,
+    obs_volmeter_destroy, obs_volmeter_attach_source, obs_volmeter_detach_source,
+    obs_volmeter_set_peak_meter_type, obs_volmeter_set_update_interval,
+    obs_volmeter_get_update_interval, obs_volmeter_get_nr_channels, obs_volmeter_add_callback,
+    obs_volmeter_remove_callback, obs_fader_t, obs_fader_create, obs_fader_destroy,
+    obs_fader_attach_source, obs_fader_detach_source, obs_fader_set_db, obs_fader_get_db,
+    obs_fader_set_deflection, obs_fader_get_deflection, obs_fader_set_mul, obs_fader_get_mul,
+    obs_fader_add_callback, obs_fader_remove_callback, obs_audio_data, MAX_AV_PLANES,
+    obs_source_audio_mix, AUDIO_OUTPUT_FRAMES,
 };
+use crate::source::SourceContext;
 use std::ptr::null_mut;
 use std::os::raw::c_void;
 use std::ffi::CStr;
+use std::sync::Arc;
 use crate::util::*;
 
 type size_t = ::std::os::raw::c_ulong;
 
+/// Converts decibels to a linear volume multiplier, as used by OBS.
+///
+/// Mirrors `obs_db_to_mul`: `0.0` dB maps to `1.0`, and non-finite values
+/// (such as `f32::NEG_INFINITY`) map to silence, `0.0`.
+pub fn db_to_mul(db: f32) -> f32 {
+    if db.is_finite() {
+        10.0f32.powf(db / 20.0)
+    } else {
+        0.0
+    }
+}
+
+/// Converts a linear volume multiplier to decibels.
+///
+/// Mirrors `obs_mul_to_db`: `1.0` maps to `0.0` dB, and silence (`0.0`) maps to
+/// `f32::NEG_INFINITY`.
+pub fn mul_to_db(mul: f32) -> f32 {
+    if mul == 0.0 {
+        std::f32::NEG_INFINITY
+    } else {
+        20.0 * mul.log10()
+    }
+}
+
 pub struct AudioOutput {
     mix_index: usize,
     callback_ptr: *mut AudioOutputCallback,
@@ -52,6 +90,200 @@ impl Drop for AudioOutput {
     }
 }
 
+/// The connections to all mix tracks, created by `Audio::connect_all_outputs`. Dropping it
+/// disconnects every track.
+pub struct AudioOutputs {
+    outputs: Vec<AudioOutput>,
+}
+
+impl AudioOutputs {
+    /// The number of connected mix tracks.
+    pub fn len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+}
+
+/// A block of audio passing through an audio filter, see `FilterAudioSource`.
+///
+/// OBS always passes audio to filters as 32-bit floating point samples, with one plane per
+/// channel, so the samples of each channel can be modified in place:
+///
+/// ```rs
+/// for channel in 0..audio.channels() {
+///     if let Some(samples) = audio.channel_mut(channel) {
+///         samples.iter_mut().for_each(|sample| *sample *= gain);
+///     }
+/// }
+/// ```
+pub struct FilterAudioData<'a> {
+    inner: &'a mut obs_audio_data,
+    channels: usize,
+}
+
+impl<'a> FilterAudioData<'a> {
+    /// # Safety
+    /// The pointer must be a valid pointer to the audio data passed to the `filter_audio`
+    /// callback of a source.
+    pub(crate) unsafe fn from_raw(raw: *mut obs_audio_data) -> Self {
+        Self {
+            inner: &mut *raw,
+            channels: Audio::get().get_output_channels().min(MAX_AV_PLANES as usize),
+        }
+    }
+
+    /// The number of samples per channel.
+    pub fn frames(&self) -> usize {
+        self.inner.frames as usize
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.inner.timestamp
+    }
+
+    /// The number of channels, as configured in the audio settings of OBS.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    pub fn channel(&self, channel: usize) -> Option<&[f32]> {
+        if channel >= self.channels || self.inner.data[channel].is_null() {
+            return None;
+        }
+
+        unsafe {
+            Some(std::slice::from_raw_parts(self.inner.data[channel] as *const f32, self.frames()))
+        }
+    }
+
+    pub fn channel_mut(&mut self, channel: usize) -> Option<&mut [f32]> {
+        if channel >= self.channels || self.inner.data[channel].is_null() {
+            return None;
+        }
+
+        unsafe {
+            Some(std::slice::from_raw_parts_mut(self.inner.data[channel] as *mut f32, self.frames()))
+        }
+    }
+}
+
+/// The audio requested from a source by `AudioRenderSource::audio_render`, to be written to
+/// the output buffers of the requested mixes.
+///
+/// OBS only requests the mixes the source is routed to, see `wants`. The buffers of the
+/// requested mixes are silent when the callback is called, so a source only needs to write to
+/// the channels it produces audio for. The buffers of mixes which have not been requested are
+/// not accessible, so that a source cannot route audio to tracks it has not been assigned to.
+///
+/// ```rs
+/// impl AudioRenderSource<Data> for ToneSource {
+///     fn audio_render(mut context: PluginContext<Data>, request: &mut AudioMixRequest) -> bool {
+///         let data = context.data_mut().as_mut().unwrap();
+///
+///         for mix in 0..obs_sys::MAX_AUDIO_MIXES as usize {
+///             for channel in 0..request.channels() {
+///                 if let Some(samples) = request.channel_mut(mix, channel) {
+///                     data.tone.fill(samples);
+///                 }
+///             }
+///         }
+///
+///         request.set_timestamp(data.next_timestamp)
+///     }
+/// }
+/// ```
+pub struct AudioMixRequest<'a> {
+    mix: &'a mut obs_source_audio_mix,
+    timestamp: &'a mut u64,
+    timestamp_set: bool,
+    mixers: u32,
+    channels: usize,
+    sample_rate: usize,
+}
+
+impl<'a> AudioMixRequest<'a> {
+    /// # Safety
+    /// The pointers must be the valid pointers passed to the `audio_render` callback of
+    /// a source.
+    pub(crate) unsafe fn from_raw(
+        timestamp: *mut u64,
+        mix: *mut obs_source_audio_mix,
+        mixers: u32,
+        channels: usize,
+        sample_rate: usize,
+    ) -> Self {
+        Self {
+            mix: &mut *mix,
+            timestamp: &mut *timestamp,
+            timestamp_set: false,
+            mixers,
+            channels: channels.min(MAX_AUDIO_CHANNELS as usize),
+            sample_rate,
+        }
+    }
+
+    /// The bitmask of the requested mixes, where bit `n` stands for the mix with index `n`.
+    pub fn mixers(&self) -> u32 {
+        self.mixers
+    }
+
+    /// Whether the mix with the index `mix` is requested.
+    pub fn wants(&self, mix: usize) -> bool {
+        mix < MAX_AUDIO_MIXES as usize && self.mixers & (1 << mix) != 0
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    pub fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+
+    /// The number of samples per channel to be written.
+    pub fn frames(&self) -> usize {
+        AUDIO_OUTPUT_FRAMES as usize
+    }
+
+    /// The output buffer of the `channel` of the `mix`, or `None` if the mix has not been
+    /// requested.
+    pub fn channel_mut(&mut self, mix: usize, channel: usize) -> Option<&mut [f32]> {
+        if !self.wants(mix) || channel >= self.channels {
+            return None;
+        }
+
+        let data = self.mix.output[mix].data[channel];
+
+        if data.is_null() {
+            None
+        } else {
+            Some(unsafe { std::slice::from_raw_parts_mut(data, self.frames()) })
+        }
+    }
+
+    /// Sets the timestamp of the first sample of the rendered audio, in nanoseconds, see
+    /// `util::time::now_ns`, and returns whether it is valid.
+    ///
+    /// OBS discards the audio of a source with a timestamp of 0, so such timestamps are
+    /// rejected. The audio is only output if a valid timestamp has been set.
+    pub fn set_timestamp(&mut self, timestamp: u64) -> bool {
+        if timestamp == 0 {
+            return false;
+        }
+
+        *self.timestamp = timestamp;
+        self.timestamp_set = true;
+        true
+    }
+
+    pub(crate) fn has_timestamp(&self) -> bool {
+        self.timestamp_set
+    }
+}
+
 pub struct SampleIterator<'a, T: AudioFormat> {
     audio_data: AudioData<'a, T>,
     next_frame: usize,
@@ -139,6 +371,36 @@ impl<'a, T: AudioFormat> AudioData<'a, T> {
             samples.map(|sample| <T as AudioFormat>::normalize_sample(sample))
         })
     }
+
+    /// Returns the samples of a channel as a contiguous slice, avoiding the overhead of
+    /// iterating over them one by one.
+    ///
+    /// Only planar formats store the samples of a channel contiguously, so `None` is returned
+    /// for interleaved formats -- use `samples` instead. As with `samples`, `None` is also
+    /// returned for channels out of the bounds of the speaker layout.
+    pub fn samples_slice(&self, channel: usize) -> Option<&[T::SampleType]> {
+        let info = &self.info;
+
+        if !info.format().is_planar()
+            || info.get_sample_stride() != std::mem::size_of::<T::SampleType>()
+            || channel >= info.speaker_layout().get_channel_count() {
+            return None;
+        }
+
+        unsafe {
+            let inner = &*self.inner;
+            let plane_data = inner.data[channel];
+
+            if plane_data.is_null() {
+                None
+            } else {
+                Some(std::slice::from_raw_parts(
+                    plane_data as *const T::SampleType,
+                    self.frames() as usize,
+                ))
+            }
+        }
+    }
 }
 
 /// A shared reference to audio data.
@@ -256,15 +518,27 @@ impl<'a> AudioData<'a, ()> {
 macro_rules! define_audio_format_types {
     {
         $(
-            $binding:ident, $name:ident, $interleaved:expr, $sample_type:ty, { $($convert:tt)* }
+            $binding:ident, $name:ident, $interleaved:expr, $sample_type:ty, { $($normalize:tt)* }, { $($denormalize:tt)* }
         );*$(;)?
     } => {
         pub trait AudioFormat: 'static {
             type SampleType: Copy;
             const KIND: AudioFormatKind;
 
-            /// Converts the sample to a normalized range 
+            /// Converts the sample to the normalized range from `-1.0` to `1.0`. The minimum and
+            /// the maximum of integer formats map to `-1.0` and `1.0` exactly.
             fn normalize_sample(sample: Self::SampleType) -> f32;
+
+            /// Converts the normalized sample back to the format, the inverse of
+            /// `normalize_sample`, e.g. to output processed audio. Integer formats clamp the
+            /// sample to the normalized range and round it to the nearest value.
+            fn denormalize_sample(sample: f32) -> Self::SampleType;
+
+            /// Converts the normalized samples of a channel, e.g. generated by an audio source,
+            /// to the format, see `denormalize_sample`.
+            fn denormalize_samples(samples: &[f32]) -> Vec<Self::SampleType> {
+                samples.iter().map(|sample| Self::denormalize_sample(*sample)).collect()
+            }
         }
 
         $(
@@ -277,7 +551,12 @@ macro_rules! define_audio_format_types {
 
                     #[inline(always)]
                     fn normalize_sample(sample: Self::SampleType) -> f32 {
-                        ($($convert)*)(sample)
+                        ($($normalize)*)(sample)
+                    }
+
+                    #[inline(always)]
+                    fn denormalize_sample(sample: f32) -> Self::SampleType {
+                        ($($denormalize)*)(sample)
                     }
                 }
             }
@@ -327,6 +606,33 @@ macro_rules! define_audio_format_types {
                 }
             }
 
+            /// Converts the normalized samples to the bytes of the format, for when the format
+            /// is only known at runtime, e.g. from `AudioOutputInfo::format`. Returns an empty
+            /// `Vec` for `Unknown`.
+            ///
+            /// The samples are written one after another, as in a single plane of a planar
+            /// format, in the native byte order expected by OBS.
+            pub fn denormalize_samples_to_bytes(self, samples: &[f32]) -> Vec<u8> {
+                use AudioFormatKind::*;
+
+                let mut bytes = Vec::with_capacity(samples.len() * self.get_bytes_per_sample());
+
+                match self {
+                    Unknown => {}
+                    $(
+                        $name => paste::expr! {
+                            for sample in samples {
+                                let sample = <[< AudioFormat $name >] as AudioFormat>::denormalize_sample(*sample);
+
+                                bytes.extend_from_slice(&sample.to_ne_bytes());
+                            }
+                        },
+                    )*
+                }
+
+                bytes
+            }
+
             pub fn into_raw(self) -> audio_format {
                 use AudioFormatKind::*;
 
@@ -341,16 +647,55 @@ macro_rules! define_audio_format_types {
     }
 }
 
-// TODO: Check these sample conversions. There might be off-by-one errors.
+/// Normalizes a signed integer sample, scaling the positive and the negative values separately,
+/// so that both the minimum and the maximum of the format map exactly to `-1.0` and `1.0`.
+#[inline(always)]
+fn normalize_signed(sample: f64, max: f64, min_magnitude: f64) -> f32 {
+    if sample >= 0.0 {
+        (sample / max) as f32
+    } else {
+        (sample / min_magnitude) as f32
+    }
+}
+
+/// The inverse of `normalize_signed`, clamping the sample to the normalized range.
+#[inline(always)]
+fn denormalize_signed(sample: f32, max: f64, min_magnitude: f64) -> f64 {
+    let sample = (sample as f64).clamp(-1.0, 1.0);
+
+    if sample >= 0.0 {
+        (sample * max).round()
+    } else {
+        (sample * min_magnitude).round()
+    }
+}
+
+// Unsigned 8-bit samples are centered on 128, like in FFmpeg.
 define_audio_format_types! {
-    audio_format_AUDIO_FORMAT_U8BIT,        InterleavedU8,  false, u8,  { |sample| (sample as i16 - (std::u8::MAX / 2) as i16) as f32 / (std::u8::MAX / 2) as f32 };
-    audio_format_AUDIO_FORMAT_16BIT,        InterleavedI16, false, i16, { |sample| (sample as f32 / std::i16::MAX as f32) };
-    audio_format_AUDIO_FORMAT_32BIT,        InterleavedI32, false, i32, { |sample| (sample as f64 / std::i32::MAX as f64) as f32 };
-    audio_format_AUDIO_FORMAT_FLOAT,        InterleavedF32, false, f32, { |sample| sample };
-    audio_format_AUDIO_FORMAT_U8BIT_PLANAR, PlanarU8,       true,  u8,  { |sample| (sample as i16 - (std::u8::MAX / 2) as i16) as f32 / (std::u8::MAX / 2) as f32 };
-    audio_format_AUDIO_FORMAT_16BIT_PLANAR, PlanarI16,      true,  i16, { |sample| (sample as f32 / std::i16::MAX as f32) };
-    audio_format_AUDIO_FORMAT_32BIT_PLANAR, PlanarI32,      true,  i32, { |sample| (sample as f64 / std::i32::MAX as f64) as f32 };
-    audio_format_AUDIO_FORMAT_FLOAT_PLANAR, PlanarF32,      true,  f32, { |sample| sample };
+    audio_format_AUDIO_FORMAT_U8BIT,        InterleavedU8,  false, u8,
+        { |sample| normalize_signed(sample as f64 - 128.0, 127.0, 128.0) },
+        { |sample| (denormalize_signed(sample, 127.0, 128.0) + 128.0) as u8 };
+    audio_format_AUDIO_FORMAT_16BIT,        InterleavedI16, false, i16,
+        { |sample| normalize_signed(sample as f64, std::i16::MAX as f64, -(std::i16::MIN as f64)) },
+        { |sample| denormalize_signed(sample, std::i16::MAX as f64, -(std::i16::MIN as f64)) as i16 };
+    audio_format_AUDIO_FORMAT_32BIT,        InterleavedI32, false, i32,
+        { |sample| normalize_signed(sample as f64, std::i32::MAX as f64, -(std::i32::MIN as f64)) },
+        { |sample| denormalize_signed(sample, std::i32::MAX as f64, -(std::i32::MIN as f64)) as i32 };
+    audio_format_AUDIO_FORMAT_FLOAT,        InterleavedF32, false, f32,
+        { |sample| sample },
+        { |sample| sample };
+    audio_format_AUDIO_FORMAT_U8BIT_PLANAR, PlanarU8,       true,  u8,
+        { |sample| normalize_signed(sample as f64 - 128.0, 127.0, 128.0) },
+        { |sample| (denormalize_signed(sample, 127.0, 128.0) + 128.0) as u8 };
+    audio_format_AUDIO_FORMAT_16BIT_PLANAR, PlanarI16,      true,  i16,
+        { |sample| normalize_signed(sample as f64, std::i16::MAX as f64, -(std::i16::MIN as f64)) },
+        { |sample| denormalize_signed(sample, std::i16::MAX as f64, -(std::i16::MIN as f64)) as i16 };
+    audio_format_AUDIO_FORMAT_32BIT_PLANAR, PlanarI32,      true,  i32,
+        { |sample| normalize_signed(sample as f64, std::i32::MAX as f64, -(std::i32::MIN as f64)) },
+        { |sample| denormalize_signed(sample, std::i32::MAX as f64, -(std::i32::MIN as f64)) as i32 };
+    audio_format_AUDIO_FORMAT_FLOAT_PLANAR, PlanarF32,      true,  f32,
+        { |sample| sample },
+        { |sample| sample };
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -414,41 +759,44 @@ impl SpeakerLayoutKind {
     }
 }
 
+/// The characteristics of audio, either of the global audio output, see
+/// `Audio::get_output_info`, or of a single source, see `SourceContext::get_audio_output_info`.
 pub struct AudioOutputInfo {
-    inner: *const audio_output_info,
+    inner: audio_output_info,
 }
 
 impl AudioOutputInfo {
+    pub(crate) unsafe fn from_raw(raw: *const audio_output_info) -> Self {
+        Self { inner: *raw }
+    }
+
+    /// Replaces the speaker layout, keeping the rest of the characteristics.
+    pub(crate) fn with_speaker_layout(mut self, speaker_layout: SpeakerLayoutKind) -> Self {
+        self.inner.speakers = speaker_layout.into_raw();
+        self
+    }
+
     pub fn name(&self) -> &CStr {
         unsafe {
-            let inner = &*self.inner;
-
-            CStr::from_ptr(inner.name)
+            CStr::from_ptr(self.inner.name)
         }
     }
 
     pub fn samples_per_sec(&self) -> u32 {
-        unsafe {
-            let inner = &*self.inner;
-
-            inner.samples_per_sec
-        }
+        self.inner.samples_per_sec
     }
 
     pub fn format(&self) -> AudioFormatKind {
-        unsafe {
-            let inner = &*self.inner;
-
-            AudioFormatKind::from_raw(inner.format)
-        }
+        AudioFormatKind::from_raw(self.inner.format)
     }
 
     pub fn speaker_layout(&self) -> SpeakerLayoutKind {
-        unsafe {
-            let inner = &*self.inner;
+        SpeakerLayoutKind::from_raw(self.inner.speakers)
+    }
 
-            SpeakerLayoutKind::from_raw(inner.format)
-        }
+    /// The number of channels of the speaker layout.
+    pub fn channels(&self) -> usize {
+        self.speaker_layout().get_channel_count()
     }
 
     /// The number of planes in a block
@@ -506,11 +854,27 @@ impl Audio {
         }
     }
 
+    /// Connects the `callback` to all mix tracks, passing it the index of the track each block
+    /// of audio data comes from. OBS only produces audio data for the tracks which are active.
+    pub fn connect_all_outputs<F>(&self, callback: F) -> AudioOutputs
+    where
+        F: Fn(usize, AudioData<()>) + 'static,
+    {
+        let callback = Arc::new(callback);
+        let outputs = (0..MAX_AUDIO_MIXES as usize)
+            .map(|mix_index| {
+                let callback = callback.clone();
+
+                self.connect_output(mix_index, Box::new(move |data| callback(mix_index, data)))
+            })
+            .collect();
+
+        AudioOutputs { outputs }
+    }
+
     pub fn get_output_info(&self) -> AudioOutputInfo {
         unsafe {
-            AudioOutputInfo {
-                inner: audio_output_get_info(self.inner)
-            }
+            AudioOutputInfo::from_raw(audio_output_get_info(self.inner))
         }
     }
 
@@ -558,3 +922,457 @@ unsafe extern "C" fn global_audio_output_callback(
 
     std::mem::forget(callback);
 }
+
+/// The curve mapping decibels to the position of a fader or meter, see `Fader` and `VolMeter`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaderType {
+    /// A cubic curve, used by the volume controls of OBS.
+    Cubic,
+    /// The curve of IEC 60-268-18 peak programme meters.
+    IEC,
+    /// A logarithmic curve.
+    Log,
+}
+
+impl FaderType {
+    pub fn as_raw(&self) -> obs_fader_type {
+        match self {
+            FaderType::Cubic => obs_fader_type_OBS_FADER_CUBIC,
+            FaderType::IEC => obs_fader_type_OBS_FADER_IEC,
+            FaderType::Log => obs_fader_type_OBS_FADER_LOG,
+        }
+    }
+}
+
+/// How the peaks reported by a `VolMeter` are measured.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PeakMeterType {
+    /// The peak of the samples.
+    SamplePeak,
+    /// The peak of the reconstructed signal, which may exceed the peak of the samples.
+    TruePeak,
+}
+
+impl PeakMeterType {
+    pub fn as_raw(&self) -> obs_peak_meter_type {
+        match self {
+            PeakMeterType::SamplePeak => obs_peak_meter_type_SAMPLE_PEAK_METER,
+            PeakMeterType::TruePeak => obs_peak_meter_type_TRUE_PEAK_METER,
+        }
+    }
+}
+
+/// The levels measured by a `VolMeter` during one update interval, in decibels, with one value
+/// per audio channel of the metered source.
+#[derive(Clone, Copy, Debug)]
+pub struct VolMeterData<'a> {
+    /// The RMS level, with the volume of the source applied.
+    pub magnitude: &'a [f32],
+    /// The peak level, with the volume of the source applied.
+    pub peak: &'a [f32],
+    /// The peak level, before the volume of the source is applied.
+    pub input_peak: &'a [f32],
+}
+
+type VolMeterCallbackFn = Box<dyn FnMut(VolMeterData) + Send>;
+
+struct VolMeterCallback {
+    volmeter: *mut obs_volmeter_t,
+    callback: VolMeterCallbackFn,
+}
+
+/// Measures the audio levels of a source, the way the volume meters of OBS do.
+///
+/// ```rs
+/// let mut meter = VolMeter::new(FaderType::Cubic);
+/// meter.attach_source(&source);
+/// meter.add_callback(move |data| {
+///     let loudest = data.peak.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+///     sender.send(loudest).unwrap_or(());
+/// });
+/// ```
+///
+/// The callbacks are invoked on the audio thread, and are removed when the meter is dropped.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_volmeter_create)
+pub struct VolMeter {
+    inner: *mut obs_volmeter_t,
+    callbacks: Vec<*mut VolMeterCallback>,
+}
+
+unsafe impl Send for VolMeter {}
+unsafe impl Sync for VolMeter {}
+
+impl VolMeter {
+    pub fn new(fader_type: FaderType) -> Self {
+        Self {
+            inner: unsafe { obs_volmeter_create(fader_type.as_raw()) },
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Starts metering the `source`, replacing the previously attached source, if any.
+    /// The source is detached automatically when it is destroyed.
+    pub fn attach_source(&mut self, source: &SourceContext) -> bool {
+        unsafe { obs_volmeter_attach_source(self.inner, source.as_raw()) }
+    }
+
+    pub fn detach_source(&mut self) {
+        unsafe {
+            obs_volmeter_detach_source(self.inner);
+        }
+    }
+
+    pub fn set_peak_meter_type(&mut self, peak_meter_type: PeakMeterType) {
+        unsafe {
+            obs_volmeter_set_peak_meter_type(self.inner, peak_meter_type.as_raw());
+        }
+    }
+
+    /// Sets how often the callbacks are invoked, in milliseconds.
+    pub fn set_update_interval(&mut self, milliseconds: u32) {
+        unsafe {
+            obs_volmeter_set_update_interval(self.inner, milliseconds);
+        }
+    }
+
+    pub fn get_update_interval(&self) -> u32 {
+        unsafe { obs_volmeter_get_update_interval(self.inner) }
+    }
+
+    /// The number of channels metered, at least 1.
+    pub fn get_channels(&self) -> usize {
+        unsafe { get_volmeter_channels(self.inner) }
+    }
+
+    /// Registers a callback invoked with the measured levels at the end of every update
+    /// interval.
+    pub fn add_callback(&mut self, callback: impl FnMut(VolMeterData) + Send + 'static) {
+        let callback_ptr = Box::into_raw(Box::new(VolMeterCallback {
+            volmeter: self.inner,
+            callback: Box::new(callback),
+        }));
+
+        unsafe {
+            obs_volmeter_add_callback(
+                self.inner,
+                Some(global_volmeter_callback),
+                callback_ptr as *mut _,
+            );
+        }
+
+        self.callbacks.push(callback_ptr);
+    }
+}
+
+impl Drop for VolMeter {
+    fn drop(&mut self) {
+        unsafe {
+            for callback_ptr in self.callbacks.drain(..) {
+                obs_volmeter_remove_callback(
+                    self.inner,
+                    Some(global_volmeter_callback),
+                    callback_ptr as *mut _,
+                );
+
+                std::mem::drop(Box::from_raw(callback_ptr));
+            }
+
+            obs_volmeter_detach_source(self.inner);
+            obs_volmeter_destroy(self.inner);
+        }
+    }
+}
+
+unsafe fn get_volmeter_channels(volmeter: *mut obs_volmeter_t) -> usize {
+    (obs_volmeter_get_nr_channels(volmeter).max(1) as usize).min(MAX_AUDIO_CHANNELS as usize)
+}
+
+unsafe extern "C" fn global_volmeter_callback(
+    param: *mut c_void,
+    magnitude: *const f32,
+    peak: *const f32,
+    input_peak: *const f32,
+) {
+    let callback = &mut *(param as *mut VolMeterCallback);
+    let channels = get_volmeter_channels(callback.volmeter);
+
+    // OBS passes arrays of `MAX_AUDIO_CHANNELS` values, of which only the metered channels
+    // are meaningful.
+    let data = VolMeterData {
+        magnitude: std::slice::from_raw_parts(magnitude, channels),
+        peak: std::slice::from_raw_parts(peak, channels),
+        input_peak: std::slice::from_raw_parts(input_peak, channels),
+    };
+
+    (callback.callback)(data);
+}
+
+type FaderCallback = Box<dyn FnMut(f32) + Send>;
+
+/// A volume control attached to a source, which maps the position of a slider, the
+/// deflection, to the volume of the source along a curve, the way the volume sliders of OBS do.
+///
+/// ```rs
+/// let mut fader = Fader::new(FaderType::Cubic);
+/// fader.attach_source(&source);
+/// fader.set_deflection(0.5);
+/// let db = fader.get_db();
+/// ```
+///
+/// Changes of the volume of the attached source, made by any means, are reflected by the
+/// fader and reported to its callbacks.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_fader_create)
+pub struct Fader {
+    inner: *mut obs_fader_t,
+    callbacks: Vec<*mut FaderCallback>,
+}
+
+unsafe impl Send for Fader {}
+unsafe impl Sync for Fader {}
+
+impl Fader {
+    pub fn new(fader_type: FaderType) -> Self {
+        Self {
+            inner: unsafe { obs_fader_create(fader_type.as_raw()) },
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Controls the volume of the `source`, replacing the previously attached source, if any.
+    /// The source is detached automatically when it is destroyed.
+    pub fn attach_source(&mut self, source: &SourceContext) -> bool {
+        unsafe { obs_fader_attach_source(self.inner, source.as_raw()) }
+    }
+
+    pub fn detach_source(&mut self) {
+        unsafe {
+            obs_fader_detach_source(self.inner);
+        }
+    }
+
+    /// The volume, in decibels.
+    pub fn get_db(&self) -> f32 {
+        unsafe { obs_fader_get_db(self.inner) }
+    }
+
+    /// Sets the volume, in decibels. Values outside of the range of the fader are clamped,
+    /// in which case `false` is returned.
+    pub fn set_db(&mut self, db: f32) -> bool {
+        unsafe { obs_fader_set_db(self.inner, db) }
+    }
+
+    /// The position of the fader along its curve, from `0.0` to `1.0`.
+    pub fn get_deflection(&self) -> f32 {
+        unsafe { obs_fader_get_deflection(self.inner) }
+    }
+
+    /// Sets the position of the fader along its curve, from `0.0` to `1.0`. Values outside of
+    /// the range are clamped, in which case `false` is returned.
+    pub fn set_deflection(&mut self, deflection: f32) -> bool {
+        unsafe { obs_fader_set_deflection(self.inner, deflection) }
+    }
+
+    /// The volume, as a linear multiplier.
+    pub fn get_mul(&self) -> f32 {
+        unsafe { obs_fader_get_mul(self.inner) }
+    }
+
+    /// Sets the volume, as a linear multiplier. Values outside of the range of the fader are
+    /// clamped, in which case `false` is returned.
+    pub fn set_mul(&mut self, mul: f32) -> bool {
+        unsafe { obs_fader_set_mul(self.inner, mul) }
+    }
+
+    /// Registers a callback invoked with the new volume, in decibels, whenever the volume of
+    /// the attached source changes.
+    pub fn add_callback(&mut self, callback: impl FnMut(f32) + Send + 'static) {
+        let callback: FaderCallback = Box::new(callback);
+        let callback_ptr = Box::into_raw(Box::new(callback));
+
+        unsafe {
+            obs_fader_add_callback(self.inner, Some(global_fader_callback), callback_ptr as *mut _);
+        }
+
+        self.callbacks.push(callback_ptr);
+    }
+}
+
+impl Drop for Fader {
+    fn drop(&mut self) {
+        unsafe {
+            for callback_ptr in self.callbacks.drain(..) {
+                obs_fader_remove_callback(
+                    self.inner,
+                    Some(global_fader_callback),
+                    callback_ptr as *mut _,
+                );
+
+                std::mem::drop(Box::from_raw(callback_ptr));
+            }
+
+            obs_fader_detach_source(self.inner);
+            obs_fader_destroy(self.inner);
+        }
+    }
+}
+
+unsafe extern "C" fn global_fader_callback(param: *mut c_void, db: f32) {
+    let callback = &mut *(param as *mut FaderCallback);
+
+    (callback)(db);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn db_to_mul_known_values() {
+        assert_eq!(db_to_mul(0.0), 1.0);
+        assert!((db_to_mul(20.0) - 10.0).abs() < 1e-5);
+        assert!((db_to_mul(-20.0) - 0.1).abs() < 1e-6);
+        assert!((db_to_mul(-6.0206) - 0.5).abs() < 1e-5);
+        assert_eq!(db_to_mul(f32::NEG_INFINITY), 0.0);
+        assert_eq!(db_to_mul(f32::NAN), 0.0);
+    }
+
+    #[test]
+    fn mul_to_db_of_silence_is_negative_infinity() {
+        let db = mul_to_db(0.0);
+
+        assert!(!db.is_nan());
+        assert_eq!(db, f32::NEG_INFINITY);
+        assert_eq!(db_to_mul(db), 0.0);
+    }
+
+    #[test]
+    fn db_round_trip() {
+        assert_eq!(mul_to_db(1.0), 0.0);
+
+        for &db in &[-60.0, -20.0, -6.0, -0.5, 0.0, 3.0, 12.0, 30.0] {
+            assert!((mul_to_db(db_to_mul(db)) - db).abs() < 1e-4, "{} dB", db);
+        }
+
+        for &mul in &[0.001, 0.25, 0.5, 1.0, 2.0, 31.6] {
+            assert!((db_to_mul(mul_to_db(mul)) - mul).abs() < mul * 1e-5, "{}x", mul);
+        }
+    }
+
+    #[test]
+    fn signed_endpoints() {
+        let (max, min_magnitude) = (i16::MAX as f64, -(i16::MIN as f64));
+
+        assert_eq!(normalize_signed(i16::MAX as f64, max, min_magnitude), 1.0);
+        assert_eq!(normalize_signed(i16::MIN as f64, max, min_magnitude), -1.0);
+        assert_eq!(normalize_signed(0.0, max, min_magnitude), 0.0);
+
+        assert_eq!(denormalize_signed(1.0, max, min_magnitude), i16::MAX as f64);
+        assert_eq!(denormalize_signed(-1.0, max, min_magnitude), i16::MIN as f64);
+        assert_eq!(denormalize_signed(0.0, max, min_magnitude), 0.0);
+    }
+
+    #[test]
+    fn signed_denormalization_clamps() {
+        let (max, min_magnitude) = (i16::MAX as f64, -(i16::MIN as f64));
+
+        assert_eq!(denormalize_signed(1.5, max, min_magnitude), i16::MAX as f64);
+        assert_eq!(denormalize_signed(-7.0, max, min_magnitude), i16::MIN as f64);
+        assert_eq!(denormalize_signed(f32::INFINITY, max, min_magnitude), i16::MAX as f64);
+    }
+
+    #[test]
+    fn signed_round_trip_is_stable() {
+        let (max, min_magnitude) = (i16::MAX as f64, -(i16::MIN as f64));
+
+        for sample in i16::MIN..=i16::MAX {
+            let normalized = normalize_signed(sample as f64, max, min_magnitude);
+
+            assert_eq!(denormalize_signed(normalized, max, min_magnitude), sample as f64);
+        }
+
+        for &sample in &[-1.0, -0.75, -0.5, -0.001, 0.0, 0.001, 0.5, 0.75, 1.0] {
+            let denormalized = denormalize_signed(sample, max, min_magnitude);
+            let normalized = normalize_signed(denormalized, max, min_magnitude);
+
+            assert!((normalized - sample).abs() <= 1.0 / max as f32, "{}", sample);
+            assert_eq!(denormalize_signed(normalized, max, min_magnitude), denormalized);
+        }
+    }
+
+    /// Normalizes the `samples` of the format `F` and converts them back, both to samples and
+    /// to bytes, returning the converted samples.
+    fn round_trip<F: AudioFormat>(
+        samples: &[F::SampleType],
+        to_ne_bytes: impl Fn(F::SampleType) -> Vec<u8>,
+    ) -> Vec<F::SampleType> {
+        let normalized = samples.iter().map(|sample| F::normalize_sample(*sample)).collect::<Vec<_>>();
+
+        assert!(normalized.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+
+        let denormalized = F::denormalize_samples(&normalized);
+        let bytes = denormalized.iter().flat_map(|sample| to_ne_bytes(*sample)).collect::<Vec<_>>();
+
+        assert_eq!(F::KIND.denormalize_samples_to_bytes(&normalized), bytes);
+        denormalized
+    }
+
+    #[test]
+    fn u8_round_trip() {
+        let samples = (0..=u8::MAX).collect::<Vec<_>>();
+
+        assert_eq!(AudioFormatInterleavedU8::normalize_sample(0), -1.0);
+        assert_eq!(AudioFormatInterleavedU8::normalize_sample(128), 0.0);
+        assert_eq!(AudioFormatInterleavedU8::normalize_sample(u8::MAX), 1.0);
+
+        assert_eq!(round_trip::<AudioFormatInterleavedU8>(&samples, |sample| vec![sample]), samples);
+        assert_eq!(round_trip::<AudioFormatPlanarU8>(&samples, |sample| vec![sample]), samples);
+    }
+
+    #[test]
+    fn i16_round_trip() {
+        let samples = (i16::MIN..=i16::MAX).collect::<Vec<_>>();
+        let to_ne_bytes = |sample: i16| sample.to_ne_bytes().to_vec();
+
+        assert_eq!(round_trip::<AudioFormatInterleavedI16>(&samples, to_ne_bytes), samples);
+        assert_eq!(round_trip::<AudioFormatPlanarI16>(&samples, to_ne_bytes), samples);
+    }
+
+    #[test]
+    fn i32_round_trip() {
+        let samples = [i32::MIN, i32::MIN + 1, -1 << 24, -12345, -1, 0, 1, 12345, 1 << 24, i32::MAX - 1, i32::MAX];
+        let to_ne_bytes = |sample: i32| sample.to_ne_bytes().to_vec();
+
+        for denormalized in [
+            round_trip::<AudioFormatInterleavedI32>(&samples, to_ne_bytes),
+            round_trip::<AudioFormatPlanarI32>(&samples, to_ne_bytes),
+        ].iter() {
+            assert_eq!(denormalized[0], i32::MIN);
+            assert_eq!(denormalized[samples.len() - 1], i32::MAX);
+
+            // Normalized samples are `f32`, so only 24 bits of large samples are preserved.
+            for (sample, denormalized) in samples.iter().zip(denormalized) {
+                let tolerance = (sample.unsigned_abs() >> 24).max(1) as i64;
+
+                assert!((*sample as i64 - *denormalized as i64).abs() <= tolerance, "{} became {}", sample, denormalized);
+            }
+
+            assert_eq!(&denormalized[3..8], &samples[3..8]);
+        }
+    }
+
+    #[test]
+    fn f32_round_trip() {
+        let samples = [-1.0, -0.5, -0.001, 0.0, 0.001, 0.5, 1.0];
+        let to_ne_bytes = |sample: f32| sample.to_ne_bytes().to_vec();
+
+        assert_eq!(round_trip::<AudioFormatInterleavedF32>(&samples, to_ne_bytes), samples);
+        assert_eq!(round_trip::<AudioFormatPlanarF32>(&samples, to_ne_bytes), samples);
+    }
+
+    #[test]
+    fn unknown_format_has_no_bytes() {
+        assert!(AudioFormatKind::Unknown.denormalize_samples_to_bytes(&[0.0, 1.0]).is_empty());
+    }
+}
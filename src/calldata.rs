@@ -0,0 +1,110 @@
+use std::ffi::{c_void, CStr};
+use std::mem::MaybeUninit;
+use std::os::raw::{c_char, c_longlong};
+use obs_sys::{calldata_get_data, calldata_get_string, calldata_set_data, calldata_t, size_t};
+
+/// Parameters and return values passed to procedures and signal callbacks.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-libobs-callback.html#c.calldata_t)
+pub struct Calldata {
+    raw: *mut calldata_t,
+}
+
+impl Calldata {
+    /// # Safety
+    /// The pointer must point to valid call data, which outlives the created `Calldata`.
+    pub unsafe fn from_raw(raw: *mut calldata_t) -> Self {
+        Self { raw }
+    }
+
+    /// # Safety
+    /// Returns a mutable pointer to the call data which if modified could cause UB.
+    pub unsafe fn as_raw(&self) -> *mut calldata_t {
+        self.raw
+    }
+
+    fn get_data<T: Copy>(&self, name: &CStr) -> Option<T> {
+        unsafe {
+            let mut value = MaybeUninit::<T>::uninit();
+
+            if calldata_get_data(
+                self.raw,
+                name.as_ptr(),
+                value.as_mut_ptr() as *mut c_void,
+                std::mem::size_of::<T>() as size_t,
+            ) {
+                Some(value.assume_init())
+            } else {
+                None
+            }
+        }
+    }
+
+    fn set_data<T: Copy>(&mut self, name: &CStr, value: T) {
+        unsafe {
+            calldata_set_data(
+                self.raw,
+                name.as_ptr(),
+                &value as *const T as *const c_void,
+                std::mem::size_of::<T>() as size_t,
+            );
+        }
+    }
+
+    pub fn get_int(&self, name: &CStr) -> Option<i64> {
+        self.get_data::<c_longlong>(name).map(|value| value as i64)
+    }
+
+    pub fn get_float(&self, name: &CStr) -> Option<f64> {
+        self.get_data::<f64>(name)
+    }
+
+    pub fn get_bool(&self, name: &CStr) -> Option<bool> {
+        self.get_data::<bool>(name)
+    }
+
+    pub fn get_ptr(&self, name: &CStr) -> Option<*mut c_void> {
+        self.get_data::<*mut c_void>(name)
+    }
+
+    pub fn get_string(&self, name: &CStr) -> Option<&CStr> {
+        unsafe {
+            let mut value: *const c_char = std::ptr::null();
+
+            if calldata_get_string(self.raw, name.as_ptr(), &mut value) && !value.is_null() {
+                Some(CStr::from_ptr(value))
+            } else {
+                None
+            }
+        }
+    }
+
+    pub fn set_int(&mut self, name: &CStr, value: i64) {
+        self.set_data(name, value as c_longlong);
+    }
+
+    pub fn set_float(&mut self, name: &CStr, value: f64) {
+        self.set_data(name, value);
+    }
+
+    pub fn set_bool(&mut self, name: &CStr, value: bool) {
+        self.set_data(name, value);
+    }
+
+    pub fn set_ptr(&mut self, name: &CStr, value: *mut c_void) {
+        self.set_data(name, value);
+    }
+
+    pub fn set_string(&mut self, name: &CStr, value: &CStr) {
+        let bytes = value.to_bytes_with_nul();
+
+        unsafe {
+            calldata_set_data(
+                self.raw,
+                name.as_ptr(),
+                bytes.as_ptr() as *const c_void,
+                bytes.len() as size_t,
+            );
+        }
+    }
+}
@@ -78,6 +78,8 @@ impl ContextDependentState for Disabled {
 /// be context-dependent. Dropping a disabled context-dependent type may cause a `panic!`,
 /// if the required context cannot be re-entered.
 /// Users should ensure all context-dependent types are dropped during the enabled state.
+/// Alternatively, `ContextDependent::try_drop` may be used to handle the failure to enter
+/// the context explicitly, and `ContextDependent::leak` to give up on dropping the value.
 ///
 /// Implementation note:
 /// It must be ensured that all context-dependent types `T` can only be constructed in a way, so that
@@ -92,6 +94,10 @@ pub struct ContextDependent<T, C: Context, S: ContextDependentState> {
     __marker: PhantomData<C>,
 }
 
+// A disabled value holds no reference to the context, so it may be sent to other threads,
+// e.g. to be dropped within the context later.
+unsafe impl<T: Send, C: Context> Send for ContextDependent<T, C, Disabled> {}
+
 impl<T, C: Context, S: ContextDependentState> ContextDependent<T, C, S> {
     pub fn state(&self) -> &S {
         &self.state
@@ -131,6 +137,15 @@ impl<'a, T, C: Context> ContextDependent<T, C, Enabled<'a, C>> {
         }
     }
 
+    /// Combines two values enabled in the same context into a single one, e.g. to store
+    /// related resources together.
+    pub fn zip<U>(mut self, mut other: ContextDependent<U, C, Enabled<'a, C>>) -> ContextDependent<(T, U), C, Enabled<'a, C>> {
+        let data = ManuallyDrop::into_inner(self.data.take().unwrap());
+        let other_data = ManuallyDrop::into_inner(other.data.take().unwrap());
+
+        ContextDependent::new((data, other_data), self.state.context)
+    }
+
     pub fn context(&self) -> &'a C {
         &self.state.context
     }
@@ -174,6 +189,62 @@ impl<T, C: Context> ContextDependent<T, C, Disabled> {
             context,
         }
     }
+
+    /// Temporarily enables the value within the `context`, passes it to `f`, and disables it
+    /// again afterwards, without moving it out of `self`.
+    ///
+    /// Useful for one-shot operations on stored values, such as textures used in `video_render`:
+    ///
+    /// ```rs
+    /// let size = data.texture.with_enabled(&graphics_context, |texture| texture.get_dimensions());
+    /// ```
+    pub fn with_enabled<'a, R>(
+        &mut self,
+        context: &'a C,
+        f: impl FnOnce(&mut ContextDependent<T, C, Enabled<'a, C>>) -> R,
+    ) -> R {
+        let mut enabled = ContextDependent {
+            data: self.data.take(),
+            state: Enabled {
+                context,
+            },
+            __marker: Default::default(),
+        };
+
+        let result = (f)(&mut enabled);
+
+        // Move the data back, so that it is not dropped along with the enabled wrapper.
+        self.data = enabled.data.take();
+
+        result
+    }
+
+    /// Attempts to enter the context `C` and drop the wrapped value within it.
+    ///
+    /// Unlike `Drop::drop`, this function never panics. If the context cannot be entered,
+    /// the value is handed back, so that the caller may retry later, or `leak` it.
+    pub fn try_drop(mut self) -> Result<(), Self> {
+        if let Some(_context) = C::enter() {
+            if let Some(mut data) = self.data.take() {
+                unsafe {
+                    ManuallyDrop::drop(&mut data);
+                }
+            }
+
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Leaks the wrapped value, without ever calling its destructor.
+    ///
+    /// Useful as a last resort when the context cannot be entered anymore, for example during
+    /// the shutdown of OBS, as it avoids the `panic!` of `Drop::drop`.
+    pub fn leak(mut self) {
+        // The data is wrapped in `ManuallyDrop`, so taking it out suffices.
+        self.data.take();
+    }
 }
 
 impl<T, C: Context, S: ContextDependentState> Drop for ContextDependent<T, C, S> {
@@ -0,0 +1,536 @@
+use std::ffi::CStr;
+use obs_sys::{
+    obs_data_addref, obs_data_array_addref, obs_data_array_count, obs_data_array_item,
+    obs_data_array_release, obs_data_array_t, obs_data_create, obs_data_erase,
+    obs_data_get_array, obs_data_get_bool, obs_data_get_double, obs_data_get_int,
+    obs_data_get_obj, obs_data_get_string, obs_data_has_default_value, obs_data_has_user_value,
+    obs_data_release, obs_data_set_array, obs_data_set_bool, obs_data_set_double,
+    obs_data_set_int, obs_data_set_obj, obs_data_set_string, obs_data_t, size_t,
+    obs_data_first, obs_data_item_byname, obs_data_item_get_name, obs_data_item_gettype,
+    obs_data_item_next, obs_data_item_numtype, obs_data_item_release, obs_data_item_t,
+    obs_data_number_type_OBS_DATA_NUM_DOUBLE, obs_data_number_type_OBS_DATA_NUM_INT,
+    obs_data_type_OBS_DATA_ARRAY, obs_data_type_OBS_DATA_BOOLEAN,
+    obs_data_type_OBS_DATA_NUMBER, obs_data_type_OBS_DATA_OBJECT, obs_data_type_OBS_DATA_STRING,
+    obs_data_apply, obs_data_set_default_bool, obs_data_set_default_double,
+    obs_data_set_default_int, obs_data_set_default_obj, obs_data_set_default_string,
+    obs_data_create_from_json, obs_data_get_json, obs_data_array_create, obs_data_array_push_back,
+};
+use std::ffi::CString;
+use std::marker::PhantomData;
+
+/// A reference-counted OBS data object, such as an item of a `DataArray`, or a nested object
+/// of the settings of a source.
+///
+/// ```rs
+/// let mut region = DataObject::new();
+/// region.set_int(cstr!("x"), 16);
+/// region.set_int(cstr!("y"), 32);
+///
+/// let x = region.get_int(cstr!("x"));
+/// ```
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-settings.html)
+pub struct DataObject {
+    raw: *mut obs_data_t,
+}
+
+impl DataObject {
+    /// Creates a new, empty data object.
+    pub fn new() -> Self {
+        unsafe {
+            Self {
+                raw: obs_data_create(),
+            }
+        }
+    }
+
+    /// Parses the JSON object `json`, returning `None` if it is not a valid JSON object.
+    pub fn from_json(json: &str) -> Option<Self> {
+        let json = CString::new(json).ok()?;
+
+        unsafe { Self::from_raw(obs_data_create_from_json(json.as_ptr())) }
+    }
+
+    /// Serializes the user-set values of the data object as a JSON object. Default values are
+    /// omitted.
+    pub fn to_json(&self) -> String {
+        unsafe {
+            let json = obs_data_get_json(self.raw);
+
+            if json.is_null() {
+                String::from("{}")
+            } else {
+                CStr::from_ptr(json).to_string_lossy().into_owned()
+            }
+        }
+    }
+
+    /// # Safety
+    /// Takes ownership of a reference to the data object, which is released on drop.
+    /// The pointer must be valid, or null, in which case `None` is returned.
+    pub unsafe fn from_raw(raw: *mut obs_data_t) -> Option<Self> {
+        if raw.is_null() {
+            None
+        } else {
+            Some(Self { raw })
+        }
+    }
+
+    /// # Safety
+    /// Returns a mutable pointer to the data object which if modified could cause UB.
+    pub unsafe fn as_raw(&self) -> *mut obs_data_t {
+        self.raw
+    }
+
+    /// Whether the item `name` has either a user-set or a default value.
+    pub fn contains(&self, name: &CStr) -> bool {
+        unsafe {
+            obs_data_has_user_value(self.raw, name.as_ptr())
+                || obs_data_has_default_value(self.raw, name.as_ptr())
+        }
+    }
+
+    pub fn get_int(&self, name: &CStr) -> Option<i64> {
+        if self.contains(name) {
+            Some(unsafe { obs_data_get_int(self.raw, name.as_ptr()) } as i64)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_double(&self, name: &CStr) -> Option<f64> {
+        if self.contains(name) {
+            Some(unsafe { obs_data_get_double(self.raw, name.as_ptr()) })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_bool(&self, name: &CStr) -> Option<bool> {
+        if self.contains(name) {
+            Some(unsafe { obs_data_get_bool(self.raw, name.as_ptr()) })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_string(&self, name: &CStr) -> Option<String> {
+        if self.contains(name) {
+            unsafe {
+                let value = obs_data_get_string(self.raw, name.as_ptr());
+
+                if value.is_null() {
+                    None
+                } else {
+                    Some(CStr::from_ptr(value).to_string_lossy().into_owned())
+                }
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn get_obj(&self, name: &CStr) -> Option<DataObject> {
+        unsafe { DataObject::from_raw(obs_data_get_obj(self.raw, name.as_ptr())) }
+    }
+
+    pub fn get_array(&self, name: &CStr) -> Option<DataArray> {
+        unsafe { DataArray::from_raw(obs_data_get_array(self.raw, name.as_ptr())) }
+    }
+
+    pub fn set_int(&mut self, name: &CStr, value: i64) {
+        unsafe {
+            obs_data_set_int(self.raw, name.as_ptr(), value as _);
+        }
+    }
+
+    pub fn set_double(&mut self, name: &CStr, value: f64) {
+        unsafe {
+            obs_data_set_double(self.raw, name.as_ptr(), value);
+        }
+    }
+
+    pub fn set_bool(&mut self, name: &CStr, value: bool) {
+        unsafe {
+            obs_data_set_bool(self.raw, name.as_ptr(), value);
+        }
+    }
+
+    pub fn set_string(&mut self, name: &CStr, value: &CStr) {
+        unsafe {
+            obs_data_set_string(self.raw, name.as_ptr(), value.as_ptr());
+        }
+    }
+
+    /// Stores the `value` as a nested object. The object is shared, not copied, so later
+    /// modifications of `value` are reflected in `self`.
+    pub fn set_obj(&mut self, name: &CStr, value: &DataObject) {
+        unsafe {
+            obs_data_set_obj(self.raw, name.as_ptr(), value.raw);
+        }
+    }
+
+    /// Stores the `value` as a nested array. The array is shared, not copied.
+    pub fn set_array(&mut self, name: &CStr, value: &DataArray) {
+        unsafe {
+            obs_data_set_array(self.raw, name.as_ptr(), value.raw);
+        }
+    }
+
+    /// Removes the user-set value of the item `name`.
+    pub fn erase(&mut self, name: &CStr) {
+        unsafe {
+            obs_data_erase(self.raw, name.as_ptr());
+        }
+    }
+
+    /// Sets the values of all items of `other` in `self`, overwriting the current values.
+    pub fn merge(&mut self, other: &DataObject) {
+        unsafe {
+            obs_data_apply(self.raw, other.raw);
+        }
+    }
+
+    /// Uses the values of the items of `other` as the default values of `self`, see
+    /// `apply_defaults`.
+    pub fn apply_defaults(&mut self, other: &DataObject) {
+        unsafe {
+            apply_defaults(self.raw, other.raw);
+        }
+    }
+
+    /// The type of the item `name`, or `None` if there is no such item.
+    pub fn item_type(&self, name: &CStr) -> Option<ObsDataType> {
+        unsafe { get_item_type(self.raw, name) }
+    }
+
+    /// Iterates over the names of all items, including those which only have a default value.
+    pub fn keys(&self) -> DataKeys<'_> {
+        unsafe { DataKeys::from_raw(self.raw) }
+    }
+}
+
+impl Default for DataObject {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for DataObject {
+    fn clone(&self) -> Self {
+        unsafe {
+            obs_data_addref(self.raw);
+        }
+
+        Self { raw: self.raw }
+    }
+}
+
+impl Drop for DataObject {
+    fn drop(&mut self) {
+        unsafe {
+            obs_data_release(self.raw);
+        }
+    }
+}
+
+/// A reference-counted array of OBS data objects, as used by array-valued settings, such as
+/// the editable list property.
+///
+/// ```rs
+/// if let Some(array) = settings.get_array(cstr!("files")) {
+///     for item in array.iter() {
+///         let path = item.get_string(cstr!("value"));
+///     }
+/// }
+/// ```
+pub struct DataArray {
+    raw: *mut obs_data_array_t,
+}
+
+impl DataArray {
+    /// Creates a new, empty array.
+    pub fn new() -> Self {
+        unsafe {
+            Self {
+                raw: obs_data_array_create(),
+            }
+        }
+    }
+
+    /// # Safety
+    /// Takes ownership of a reference to the array, which is released on drop.
+    /// The pointer must be valid, or null, in which case `None` is returned.
+    pub unsafe fn from_raw(raw: *mut obs_data_array_t) -> Option<Self> {
+        if raw.is_null() {
+            None
+        } else {
+            Some(Self { raw })
+        }
+    }
+
+    /// # Safety
+    /// Returns a mutable pointer to the array which if modified could cause UB.
+    pub unsafe fn as_raw(&self) -> *mut obs_data_array_t {
+        self.raw
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { obs_data_array_count(self.raw) as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<DataObject> {
+        if index >= self.len() {
+            return None;
+        }
+
+        unsafe { DataObject::from_raw(obs_data_array_item(self.raw, index as size_t)) }
+    }
+
+    /// Appends the `item` to the array. The item is shared, not copied.
+    pub fn push(&mut self, item: &DataObject) {
+        unsafe {
+            obs_data_array_push_back(self.raw, item.raw);
+        }
+    }
+
+    pub fn iter(&self) -> DataArrayIter<'_> {
+        DataArrayIter {
+            array: self,
+            index: 0,
+            len: self.len(),
+        }
+    }
+}
+
+impl Default for DataArray {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for DataArray {
+    fn clone(&self) -> Self {
+        unsafe {
+            obs_data_array_addref(self.raw);
+        }
+
+        Self { raw: self.raw }
+    }
+}
+
+impl Drop for DataArray {
+    fn drop(&mut self) {
+        unsafe {
+            obs_data_array_release(self.raw);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a DataArray {
+    type Item = DataObject;
+    type IntoIter = DataArrayIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterates over the items of a `DataArray`.
+pub struct DataArrayIter<'a> {
+    array: &'a DataArray,
+    index: usize,
+    len: usize,
+}
+
+impl<'a> Iterator for DataArrayIter<'a> {
+    type Item = DataObject;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.len {
+            let index = self.index;
+            self.index += 1;
+
+            if let Some(item) = self.array.get(index) {
+                return Some(item);
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.len - self.index))
+    }
+}
+
+/// The type of an item of a data object, see `DataObject::item_type`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObsDataType {
+    Null,
+    String,
+    Int,
+    Double,
+    Bool,
+    Object,
+    Array,
+}
+
+impl ObsDataType {
+    #[cfg(feature = "mock")]
+    pub(crate) fn from_json(value: &serde_json::Value) -> Self {
+        use serde_json::Value;
+
+        match value {
+            Value::Null => ObsDataType::Null,
+            Value::String(_) => ObsDataType::String,
+            Value::Number(number) if number.is_f64() => ObsDataType::Double,
+            Value::Number(_) => ObsDataType::Int,
+            Value::Bool(_) => ObsDataType::Bool,
+            Value::Object(_) => ObsDataType::Object,
+            Value::Array(_) => ObsDataType::Array,
+        }
+    }
+}
+
+/// # Safety
+/// The `data` must be a valid pointer to a data object.
+pub(crate) unsafe fn get_item_type(data: *mut obs_data_t, name: &CStr) -> Option<ObsDataType> {
+    let mut item = obs_data_item_byname(data, name.as_ptr());
+
+    if item.is_null() {
+        return None;
+    }
+
+    #[allow(non_upper_case_globals)]
+    let data_type = match obs_data_item_gettype(item) {
+        obs_data_type_OBS_DATA_STRING => ObsDataType::String,
+        obs_data_type_OBS_DATA_NUMBER => match obs_data_item_numtype(item) {
+            obs_data_number_type_OBS_DATA_NUM_INT => ObsDataType::Int,
+            obs_data_number_type_OBS_DATA_NUM_DOUBLE => ObsDataType::Double,
+            _ => ObsDataType::Null,
+        },
+        obs_data_type_OBS_DATA_BOOLEAN => ObsDataType::Bool,
+        obs_data_type_OBS_DATA_OBJECT => ObsDataType::Object,
+        obs_data_type_OBS_DATA_ARRAY => ObsDataType::Array,
+        _ => ObsDataType::Null,
+    };
+
+    obs_data_item_release(&mut item);
+
+    Some(data_type)
+}
+
+/// Sets the values of the items of `source` as the default values of the items of the same
+/// names in `target`. Arrays are skipped, as OBS does not support default values of arrays.
+///
+/// # Safety
+/// Both pointers must be valid pointers to data objects.
+pub(crate) unsafe fn apply_defaults(target: *mut obs_data_t, source: *mut obs_data_t) {
+    for key in DataKeys::from_raw(source) {
+        let name = match CString::new(key) {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+        let name_ptr = name.as_ptr();
+
+        match get_item_type(source, &name) {
+            Some(ObsDataType::String) => {
+                obs_data_set_default_string(target, name_ptr, obs_data_get_string(source, name_ptr))
+            }
+            Some(ObsDataType::Int) => {
+                obs_data_set_default_int(target, name_ptr, obs_data_get_int(source, name_ptr))
+            }
+            Some(ObsDataType::Double) => {
+                obs_data_set_default_double(target, name_ptr, obs_data_get_double(source, name_ptr))
+            }
+            Some(ObsDataType::Bool) => {
+                obs_data_set_default_bool(target, name_ptr, obs_data_get_bool(source, name_ptr))
+            }
+            Some(ObsDataType::Object) => {
+                if let Some(object) = DataObject::from_raw(obs_data_get_obj(source, name_ptr)) {
+                    obs_data_set_default_obj(target, name_ptr, object.raw);
+                }
+            }
+            Some(ObsDataType::Array) | Some(ObsDataType::Null) | None => {}
+        }
+    }
+}
+
+enum DataKeysInner {
+    Raw(*mut obs_data_item_t),
+    #[cfg(feature = "mock")]
+    Mock(std::vec::IntoIter<String>),
+}
+
+/// Iterates over the names of the items of a data object, see `DataObject::keys`.
+pub struct DataKeys<'a> {
+    inner: DataKeysInner,
+    __marker: PhantomData<&'a ()>,
+}
+
+impl<'a> DataKeys<'a> {
+    /// # Safety
+    /// The `data` must be a valid pointer to a data object, or null, in which case the
+    /// iterator is empty. The data object must outlive the iterator.
+    pub(crate) unsafe fn from_raw(data: *mut obs_data_t) -> Self {
+        Self {
+            inner: DataKeysInner::Raw(if data.is_null() {
+                std::ptr::null_mut()
+            } else {
+                obs_data_first(data)
+            }),
+            __marker: PhantomData,
+        }
+    }
+
+    #[cfg(feature = "mock")]
+    pub(crate) fn from_names(names: Vec<String>) -> Self {
+        Self {
+            inner: DataKeysInner::Mock(names.into_iter()),
+            __marker: PhantomData,
+        }
+    }
+}
+
+impl<'a> Iterator for DataKeys<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            DataKeysInner::Raw(item) => unsafe {
+                if item.is_null() {
+                    return None;
+                }
+
+                let name = CStr::from_ptr(obs_data_item_get_name(*item))
+                    .to_string_lossy()
+                    .into_owned();
+
+                // Releases the current item and acquires a reference to the next one, or sets
+                // the pointer to null at the end.
+                obs_data_item_next(item);
+
+                Some(name)
+            },
+            #[cfg(feature = "mock")]
+            DataKeysInner::Mock(names) => names.next(),
+        }
+    }
+}
+
+impl<'a> Drop for DataKeys<'a> {
+    fn drop(&mut self) {
+        if let DataKeysInner::Raw(item) = &mut self.inner {
+            if !item.is_null() {
+                unsafe {
+                    obs_data_item_release(item);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Bindings to the OBS frontend API, which is only available to plugins loaded by the OBS
+//! application itself, rather than by other programs using libobs.
+//!
+//! Enabled by the `frontend` feature.
+
+use crate::graphics::*;
+use obs_sys::obs_get_main_texture;
+
+#[link(name = "obs-frontend-api")]
+extern "C" {
+    fn obs_frontend_take_screenshot();
+}
+
+/// Saves a screenshot of the program output to the screenshot directory configured by the
+/// user, as if the user pressed the screenshot hotkey.
+///
+/// The screenshot is taken asynchronously: this function returns immediately and the file is
+/// written once the next frame has been rendered.
+pub fn take_screenshot() {
+    unsafe {
+        obs_frontend_take_screenshot();
+    }
+}
+
+/// A frame captured from the program output, with tightly packed RGBA pixels.
+pub struct CapturedFrame {
+    pub dimensions: [usize; 2],
+    pub data: Vec<u8>,
+}
+
+/// Reads back the most recently rendered frame of the program output.
+///
+/// This waits for the GPU to finish rendering the frame, which is costly, so it is meant for
+/// occasional captures, such as periodic thumbnails, rather than for every frame. Must be called
+/// in the graphics context, e.g. from a callback registered with
+/// `obs_add_main_render_callback`.
+pub fn capture_output_frame(context: &GraphicsContext) -> Option<CapturedFrame> {
+    let raw = unsafe { obs_get_main_texture() };
+
+    if raw.is_null() {
+        return None;
+    }
+
+    let texture = unsafe { Texture::from_raw(raw, 0) };
+    let dimensions = texture.get_dimensions();
+    let color_format = texture.get_color_format();
+    let mut surface = StageSurface::new(dimensions, color_format, context)?;
+
+    surface.stage(&texture);
+
+    let mut data = StageSurface::map(&mut surface)?.to_packed_vec();
+
+    match color_format {
+        ColorFormatKind::RGBA => (),
+        ColorFormatKind::BGRA | ColorFormatKind::BGRX => {
+            for pixel in data.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+
+                if color_format == ColorFormatKind::BGRX {
+                    pixel[3] = 0xFF;
+                }
+            }
+        }
+        _ => return None,
+    }
+
+    Some(CapturedFrame { dimensions, data })
+}
@@ -0,0 +1,94 @@
+use super::*;
+use crate::util::{Easing, Interpolate, Tween};
+
+/// An effect parameter whose value is animated towards a target, e.g. the intensity of a glow
+/// fading in and out.
+///
+/// The animation is advanced in `VideoTickSource::video_tick`, and the current value is set to
+/// the parameter when rendering:
+///
+/// ```rs
+/// // In `create`
+/// let intensity = effect.require_param::<ShaderParamTypeFloat>(cstr!("intensity"))?;
+/// let intensity = AnimatedParam::new(intensity, 0.0, Easing::EaseInOut);
+///
+/// // In `update`
+/// let target = if settings.glow { settings.intensity } else { 0.0 };
+/// data.intensity.as_enabled_mut(&graphics_context).set_target(target, 0.5);
+///
+/// // In `video_tick`
+/// data.intensity.as_enabled_mut(&graphics_context).advance(seconds);
+///
+/// // In `video_render`
+/// let intensity = &mut data.intensity.as_enabled_mut(graphics_context);
+///
+/// source.process_filter(effect, (cx, cy), ColorFormatKind::RGBA, direct, |context, _effect| {
+///     intensity.apply(context);
+/// });
+/// ```
+pub struct AnimatedParam<T: ShaderParamType>
+where
+    T::RustType: Interpolate,
+{
+    param: GraphicsEffectParamTyped<T>,
+    tween: Tween<T::RustType>,
+}
+
+impl<T: ShaderParamType> AnimatedParam<T>
+where
+    T::RustType: Interpolate,
+{
+    /// Wraps the `param`, starting at the `value`, without animating.
+    pub fn new<'a>(
+        param: GraphicsContextDependentEnabled<'a, GraphicsEffectParamTyped<T>>,
+        value: T::RustType,
+        easing: Easing,
+    ) -> GraphicsContextDependentEnabled<'a, Self> {
+        param.map(|param| Self {
+            param,
+            tween: Tween::new(value, easing),
+        })
+    }
+
+    /// Starts animating towards the `target`, over `duration` seconds, see `Tween::set_target`.
+    pub fn set_target(&mut self, target: T::RustType, duration: f32) {
+        self.tween.set_target(target, duration);
+    }
+
+    /// Jumps to the `value`, stopping the animation.
+    pub fn set_value(&mut self, value: T::RustType) {
+        self.tween.set_value(value);
+    }
+
+    /// Advances the animation by `seconds`, without setting the parameter.
+    pub fn advance(&mut self, seconds: f32) {
+        self.tween.advance(seconds);
+    }
+
+    /// Sets the current value of the animation to the parameter.
+    pub fn apply(&mut self, context: &FilterContext) {
+        let value = self.tween.value();
+
+        self.param.set_param_value(&value, context);
+    }
+
+    /// Advances the animation by `seconds` and sets the resulting value to the parameter.
+    pub fn tick(&mut self, seconds: f32, context: &FilterContext) {
+        self.advance(seconds);
+        self.apply(context);
+    }
+
+    /// The current value of the animation.
+    pub fn value(&self) -> T::RustType {
+        self.tween.value()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.tween.is_finished()
+    }
+
+    /// See `GraphicsEffectParamTyped::refetch`.
+    pub fn refetch(&mut self, effect: &GraphicsEffect) -> bool {
+        self.param.refetch(effect)
+    }
+}
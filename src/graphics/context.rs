@@ -1,8 +1,57 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, mpsc};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
 use std::cell::RefCell;
-use obs_sys::{graphics_t, gs_get_context, obs_enter_graphics, obs_leave_graphics};
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::marker::PhantomData;
+use obs_sys::{
+    graphics_t, gs_clear, gs_get_context, gs_get_render_target, obs_enter_graphics,
+    obs_leave_graphics,
+    vec4, vec4__bindgen_ty_1, GS_CLEAR_COLOR, GS_CLEAR_DEPTH, GS_CLEAR_STENCIL,
+    gs_get_device_name, gs_get_device_type, gs_nv12_available, GS_DEVICE_DIRECT3D_11,
+    GS_DEVICE_OPENGL, obs_initialized, gs_effect_get_param_by_name, gs_effect_set_texture,
+    gs_effect_loop, gs_draw_sprite, gs_get_width, gs_get_height, gs_texture_get_width,
+    gs_texture_get_height, gs_ortho, gs_flush,
+};
+use cstr::cstr;
 use crate::context::*;
 use crate::graphics::texture::*;
+use crate::graphics::GraphicsEffect;
+use crate::source::properties::Color;
+
+/// The graphics API used by OBS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphicsDeviceType {
+    OpenGL,
+    Direct3D11,
+    Unknown,
+}
+
+/// The reason the graphics context could not be entered, see `GraphicsContext::try_enter`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphicsContextError {
+    /// OBS has not been initialized yet, or has already been shut down.
+    ObsNotInitialized,
+    /// OBS is running, but its graphics subsystem is not available, e.g. because the video
+    /// output has not been initialized, or has failed to initialize.
+    GraphicsUnavailable,
+}
+
+impl std::fmt::Display for GraphicsContextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphicsContextError::ObsNotInitialized => {
+                write!(f, "Could not enter the graphics context, as OBS is not initialized.")
+            }
+            GraphicsContextError::GraphicsUnavailable => {
+                write!(f, "Could not enter the graphics context, as the graphics subsystem of OBS is not available.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GraphicsContextError {}
 
 /// A handle to the graphics context.
 pub struct GraphicsContext {
@@ -50,11 +99,343 @@ impl Context for GraphicsContext {
 }
 
 impl GraphicsContext {
+    /// Like `Context::enter`, returns the current graphics context, if called within it, and
+    /// enters it otherwise, but describes why the context could not be entered on failure.
+    ///
+    /// ```rs
+    /// let graphics_context = GraphicsContext::try_enter()
+    ///     .unwrap_or_else(|error| panic!("Could not create the resources of the plugin: {}", error));
+    /// ```
+    ///
+    /// Note: entering the context from a thread other than the graphics thread blocks until the
+    /// graphics thread leaves the context, rather than failing, see `try_enter_with_timeout`.
+    pub fn try_enter() -> Result<Self, GraphicsContextError> {
+        if let Some(context) = Self::get_current() {
+            return Ok(context);
+        }
+
+        if !unsafe { obs_initialized() } {
+            return Err(GraphicsContextError::ObsNotInitialized);
+        }
+
+        Self::enter_once().ok_or(GraphicsContextError::GraphicsUnavailable)
+    }
+
+    /// Runs `f` within the graphics context, waiting at most `timeout` for the context to be
+    /// entered, e.g. for a worker thread of the plugin which must not block indefinitely.
+    /// Returns `None` if the context could not be entered in time, or at all.
+    ///
+    /// ```rs
+    /// let uploaded = GraphicsContext::try_enter_with_timeout(Duration::from_millis(100), move |graphics_context| {
+    ///     Texture::new(dimensions, ColorFormatKind::RGBA, &[&pixels], 0, graphics_context)
+    ///         .map(|texture| texture.disable())
+    /// });
+    /// ```
+    ///
+    /// Entering the context from a thread other than the graphics thread blocks until the
+    /// graphics thread finishes rendering the current frame, and deadlocks if the graphics
+    /// thread in turn waits for something held by the calling thread, such as a lock shared
+    /// with `VideoRenderSource::video_render`. Prefer passing the data to the callbacks which
+    /// already run within the context, e.g. through a channel polled in `video_render`, and
+    /// only use this function where that is not possible.
+    ///
+    /// If called within the context already, `f` is run immediately on the calling thread.
+    /// Otherwise, as the context is bound to the thread which entered it, `f` runs on a
+    /// separate thread. The timeout only applies to entering the context, once `f` is called,
+    /// its result is awaited.
+    ///
+    /// After a timeout, the separate thread is leaked, blocked until the context is released
+    /// by its current holder, after which it leaves the context without calling `f`. Calling
+    /// this function repeatedly while the context is held accumulates such blocked threads.
+    pub fn try_enter_with_timeout<R: Send + 'static>(
+        timeout: Duration,
+        f: impl FnOnce(&GraphicsContext) -> R + Send + 'static,
+    ) -> Option<R> {
+        if let Some(graphics_context) = Self::get_current() {
+            // Entering from a separate thread would wait for the calling thread to leave.
+            return Some(f(&graphics_context));
+        }
+
+        const WAITING: u8 = 0;
+        const STARTED: u8 = 1;
+        const CANCELLED: u8 = 2;
+
+        let state = Arc::new(AtomicU8::new(WAITING));
+        let (sender, receiver) = mpsc::channel();
+
+        {
+            let state = state.clone();
+
+            std::thread::spawn(move || {
+                let graphics_context = GraphicsContext::try_enter();
+
+                if state.compare_exchange(WAITING, STARTED, Ordering::AcqRel, Ordering::Acquire).is_err() {
+                    return;
+                }
+
+                let _ = sender.send(graphics_context.ok().map(|graphics_context| f(&graphics_context)));
+            });
+        }
+
+        match receiver.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(_) => {
+                if state.compare_exchange(WAITING, CANCELLED, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+                    None
+                } else {
+                    // The context was entered right after the timeout, so `f` is already running.
+                    receiver.recv().ok().flatten()
+                }
+            }
+        }
+    }
+
+    /// The name of the graphics API, e.g. `OpenGL` or `Direct3D 11`.
+    pub fn device_name(&self) -> String {
+        unsafe {
+            let name = gs_get_device_name();
+
+            if name.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(name).to_string_lossy().into_owned()
+            }
+        }
+    }
+
+    pub fn device_type(&self) -> GraphicsDeviceType {
+        match unsafe { gs_get_device_type() } as u32 {
+            GS_DEVICE_OPENGL => GraphicsDeviceType::OpenGL,
+            GS_DEVICE_DIRECT3D_11 => GraphicsDeviceType::Direct3D11,
+            _ => GraphicsDeviceType::Unknown,
+        }
+    }
+
+    /// The largest width and height of textures that can safely be created. Creating larger
+    /// textures fails.
+    ///
+    /// OBS does not expose the limit of the device, so this is a conservative estimate:
+    /// 16384 for Direct3D 11, guaranteed by the feature level OBS requires, and 8192 for
+    /// OpenGL and unknown devices, supported by virtually all hardware capable of running OBS.
+    pub fn max_texture_size(&self) -> u32 {
+        match self.device_type() {
+            GraphicsDeviceType::Direct3D11 => 16384,
+            GraphicsDeviceType::OpenGL | GraphicsDeviceType::Unknown => 8192,
+        }
+    }
+
+    /// Whether textures of the `NV12` format are supported.
+    pub fn nv12_available(&self) -> bool {
+        unsafe { gs_nv12_available() }
+    }
+
+    /// Begins a named GPU scope, see `GpuMarker`.
+    pub fn marker(&self, name: &'static CStr) -> GpuMarker<'_> {
+        GpuMarker::new(name, self)
+    }
+
+    /// Clears the current render target with the `color`, and the depth and stencil buffers,
+    /// if values for them are specified.
+    ///
+    /// Note: must be called while a render target is bound, e.g. within `TextureRender::render`.
+    pub fn clear(&self, color: Color, depth: Option<f32>, stencil: Option<u8>) {
+        let mut flags = GS_CLEAR_COLOR;
+
+        if depth.is_some() {
+            flags |= GS_CLEAR_DEPTH;
+        }
+
+        if stencil.is_some() {
+            flags |= GS_CLEAR_STENCIL;
+        }
+
+        let color = vec4 {
+            __bindgen_anon_1: vec4__bindgen_ty_1 { ptr: color.0 },
+        };
+
+        unsafe {
+            debug_assert!(
+                !gs_get_render_target().is_null(),
+                "`GraphicsContext::clear` called without a render target bound."
+            );
+
+            gs_clear(flags, &color, depth.unwrap_or(1.0), stencil.unwrap_or(0));
+        }
+    }
+
+    /// Submits all pending GPU commands, so that their results can be read back in the same
+    /// frame, e.g. when mapping a `StageSurface` right after staging a texture rendered to:
+    ///
+    /// ```rs
+    /// stage_surface.stage(&texture);
+    /// graphics_context.flush();
+    ///
+    /// if let Some(mapped) = stage_surface.map() {
+    ///     // ...
+    /// }
+    /// ```
+    ///
+    /// Flushing, and then mapping, stalls the CPU until the GPU catches up, which can noticeably
+    /// increase the render time of every frame. Where a frame of latency is acceptable, map the
+    /// surface staged in the previous frame instead.
+    pub fn flush(&self) {
+        unsafe {
+            gs_flush();
+        }
+    }
+
+    /// The width and height of the current render target, or of the current swap chain, if
+    /// no render target is bound, e.g. for a source drawing custom geometry to size it to the
+    /// actual output. Returns `[0, 0]` if neither is bound.
+    pub fn get_current_render_target_size(&self) -> [u32; 2] {
+        unsafe {
+            let target = gs_get_render_target();
+
+            if target.is_null() {
+                [gs_get_width(), gs_get_height()]
+            } else {
+                [gs_texture_get_width(target), gs_texture_get_height(target)]
+            }
+        }
+    }
+
+    /// Sets up an orthographic projection with the origin at the top left corner, mapping
+    /// one unit to one pixel of a target of the `size`, e.g. the one returned by
+    /// `get_current_render_target_size`.
+    pub fn set_ortho(&self, [width, height]: [u32; 2]) {
+        unsafe {
+            gs_ortho(0.0, width as f32, 0.0, height as f32, -100.0, 100.0);
+        }
+    }
+
+    /// Draws the `texture` as a sprite of the `size`, using all passes of the `technique` of
+    /// the `effect`, with the texture bound to its `image` parameter. If either component of
+    /// the `size` is 0, the size of the texture is used for it.
+    ///
+    /// The other parameters of the effect must be set beforehand. Returns `false` if the effect
+    /// has no such technique.
+    ///
+    /// ```rs
+    /// let effect = data.effect.as_enabled(&graphics_context);
+    ///
+    /// data.target.render([width, height], Color([0.0; 4]), &graphics_context, |graphics_context| {
+    ///     graphics_context.draw_effect(&effect, cstr!("Draw"), &input, [width, height]);
+    /// });
+    /// ```
+    pub fn draw_effect(
+        &self,
+        effect: &GraphicsEffect,
+        technique: &CStr,
+        texture: &Texture,
+        [width, height]: [u32; 2],
+    ) -> bool {
+        if effect.get_technique_by_name(technique).is_none() {
+            return false;
+        }
+
+        unsafe {
+            let image = gs_effect_get_param_by_name(effect.as_ptr(), cstr!("image").as_ptr());
+
+            if !image.is_null() {
+                gs_effect_set_texture(image, texture.inner() as *mut _);
+            }
+
+            while gs_effect_loop(effect.as_ptr(), technique.as_ptr()) {
+                gs_draw_sprite(texture.inner() as *mut _, 0, width, height);
+            }
+        }
+
+        true
+    }
+}
+
+/// Brackets a named scope of rendering commands, so that the render passes of a plugin are
+/// labeled in GPU captures (e.g. RenderDoc) and measured by the OBS profiler. The scope ends
+/// when the marker is dropped.
+///
+/// ```rs
+/// let _marker = graphics_context.marker(cstr!("blur: horizontal pass"));
+/// // Rendering commands
+/// ```
+///
+/// When the renderer does not support debug markers, OBS ignores them, so markers may be left
+/// in release builds. The profiler and debug marker functions are looked up at runtime, see
+/// `try_symbol!`, and the respective part of the marker does nothing if libobs lacks them.
+pub struct GpuMarker<'a> {
+    name: &'static CStr,
+    __marker: PhantomData<&'a GraphicsContext>,
+}
+
+impl<'a> GpuMarker<'a> {
+    /// The color used when none is specified, an opaque white.
+    pub const DEFAULT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+    /// The name must be `'static`, because the OBS profiler keeps a reference to it.
+    pub fn new(name: &'static CStr, context: &'a GraphicsContext) -> Self {
+        Self::with_color(name, Self::DEFAULT_COLOR, context)
+    }
+
+    /// Creates a marker displayed with the RGBA `color` in GPU captures.
+    pub fn with_color(name: &'static CStr, color: [f32; 4], _context: &'a GraphicsContext) -> Self {
+        let profile = profile_functions();
+        let debug_marker = debug_marker_functions();
+
+        unsafe {
+            if let Some((profile_start, _)) = profile {
+                profile_start(name.as_ptr());
+            }
+
+            if let Some((debug_marker_begin, _)) = debug_marker {
+                debug_marker_begin(color.as_ptr(), name.as_ptr());
+            }
+        }
+
+        Self {
+            name,
+            __marker: PhantomData,
+        }
+    }
+}
+
+impl<'a> Drop for GpuMarker<'a> {
+    fn drop(&mut self) {
+        let profile = profile_functions();
+        let debug_marker = debug_marker_functions();
+
+        unsafe {
+            if let Some((_, debug_marker_end)) = debug_marker {
+                debug_marker_end();
+            }
+
+            if let Some((_, profile_end)) = profile {
+                profile_end(self.name.as_ptr());
+            }
+        }
+    }
+}
+
+type ProfileScopeFn = unsafe extern "C" fn(*const c_char);
+type DebugMarkerBeginFn = unsafe extern "C" fn(*const f32, *const c_char);
+type DebugMarkerEndFn = unsafe extern "C" fn();
+
+/// `profile_start` and `profile_end`, only if both are available, so that scopes stay balanced.
+fn profile_functions() -> Option<(ProfileScopeFn, ProfileScopeFn)> {
+    crate::try_symbol!(profile_start: ProfileScopeFn).zip(crate::try_symbol!(profile_end: ProfileScopeFn))
+}
+
+/// `gs_debug_marker_begin` and `gs_debug_marker_end`, only if both are available.
+fn debug_marker_functions() -> Option<(DebugMarkerBeginFn, DebugMarkerEndFn)> {
+    crate::try_symbol!(gs_debug_marker_begin: DebugMarkerBeginFn)
+        .zip(crate::try_symbol!(gs_debug_marker_end: DebugMarkerEndFn))
 }
 
 impl Drop for GraphicsContext {
     fn drop(&mut self) {
         if self.drop {
+            // Free the resources which could not be freed while the context was unavailable,
+            // before leaving it.
+            GraphicsResourceGraveyard::global().drain(self);
+
             unsafe {
                 obs_leave_graphics();
             }
@@ -65,6 +446,109 @@ impl Drop for GraphicsContext {
 pub type GraphicsContextDependentEnabled<'a, T> = ContextDependent<T, GraphicsContext, Enabled<'a, GraphicsContext>>;
 pub type GraphicsContextDependentDisabled<T> = ContextDependent<T, GraphicsContext, Disabled>;
 
+impl<T: Send + 'static> ContextDependent<T, GraphicsContext, Disabled> {
+    /// Drops the value immediately, if called within the graphics context, or buries it in the
+    /// global `GraphicsResourceGraveyard` otherwise, to be freed later.
+    ///
+    /// Unlike `Drop::drop`, this function never panics, so it is the preferred way of disposing
+    /// of graphics resources in `Drop` implementations of plugin data.
+    pub fn drop_or_bury(self) {
+        GraphicsResourceGraveyard::global().bury(self);
+    }
+}
+
+type GraveyardEntry = Box<dyn FnOnce(&GraphicsContext) + Send>;
+
+/// A queue of disabled graphics resources which could not be freed, because the graphics
+/// context was unavailable at the time, e.g. when plugin data is destroyed on a thread which
+/// cannot enter it. The resources are freed the next time the graveyard is drained.
+///
+/// The global graveyard is drained automatically whenever the graphics context entered by
+/// `GraphicsContext::enter` is left, and before every `video_render` callback. Graveyards
+/// created with `new` must be drained manually, with `drain`.
+///
+/// ```rs
+/// struct Data {
+///     texture: Option<GraphicsContextDependentDisabled<Texture>>,
+/// }
+///
+/// impl Drop for Data {
+///     fn drop(&mut self) {
+///         if let Some(texture) = self.texture.take() {
+///             texture.drop_or_bury();
+///         }
+///     }
+/// }
+/// ```
+pub struct GraphicsResourceGraveyard {
+    entries: Mutex<Vec<GraveyardEntry>>,
+}
+
+static GLOBAL_GRAVEYARD: GraphicsResourceGraveyard = GraphicsResourceGraveyard::new();
+
+impl GraphicsResourceGraveyard {
+    pub const fn new() -> Self {
+        Self {
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The graveyard drained automatically by the wrapper.
+    pub fn global() -> &'static Self {
+        &GLOBAL_GRAVEYARD
+    }
+
+    fn entries(&self) -> MutexGuard<'_, Vec<GraveyardEntry>> {
+        // A panicking destructor does not corrupt the queue, as entries are removed before
+        // being run.
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Drops the `resource` immediately, if called within the graphics context, or queues it
+    /// to be dropped during the next `drain` otherwise.
+    ///
+    /// The context is never entered, as entering it from a thread other than the graphics
+    /// thread blocks until the current frame is rendered.
+    pub fn bury<T: Send + 'static>(&self, resource: GraphicsContextDependentDisabled<T>) {
+        match GraphicsContext::get_current() {
+            Some(context) => drop(resource.enable(&context)),
+            None => self.queue(resource),
+        }
+    }
+
+    fn queue<T: Send + 'static>(&self, resource: GraphicsContextDependentDisabled<T>) {
+        self.entries().push(Box::new(move |context: &GraphicsContext| {
+            drop(resource.enable(context));
+        }));
+    }
+
+    /// Drops all queued resources within the graphics `context`.
+    pub fn drain(&self, context: &GraphicsContext) {
+        // Take the entries out first, so that the lock is not held while the destructors run,
+        // which may bury further resources.
+        let entries = std::mem::take(&mut *self.entries());
+
+        for entry in entries {
+            (entry)(context);
+        }
+    }
+
+    /// The number of resources waiting to be dropped.
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+}
+
+impl Default for GraphicsResourceGraveyard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// A context used to store source filter data to be submitted at the end of the processing.
 pub struct FilterContext {
     graphics: GraphicsContext,
@@ -129,3 +613,83 @@ impl ContextCarrier<GraphicsContext> for FilterContext {
 
 pub type FilterContextDependentEnabled<'a, T> = ContextDependent<T, FilterContext, Enabled<'a, FilterContext>>;
 pub type FilterContextDependentDisabled<T> = ContextDependent<T, FilterContext, Disabled>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    struct Resource(Arc<AtomicUsize>);
+
+    impl Drop for Resource {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// A handle standing in for the graphics context, which is never left.
+    fn fake_context() -> GraphicsContext {
+        GraphicsContext {
+            inner: std::ptr::null_mut(),
+            drop: false,
+        }
+    }
+
+    #[test]
+    fn off_thread_drop_is_deferred_until_drained() {
+        let graveyard = Arc::new(GraphicsResourceGraveyard::new());
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let resources = (0..3)
+            .map(|_| ContextDependent::new(Resource(dropped.clone()), &fake_context()).disable())
+            .collect::<Vec<_>>();
+
+        {
+            let graveyard = graveyard.clone();
+
+            std::thread::spawn(move || {
+                for resource in resources {
+                    graveyard.queue(resource);
+                }
+            })
+            .join()
+            .unwrap();
+        }
+
+        assert_eq!(graveyard.len(), 3);
+        assert_eq!(dropped.load(Ordering::SeqCst), 0);
+
+        graveyard.drain(&fake_context());
+
+        assert!(graveyard.is_empty());
+        assert_eq!(dropped.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn resources_buried_while_draining_are_kept_for_the_next_drain() {
+        struct Burying(Arc<GraphicsResourceGraveyard>, Option<GraphicsContextDependentDisabled<Resource>>);
+
+        impl Drop for Burying {
+            fn drop(&mut self) {
+                if let Some(resource) = self.1.take() {
+                    self.0.queue(resource);
+                }
+            }
+        }
+
+        let graveyard = Arc::new(GraphicsResourceGraveyard::new());
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let inner = ContextDependent::new(Resource(dropped.clone()), &fake_context()).disable();
+        let outer = Burying(graveyard.clone(), Some(inner));
+
+        graveyard.queue(ContextDependent::new(outer, &fake_context()).disable());
+        graveyard.drain(&fake_context());
+
+        assert_eq!(graveyard.len(), 1);
+        assert_eq!(dropped.load(Ordering::SeqCst), 0);
+
+        graveyard.drain(&fake_context());
+
+        assert!(graveyard.is_empty());
+        assert_eq!(dropped.load(Ordering::SeqCst), 1);
+    }
+}
@@ -3,9 +3,12 @@ use std::mem;
 use std::mem::MaybeUninit;
 use std::borrow::Cow;
 use std::ffi::{CStr, CString};
-use std::os::raw::{c_void, c_char};
+use std::os::raw::{c_void, c_char, c_int};
 use std::marker::PhantomData;
+use std::path::Path;
 use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
 use core::convert::TryFrom;
 use obs_sys::{
     bfree,
@@ -45,6 +48,8 @@ use obs_sys::{
     gs_effect_get_param_by_idx,
     gs_effect_get_default_val_size,
     gs_effect_get_default_val,
+    gs_effect_get_val_size,
+    gs_effect_get_val,
     vec3, vec4,
     gs_effect_set_bool,
     gs_effect_set_float,
@@ -55,16 +60,26 @@ use obs_sys::{
     gs_effect_set_val,
     gs_effect_set_texture,
     gs_effect_set_matrix4,
+    gs_effect_get_technique, gs_technique_t,
 };
 use paste::item;
 use cstr::cstr;
 use crate::context::*;
+use crate::video::VideoFormatKind;
 
+mod animated;
 mod context;
+mod stagesurf;
+mod texrender;
 mod texture;
+mod transform;
 
+pub use animated::*;
 pub use context::*;
+pub use stagesurf::*;
+pub use texrender::*;
 pub use texture::*;
+pub use transform::*;
 
 pub mod shader_param_types {
     use super::*;
@@ -90,9 +105,75 @@ pub mod shader_param_types {
             }
         }
 
+        /// Reads the value currently set to the parameter, rather than its default value.
+        /// Returns `None` if no value has been set yet, or if the parameter type does not
+        /// support reading back values (textures).
+        ///
+        /// May only be called in a graphics context.
+        unsafe fn get_param_value_current(param: *mut gs_eparam_t) -> Option<Self::RustType> {
+            get_param_value_current_as::<Self::RustType>(param)
+        }
+
         fn corresponding_enum_variant() -> ShaderParamTypeKind;
     }
 
+    /// Reads the value currently set to the parameter as a `V`, which must be of the same size
+    /// as the value stored by OBS.
+    ///
+    /// May only be called in a graphics context.
+    unsafe fn get_param_value_current_as<V>(param: *mut gs_eparam_t) -> Option<V> {
+        let size = gs_effect_get_val_size(param) as usize;
+
+        if size != std::mem::size_of::<V>() {
+            return None;
+        }
+
+        let ptr = gs_effect_get_val(param);
+
+        if ptr.is_null() {
+            None
+        } else {
+            // `gs_effect_get_val` returns a copy of the value, which needs to be freed.
+            let value = std::ptr::read_unaligned(ptr as *const V);
+            bfree(ptr);
+            Some(value)
+        }
+    }
+
+    /// A value which can be set to a parameter of the type `T`, see
+    /// `GraphicsEffectParamTyped::set_param_value`.
+    ///
+    /// Implemented for the `RustType` of every parameter type, and for the vector types,
+    /// so that values computed using vector math can be set without converting them to arrays.
+    pub trait ShaderParamValue<T: ShaderParamType> {
+        /// May only be called in a graphics context.
+        unsafe fn set_to_param(&self, param: *mut gs_eparam_t, context: &FilterContext);
+    }
+
+    impl<T: ShaderParamType> ShaderParamValue<T> for <T as ShaderParamType>::RustType {
+        unsafe fn set_to_param(&self, param: *mut gs_eparam_t, context: &FilterContext) {
+            T::set_param_value(param, self, context);
+        }
+    }
+
+    impl ShaderParamValue<ShaderParamTypeVec2> for Vec2 {
+        unsafe fn set_to_param(&self, param: *mut gs_eparam_t, _context: &FilterContext) {
+            gs_effect_set_vec2(param, &self.raw);
+        }
+    }
+
+    impl ShaderParamValue<ShaderParamTypeVec3> for Vec3 {
+        unsafe fn set_to_param(&self, param: *mut gs_eparam_t, _context: &FilterContext) {
+            gs_effect_set_vec3(param, &self.raw);
+        }
+    }
+
+    impl ShaderParamValue<ShaderParamTypeVec4> for Vec4 {
+        unsafe fn set_to_param(&self, param: *mut gs_eparam_t, _context: &FilterContext) {
+            gs_effect_set_vec4(param, &self.raw);
+        }
+    }
+
     pub struct ShaderParamTypeBool;
     impl ShaderParamType for ShaderParamTypeBool {
         type RustType = bool;
@@ -101,6 +182,11 @@ pub mod shader_param_types {
             gs_effect_set_bool(param, *value);
         }
 
+        /// OBS stores booleans as 4-byte integers.
+        unsafe fn get_param_value_current(param: *mut gs_eparam_t) -> Option<Self::RustType> {
+            get_param_value_current_as::<i32>(param).map(|value| value != 0)
+        }
+
         fn corresponding_enum_variant() -> ShaderParamTypeKind {
             ShaderParamTypeKind::Bool
         }
@@ -254,7 +340,12 @@ pub mod shader_param_types {
             );
         }
 
-        unsafe fn get_param_value_default<'a>(param: *mut gs_eparam_t) -> Option<&'a Self::RustType> {
+        unsafe fn get_param_value_default<'a>(_param: *mut gs_eparam_t) -> Option<&'a Self::RustType> {
+            None
+        }
+
+        /// Textures cannot be read back from the parameter.
+        unsafe fn get_param_value_current(_param: *mut gs_eparam_t) -> Option<Self::RustType> {
             None
         }
 
@@ -325,26 +416,239 @@ impl ShaderParamTypeKind {
 
 pub struct GraphicsEffect {
     raw: *mut gs_effect_t,
+    name: String,
+    technique_names: Vec<String>,
+    shared: Arc<GraphicsEffectShared>,
+}
+
+/// The state of an effect shared with the parameters retrieved from it, so that the parameters
+/// are refetched once the effect is reloaded, and are not used once the effect is dropped.
+struct GraphicsEffectShared {
+    /// The current effect, or null once the effect has been dropped.
+    raw: AtomicPtr<gs_effect_t>,
+    /// Incremented whenever `raw` changes, invalidating the parameters of the previous effect.
+    generation: AtomicUsize,
+}
+
+impl GraphicsEffectShared {
+    fn new(raw: *mut gs_effect_t) -> Arc<Self> {
+        Arc::new(Self {
+            raw: AtomicPtr::new(raw),
+            generation: AtomicUsize::new(0),
+        })
+    }
+
+    /// Replaces the effect, must be called before the previous effect is destroyed.
+    fn replace(&self, raw: *mut gs_effect_t) {
+        self.raw.store(raw, Ordering::SeqCst);
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Finds the names of the techniques declared in the source of an effect, as OBS does not
+/// provide a way to enumerate them.
+fn parse_technique_names(source: &str) -> Vec<String> {
+    let mut code = String::with_capacity(source.len());
+    let mut rest = source;
+
+    // Strip comments, so that commented out techniques are not listed
+    while !rest.is_empty() {
+        if rest.starts_with("//") {
+            rest = rest.find('\n').map(|end| &rest[end..]).unwrap_or("");
+        } else if rest.starts_with("/*") {
+            rest = rest[2..].find("*/").map(|end| &rest[end + 4..]).unwrap_or("");
+            code.push(' ');
+        } else {
+            let c = rest.chars().next().unwrap();
+            code.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    let mut words = code
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty());
+    let mut names = Vec::new();
+
+    while let Some(word) = words.next() {
+        if word == "technique" {
+            if let Some(name) = words.next() {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    names
+}
+
+/// Compiles the effect, returning the error message of the compiler on failure, if any.
+unsafe fn create_raw_effect(value: &CStr, name: &CStr) -> Result<*mut gs_effect_t, Option<Cow<'static, str>>> {
+    let mut error_string_raw: *mut c_char = std::ptr::null_mut();
+    let raw = gs_effect_create(value.as_ptr(), name.as_ptr(), &mut error_string_raw as *mut *mut c_char);
+
+    if raw.is_null() {
+        if error_string_raw == std::ptr::null_mut() {
+            Err(None)
+        } else {
+            let error_string = CStr::from_ptr(error_string_raw).to_string_lossy().to_string();
+
+            bfree(error_string_raw as *mut _);
+
+            Err(Some(Cow::Owned(error_string)))
+        }
+    } else {
+        Ok(raw)
+    }
+}
+
+unsafe fn get_effect_technique_names(raw: *mut gs_effect_t, value: &CStr) -> Vec<String> {
+    parse_technique_names(&value.to_string_lossy())
+        .into_iter()
+        .filter(|technique_name| {
+            CString::new(technique_name.as_str())
+                .map(|technique_name| !gs_effect_get_technique(raw, technique_name.as_ptr()).is_null())
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+fn read_effect_file(path: &Path) -> Result<(CString, CString), Cow<'static, str>> {
+    let value = std::fs::read_to_string(path)
+        .map_err(|error| Cow::Owned(format!("Could not read the effect file `{}`: {}", path.display(), error)))?;
+    let value = CString::new(value)
+        .map_err(|_| Cow::Borrowed("The effect file contains a nul character."))?;
+    let name = CString::new(path.to_string_lossy().into_owned())
+        .map_err(|_| Cow::Borrowed("The path of the effect file contains a nul character."))?;
+
+    Ok((value, name))
 }
 
 impl GraphicsEffect {
     pub fn from_effect_string<'a>(value: &CStr, name: &CStr, context: &'a GraphicsContext) -> Result<GraphicsContextDependentEnabled<'a, Self>, Option<Cow<'static, str>>> {
         unsafe {
-            let mut error_string_raw: *mut c_char = std::ptr::null_mut();
-            let raw = gs_effect_create(value.as_ptr(), name.as_ptr(), &mut error_string_raw as *mut *mut c_char);
+            let raw = create_raw_effect(value, name)?;
+            let name = name.to_string_lossy().into_owned();
+            let technique_names = get_effect_technique_names(raw, value);
 
-            if raw.is_null() {
-                if error_string_raw == std::ptr::null_mut() {
-                    Err(None)
-                } else {
-                    let error_string = CStr::from_ptr(error_string_raw).to_string_lossy().to_string();
+            let shared = GraphicsEffectShared::new(raw);
 
-                    bfree(error_string_raw as *mut _);
+            Ok(ContextDependent::new(Self { raw, name, technique_names, shared }, context))
+        }
+    }
 
-                    Err(Some(Cow::Owned(error_string)))
-                }
+    /// Loads and compiles the effect file at `path`. The path is used as the name of the
+    /// effect.
+    pub fn from_file<'a>(path: impl AsRef<Path>, context: &'a GraphicsContext) -> Result<GraphicsContextDependentEnabled<'a, Self>, Option<Cow<'static, str>>> {
+        let (value, name) = read_effect_file(path.as_ref()).map_err(Some)?;
+
+        Self::from_effect_string(&value, &name, context)
+    }
+
+    /// Recompiles the effect from the effect file at `path`, so that changes to the shader
+    /// can be tested without restarting OBS, e.g. when a button of the properties is clicked.
+    ///
+    /// If the file cannot be read or compiled, or if a parameter of the current effect is
+    /// missing from the new effect or has changed its type, the error is logged and returned,
+    /// and the current effect is kept.
+    ///
+    /// On success, the parameters retrieved from the previous effect are refetched from the new
+    /// effect by their names the next time they are used.
+    pub fn reload_from_file<'a>(
+        self: &mut GraphicsContextDependentEnabled<'a, Self>,
+        path: impl AsRef<Path>,
+    ) -> Result<(), GraphicsError> {
+        let result = self.reload_from_file_inner(path.as_ref());
+
+        if let Err(error) = &result {
+            crate::warning!("{}", error);
+        }
+
+        result
+    }
+
+    fn reload_from_file_inner<'a>(
+        self: &mut GraphicsContextDependentEnabled<'a, Self>,
+        path: &Path,
+    ) -> Result<(), GraphicsError> {
+        let reload_failed = |reason: Cow<'static, str>| GraphicsError::EffectReloadFailed {
+            effect: self.name.clone(),
+            reason: reason.into_owned(),
+        };
+
+        let (value, name) = read_effect_file(path).map_err(reload_failed)?;
+
+        unsafe {
+            let raw = create_raw_effect(&value, &name).map_err(|error| {
+                reload_failed(error.unwrap_or(Cow::Borrowed("The effect could not be compiled.")))
+            })?;
+
+            // Ensure that the parameters of the current effect can be refetched.
+            let missing_param = self.params_iter().find(|param| {
+                CString::new(param.name())
+                    .map(|name| {
+                        let new_param = gs_effect_get_param_by_name(raw, name.as_ptr());
+
+                        new_param.is_null()
+                            || GraphicsEffectParam::from_raw(new_param, self.context()).param_type() != param.param_type()
+                    })
+                    .unwrap_or(true)
+            });
+
+            if let Some(param) = missing_param {
+                gs_effect_destroy(raw);
+
+                return Err(reload_failed(Cow::Owned(format!(
+                    "The parameter `{}` is missing from the new effect or has changed its type.",
+                    param.name(),
+                ))));
+            }
+
+            let previous = self.raw;
+
+            self.shared.replace(raw);
+            self.raw = raw;
+            gs_effect_destroy(previous);
+
+            self.technique_names = get_effect_technique_names(raw, &value);
+        }
+
+        Ok(())
+    }
+
+    /// The name the effect was created with.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The names of the techniques of the effect, in the order they are declared in.
+    ///
+    /// OBS does not provide a way to enumerate the techniques of an effect, so they are found
+    /// by scanning the source of the effect for `technique` declarations. Techniques declared
+    /// in included files or generated by preprocessor macros are not listed.
+    pub fn technique_names(&self) -> &[String] {
+        &self.technique_names
+    }
+
+    pub fn get_technique_by_index(&self, index: usize) -> Option<GraphicsEffectTechnique<'_>> {
+        self.technique_names.get(index).and_then(|name| {
+            let name = CString::new(name.as_str()).ok()?;
+
+            self.get_technique_by_name(&name)
+        })
+    }
+
+    pub fn get_technique_by_name(&self, name: &CStr) -> Option<GraphicsEffectTechnique<'_>> {
+        unsafe {
+            let raw = gs_effect_get_technique(self.raw, name.as_ptr());
+
+            if raw.is_null() {
+                None
             } else {
-                Ok(ContextDependent::new(Self { raw }, context))
+                Some(GraphicsEffectTechnique {
+                    raw,
+                    name: name.to_string_lossy().into_owned(),
+                    __marker: PhantomData,
+                })
             }
         }
     }
@@ -362,7 +666,7 @@ impl GraphicsEffect {
         unsafe {
             let pointer = gs_effect_get_param_by_idx(self.raw, index as size_t);
             if !pointer.is_null() {
-                Some(GraphicsEffectParam::from_raw(pointer, self.context()))
+                Some(GraphicsEffectParam::from_raw_in_effect(pointer, self, self.context()))
             } else {
                 None
             }
@@ -376,7 +680,7 @@ impl GraphicsEffect {
         unsafe {
             let pointer = gs_effect_get_param_by_name(self.raw, name.as_ptr());
             if !pointer.is_null() {
-                Some(GraphicsEffectParam::from_raw(pointer, self.context()))
+                Some(GraphicsEffectParam::from_raw_in_effect(pointer, self, self.context()))
             } else {
                 None
             }
@@ -415,6 +719,88 @@ impl GraphicsEffect {
         } as EffectParamIterator<'a, 'b>
     }
 
+    /// Retrieves the parameter `name`, ensuring it is of the type `T`.
+    pub fn require_param<'a, T: ShaderParamType>(
+        self: &GraphicsContextDependentEnabled<'a, Self>,
+        name: &CStr,
+    ) -> Result<GraphicsContextDependentEnabled<'a, GraphicsEffectParamTyped<T>>, GraphicsError> {
+        let param = self.require_params(&[(name, T::corresponding_enum_variant())])?
+            .pop()
+            .expect("A parameter went unexpectedly missing.");
+
+        Ok(param.downcast::<T>().expect("The type of the parameter has already been checked."))
+    }
+
+    /// Retrieves all of the listed parameters, ensuring each of them is of the specified type.
+    /// The parameters are returned in the order they were listed in.
+    ///
+    /// To get typed parameters directly, see the `require_params!` macro.
+    pub fn require_params<'a>(
+        self: &GraphicsContextDependentEnabled<'a, Self>,
+        params: &[(&CStr, ShaderParamTypeKind)],
+    ) -> Result<Vec<GraphicsContextDependentEnabled<'a, GraphicsEffectParam>>, GraphicsError> {
+        params.iter().map(|(name, expected)| {
+            let param = self.get_param_by_name(name).ok_or_else(|| {
+                GraphicsError::MissingEffectParam {
+                    effect: self.name.clone(),
+                    name: name.to_string_lossy().into_owned(),
+                }
+            })?;
+
+            if param.param_type() != *expected {
+                return Err(GraphicsError::EffectParamTypeMismatch {
+                    effect: self.name.clone(),
+                    name: param.name().to_string(),
+                    expected: *expected,
+                    actual: param.param_type(),
+                });
+            }
+
+            Ok(param)
+        }).collect()
+    }
+
+    /// Sets the values of multiple parameters at once, e.g. from a `HashMap` of values driven by
+    /// data rather than known at compile time.
+    ///
+    /// All parameters that can be set are set, even if some of them fail. The parameters that
+    /// are missing from the effect, or whose types do not match the provided value, are
+    /// returned as errors.
+    pub fn set_params<'a, 'b>(
+        self: &GraphicsContextDependentEnabled<'a, Self>,
+        params: impl IntoIterator<Item = (&'b CStr, ParamValue<'b>)>,
+        context: &FilterContext,
+    ) -> Result<(), Vec<GraphicsEffectParamError>> {
+        let mut errors = Vec::new();
+
+        for (name, value) in params {
+            match self.get_param_by_name(name) {
+                None => errors.push(GraphicsEffectParamError::Missing {
+                    name: name.to_string_lossy().into_owned(),
+                }),
+                Some(param) => {
+                    if param.param_type() == value.kind() {
+                        unsafe {
+                            value.set_param_value(param.raw, context);
+                        }
+                    } else {
+                        errors.push(GraphicsEffectParamError::TypeMismatch {
+                            name: param.name().to_string(),
+                            expected: value.kind(),
+                            actual: param.param_type(),
+                        });
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     /// # Safety
     /// Returns a mutable pointer to an effect which if modified could cause UB.
     pub unsafe fn as_ptr(&self) -> *mut gs_effect_t {
@@ -422,8 +808,29 @@ impl GraphicsEffect {
     }
 }
 
+/// A technique of a `GraphicsEffect`, selecting the shaders used to draw with the effect.
+pub struct GraphicsEffectTechnique<'a> {
+    raw: *mut gs_technique_t,
+    name: String,
+    __marker: PhantomData<&'a GraphicsEffect>,
+}
+
+impl<'a> GraphicsEffectTechnique<'a> {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// # Safety
+    /// Returns a mutable pointer to a technique which if modified could cause UB.
+    pub unsafe fn as_ptr(&self) -> *mut gs_technique_t {
+        self.raw
+    }
+}
+
 impl Drop for GraphicsEffect {
     fn drop(&mut self) {
+        self.shared.replace(std::ptr::null_mut());
+
         unsafe {
             gs_effect_destroy(self.raw);
         }
@@ -434,6 +841,8 @@ pub struct GraphicsEffectParam {
     raw: *mut gs_eparam_t,
     name: String,
     shader_type: ShaderParamTypeKind,
+    /// The effect the parameter was retrieved from, and its generation at that time, if known.
+    effect: Option<(Arc<GraphicsEffectShared>, usize)>,
 }
 
 impl GraphicsEffectParam {
@@ -454,11 +863,78 @@ impl GraphicsEffectParam {
                 raw,
                 shader_type,
                 name,
+                effect: None,
             },
             context,
         )
     }
 
+    /// Creates the parameter of the `effect`, so that it is refetched automatically when the
+    /// effect is reloaded.
+    unsafe fn from_raw_in_effect<'a>(
+        raw: *mut gs_eparam_t,
+        effect: &GraphicsEffect,
+        context: &'a GraphicsContext,
+    ) -> GraphicsContextDependentEnabled<'a, Self> {
+        let mut param = Self::from_raw(raw, context);
+        let shared = effect.shared.clone();
+        let generation = shared.generation.load(Ordering::SeqCst);
+
+        param.effect = Some((shared, generation));
+        param
+    }
+
+    /// The parameter of the current effect, looking it up again if the effect it was retrieved
+    /// from has since been reloaded. `None` if the effect has been dropped.
+    fn current_raw(&self) -> Option<*mut gs_eparam_t> {
+        let (shared, generation) = match &self.effect {
+            Some(effect) => effect,
+            None => return Some(self.raw),
+        };
+
+        if shared.generation.load(Ordering::SeqCst) == *generation {
+            return Some(self.raw);
+        }
+
+        let effect = shared.raw.load(Ordering::SeqCst);
+        let name = CString::new(self.name.as_str()).ok()?;
+
+        if effect.is_null() {
+            return None;
+        }
+
+        unsafe {
+            let raw = gs_effect_get_param_by_name(effect, name.as_ptr());
+
+            if raw.is_null() {
+                return None;
+            }
+
+            let mut info = gs_effect_param_info::default();
+            gs_effect_get_param_info(raw, &mut info);
+
+            if ShaderParamTypeKind::from_raw(info.type_) != self.shader_type {
+                return None;
+            }
+
+            Some(raw)
+        }
+    }
+
+    /// Like `current_raw`, but remembers the refetched parameter, so that it is only looked up
+    /// once per reload.
+    fn current_raw_mut(&mut self) -> Option<*mut gs_eparam_t> {
+        let raw = self.current_raw()?;
+
+        self.raw = raw;
+
+        if let Some((shared, generation)) = &mut self.effect {
+            *generation = shared.generation.load(Ordering::SeqCst);
+        }
+
+        Some(raw)
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -467,6 +943,55 @@ impl GraphicsEffectParam {
         self.shader_type
     }
 
+    /// Retrieves the parameter of the same name and type from the `effect`, e.g. to use the
+    /// parameter with a different instance of the same effect. Parameters are refetched
+    /// automatically after `GraphicsEffect::reload_from_file`. Returns `false` and keeps the
+    /// parameter unchanged if the effect has no such parameter.
+    pub fn refetch(&mut self, effect: &GraphicsEffect) -> bool {
+        let name = match CString::new(self.name.as_str()) {
+            Ok(name) => name,
+            Err(_) => return false,
+        };
+
+        unsafe {
+            let raw = gs_effect_get_param_by_name(effect.raw, name.as_ptr());
+
+            if raw.is_null() {
+                return false;
+            }
+
+            let mut info = gs_effect_param_info::default();
+            gs_effect_get_param_info(raw, &mut info);
+
+            if ShaderParamTypeKind::from_raw(info.type_) != self.shader_type {
+                return false;
+            }
+
+            self.raw = raw;
+            self.effect = Some((effect.shared.clone(), effect.shared.generation.load(Ordering::SeqCst)));
+        }
+
+        true
+    }
+
+    /// Sets the sampler used to sample the texture of this parameter during the next draw.
+    ///
+    /// OBS only respects the sampler for texture parameters, and resets it once the
+    /// parameters are uploaded, so it must be set every frame, before drawing. When setting
+    /// the texture manually, set the sampler first, or use
+    /// `GraphicsEffectParamTyped::set_param_value_with_sampler`.
+    pub fn set_next_sampler(
+        &mut self,
+        _context: &FilterContext,
+        value: &GraphicsSamplerState,
+    ) {
+        if let Some(raw) = self.current_raw_mut() {
+            unsafe {
+                gs_effect_set_next_sampler(raw, value.raw);
+            }
+        }
+    }
+
     pub fn downcast<'a, T: ShaderParamType>(self: GraphicsContextDependentEnabled<'a, Self>) -> Option<GraphicsContextDependentEnabled<'a, GraphicsEffectParamTyped<T>>> {
         if self.shader_type == <T as ShaderParamType>::corresponding_enum_variant() {
             Some(self.map(|inner| {
@@ -487,28 +1012,254 @@ pub struct GraphicsEffectParamTyped<T: ShaderParamType> {
 }
 
 impl<T: ShaderParamType> GraphicsEffectParamTyped<T> {
-    pub fn set_param_value(&mut self, value: &<T as ShaderParamType>::RustType, context: &FilterContext) {
-        unsafe {
-            <T as ShaderParamType>::set_param_value(self.inner.raw, value, context);
+    /// See `GraphicsEffectParam::refetch`.
+    pub fn refetch(&mut self, effect: &GraphicsEffect) -> bool {
+        self.inner.refetch(effect)
+    }
+
+    /// Sets the value of the parameter, either of its `RustType`, or of the corresponding
+    /// vector type, e.g. `Vec2` for `ShaderParamTypeVec2`.
+    ///
+    /// Does nothing if the effect the parameter was retrieved from has been dropped.
+    pub fn set_param_value<V: ShaderParamValue<T> + ?Sized>(&mut self, value: &V, context: &FilterContext) {
+        if let Some(raw) = self.inner.current_raw_mut() {
+            unsafe {
+                value.set_to_param(raw, context);
+            }
         }
     }
 
     pub fn get_param_value_default<'a>(&'a self) -> Option<&'a <T as ShaderParamType>::RustType> {
+        let raw = self.inner.current_raw()?;
+
+        unsafe {
+            <T as ShaderParamType>::get_param_value_default::<'a>(raw)
+        }
+    }
+
+    /// Reads the value currently set to the parameter, e.g. for debugging or for computing
+    /// derived parameters.
+    ///
+    /// Supported by all parameter types except for textures, for which `None` is always
+    /// returned. `None` is also returned if no value has been set to the parameter yet.
+    pub fn get_current_value(&self) -> Option<<T as ShaderParamType>::RustType> {
+        let raw = self.inner.current_raw()?;
+
         unsafe {
-            <T as ShaderParamType>::get_param_value_default::<'a>(self.inner.raw)
+            <T as ShaderParamType>::get_param_value_current(raw)
         }
     }
 }
 
+/// A value of any of the supported shader parameter types, used to set parameters without
+/// knowing their types at compile time.
+#[derive(Clone, Copy, Debug)]
+pub enum ParamValue<'a> {
+    Bool(bool),
+    Float(f32),
+    Int(i32),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+    IVec2([i32; 2]),
+    IVec3([i32; 3]),
+    IVec4([i32; 4]),
+    Mat4([[f32; 4]; 4]),
+    Texture(&'a Texture),
+}
+
+impl<'a> ParamValue<'a> {
+    pub fn kind(&self) -> ShaderParamTypeKind {
+        match self {
+            ParamValue::Bool(_) => ShaderParamTypeKind::Bool,
+            ParamValue::Float(_) => ShaderParamTypeKind::Float,
+            ParamValue::Int(_) => ShaderParamTypeKind::Int,
+            ParamValue::Vec2(_) => ShaderParamTypeKind::Vec2,
+            ParamValue::Vec3(_) => ShaderParamTypeKind::Vec3,
+            ParamValue::Vec4(_) => ShaderParamTypeKind::Vec4,
+            ParamValue::IVec2(_) => ShaderParamTypeKind::IVec2,
+            ParamValue::IVec3(_) => ShaderParamTypeKind::IVec3,
+            ParamValue::IVec4(_) => ShaderParamTypeKind::IVec4,
+            ParamValue::Mat4(_) => ShaderParamTypeKind::Mat4,
+            ParamValue::Texture(_) => ShaderParamTypeKind::Texture,
+        }
+    }
+
+    /// May only be called in a graphics context.
+    unsafe fn set_param_value(&self, param: *mut gs_eparam_t, context: &FilterContext) {
+        match self {
+            ParamValue::Bool(value) => ShaderParamTypeBool::set_param_value(param, value, context),
+            ParamValue::Float(value) => ShaderParamTypeFloat::set_param_value(param, value, context),
+            ParamValue::Int(value) => ShaderParamTypeInt::set_param_value(param, value, context),
+            ParamValue::Vec2(value) => ShaderParamTypeVec2::set_param_value(param, value, context),
+            ParamValue::Vec3(value) => ShaderParamTypeVec3::set_param_value(param, value, context),
+            ParamValue::Vec4(value) => ShaderParamTypeVec4::set_param_value(param, value, context),
+            ParamValue::IVec2(value) => ShaderParamTypeIVec2::set_param_value(param, value, context),
+            ParamValue::IVec3(value) => ShaderParamTypeIVec3::set_param_value(param, value, context),
+            ParamValue::IVec4(value) => ShaderParamTypeIVec4::set_param_value(param, value, context),
+            ParamValue::Mat4(value) => ShaderParamTypeMat4::set_param_value(param, value, context),
+            ParamValue::Texture(value) => ShaderParamTypeTexture::set_param_value(param, value, context),
+        }
+    }
+}
+
+/// The reason an effect parameter could not be set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GraphicsEffectParamError {
+    Missing {
+        name: String,
+    },
+    TypeMismatch {
+        name: String,
+        expected: ShaderParamTypeKind,
+        actual: ShaderParamTypeKind,
+    },
+}
+
+impl std::fmt::Display for GraphicsEffectParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphicsEffectParamError::Missing { name } => {
+                write!(f, "The effect parameter `{}` does not exist.", name)
+            }
+            GraphicsEffectParamError::TypeMismatch { name, expected, actual } => {
+                write!(
+                    f,
+                    "The effect parameter `{}` is of type {:?}, but a value of type {:?} was provided.",
+                    name, actual, expected,
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for GraphicsEffectParamError {}
+
+/// An error that occurred while working with graphics resources.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GraphicsError {
+    /// The effect `effect` has no parameter `name`.
+    MissingEffectParam {
+        effect: String,
+        name: String,
+    },
+    /// The parameter `name` of the effect `effect` is not of the `expected` type.
+    EffectParamTypeMismatch {
+        effect: String,
+        name: String,
+        expected: ShaderParamTypeKind,
+        actual: ShaderParamTypeKind,
+    },
+    /// A texture could not be created, e.g. due to running out of video memory, or due to its
+    /// size or color format not being supported.
+    TextureCreationFailed {
+        dimensions: [usize; 2],
+        color_format: ColorFormatKind,
+    },
+    /// The effect `effect` could not be reloaded, see `GraphicsEffect::reload_from_file`.
+    EffectReloadFailed {
+        effect: String,
+        reason: String,
+    },
+    /// Frames of the video format cannot be uploaded to a texture directly, see
+    /// `ColorFormatKind::from_video_format`.
+    UnsupportedVideoFormat(VideoFormatKind),
+    /// A frame cannot be uploaded to an existing texture, as it is not dynamic, or differs in
+    /// dimensions or format from the frame, see `Texture::update_from_video_frame`.
+    VideoFrameMismatch {
+        texture_dimensions: [usize; 2],
+        texture_color_format: ColorFormatKind,
+        frame_dimensions: [usize; 2],
+        frame_format: VideoFormatKind,
+    },
+}
+
+impl std::fmt::Display for GraphicsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphicsError::MissingEffectParam { effect, name } => {
+                write!(f, "The effect `{}` has no parameter `{}`.", effect, name)
+            }
+            GraphicsError::EffectParamTypeMismatch { effect, name, expected, actual } => {
+                write!(
+                    f,
+                    "The parameter `{}` of the effect `{}` is of type {:?}, but {:?} was expected.",
+                    name, effect, actual, expected,
+                )
+            }
+            GraphicsError::TextureCreationFailed { dimensions, color_format } => {
+                write!(
+                    f,
+                    "Could not create a {}x{} texture of the color format {:?}.",
+                    dimensions[0], dimensions[1], color_format,
+                )
+            }
+            GraphicsError::EffectReloadFailed { effect, reason } => {
+                write!(f, "Could not reload the effect `{}`: {}", effect, reason)
+            }
+            GraphicsError::UnsupportedVideoFormat(format) => {
+                write!(f, "Frames of the video format {:?} cannot be uploaded to a texture.", format)
+            }
+            GraphicsError::VideoFrameMismatch {
+                texture_dimensions,
+                texture_color_format,
+                frame_dimensions,
+                frame_format,
+            } => {
+                write!(
+                    f,
+                    "Could not upload a {}x{} frame of the video format {:?} to a {}x{} texture of the color format {:?}.",
+                    frame_dimensions[0], frame_dimensions[1], frame_format,
+                    texture_dimensions[0], texture_dimensions[1], texture_color_format,
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for GraphicsError {}
+
+/// Retrieves multiple typed parameters of an effect at once, returning a tuple of the
+/// parameters, or the `GraphicsError` describing the first parameter that is missing or
+/// of the wrong type.
+///
+/// ```rs
+/// let (image, add_val) = require_params!(effect,
+///     cstr!("image") => ShaderParamTypeTexture,
+///     cstr!("add_val") => ShaderParamTypeVec2,
+/// )?;
+/// ```
+#[macro_export]
+macro_rules! require_params {
+    ($effect:expr, $($name:expr => $param_type:ty),+ $(,)?) => {
+        (|| -> ::std::result::Result<_, $crate::graphics::GraphicsError> {
+            Ok(($(
+                $crate::graphics::GraphicsEffect::require_param::<$param_type>(&$effect, $name)?,
+            )+))
+        })()
+    };
+}
+
 impl GraphicsEffectParamTyped<ShaderParamTypeTexture> {
+    /// See `GraphicsEffectParam::set_next_sampler`.
     pub fn set_next_sampler(
         &mut self,
-        _context: &FilterContext,
-        value: &mut GraphicsSamplerState,
+        context: &FilterContext,
+        value: &GraphicsSamplerState,
     ) {
-        unsafe {
-            gs_effect_set_next_sampler(self.inner.raw, value.raw);
-        }
+        self.inner.set_next_sampler(context, value);
+    }
+
+    /// Sets the sampler and the texture of the parameter together, in the order required by
+    /// OBS. Prefer this over calling `set_next_sampler` and `set_param_value` separately.
+    pub fn set_param_value_with_sampler(
+        &mut self,
+        value: &Texture,
+        sampler: &GraphicsSamplerState,
+        context: &FilterContext,
+    ) {
+        self.set_next_sampler(context, sampler);
+        self.set_param_value(value, context);
     }
 }
 
@@ -535,6 +1286,8 @@ impl GraphicsAddressMode {
 pub enum GraphicsSampleFilter {
     Point,
     Linear,
+    /// Sharpens textures viewed at oblique angles, taking up to the max anisotropy samples,
+    /// see `GraphicsSamplerInfo::with_max_anisotropy`.
     Anisotropic,
     MinMagPointMipLinear,
     MinPointMagLinearMipPoint,
@@ -577,7 +1330,28 @@ pub struct GraphicsSamplerInfo {
 }
 
 impl GraphicsSamplerInfo {
+    /// Point filtering with clamped addressing, same as `GraphicsSamplerInfo::point_clamp`.
     pub fn new<'a>(context: &'a GraphicsContext) -> GraphicsContextDependentEnabled<'a, Self> {
+        Self::point_clamp(context)
+    }
+
+    /// Nearest-neighbour sampling with clamped addressing.
+    ///
+    /// Keeps hard edges between pixels, which suits pixel art and 1:1 rendering, but looks
+    /// blocky when upscaling, e.g. when zooming in.
+    pub fn point_clamp<'a>(context: &'a GraphicsContext) -> GraphicsContextDependentEnabled<'a, Self> {
+        Self::clamp_with_filter(GraphicsSampleFilter::Point, context)
+    }
+
+    /// Bilinear sampling with clamped addressing.
+    ///
+    /// Interpolates between neighbouring pixels, which gives smooth results when upscaling or
+    /// zooming, at the cost of slightly blurring hard edges.
+    pub fn linear_clamp<'a>(context: &'a GraphicsContext) -> GraphicsContextDependentEnabled<'a, Self> {
+        Self::clamp_with_filter(GraphicsSampleFilter::Linear, context)
+    }
+
+    fn clamp_with_filter<'a>(filter: GraphicsSampleFilter, context: &'a GraphicsContext) -> GraphicsContextDependentEnabled<'a, Self> {
         ContextDependent::new(Self {
             info: gs_sampler_info {
                 address_u: GraphicsAddressMode::Clamp.as_raw(),
@@ -585,7 +1359,7 @@ impl GraphicsSamplerInfo {
                 address_w: GraphicsAddressMode::Clamp.as_raw(),
                 max_anisotropy: 0,
                 border_color: 0,
-                filter: GraphicsSampleFilter::Point.as_raw(),
+                filter: filter.as_raw(),
             },
         }, context)
     }
@@ -609,8 +1383,48 @@ impl GraphicsSamplerInfo {
         self.info.filter = mode.as_raw();
         self
     }
+
+    /// Sets the maximum number of samples taken by `GraphicsSampleFilter::Anisotropic`, from
+    /// 1 to `MAX_ANISOTROPY`. Values above the maximum are clamped, with a warning. Ignored by
+    /// the other filters.
+    pub fn with_max_anisotropy(mut self, max_anisotropy: u32) -> Self {
+        if max_anisotropy > MAX_ANISOTROPY {
+            crate::warning!(
+                "The max anisotropy {} of a sampler exceeds the supported maximum, {} is used instead.",
+                max_anisotropy,
+                MAX_ANISOTROPY,
+            );
+        }
+
+        self.info.max_anisotropy = max_anisotropy.min(MAX_ANISOTROPY) as c_int;
+        self
+    }
+
+    /// The sampler info to create the sampler state from. Anisotropic filtering without a max
+    /// anisotropy, which would fall back to point filtering, uses `MAX_ANISOTROPY` instead,
+    /// with a warning.
+    fn validated_info(&self) -> gs_sampler_info {
+        let mut info = self.info;
+
+        if info.filter == gs_sample_filter_GS_FILTER_ANISOTROPIC && info.max_anisotropy < 1 {
+            crate::warning!(
+                "A sampler uses anisotropic filtering without a max anisotropy, {} is used. Set it using `GraphicsSamplerInfo::with_max_anisotropy`.",
+                MAX_ANISOTROPY,
+            );
+            info.max_anisotropy = MAX_ANISOTROPY as c_int;
+        }
+
+        info
+    }
 }
 
+/// The maximum anisotropy supported by `GraphicsSamplerInfo::with_max_anisotropy`.
+///
+/// OBS does not expose the limit of the device, but Direct3D 11 guarantees 16, which is also
+/// the limit of common OpenGL drivers. With OpenGL, the anisotropy is additionally clamped by
+/// the driver to the limit of the device.
+pub const MAX_ANISOTROPY: u32 = 16;
+
 pub struct GraphicsSamplerState {
     raw: *mut gs_samplerstate_t,
 }
@@ -618,7 +1432,7 @@ pub struct GraphicsSamplerState {
 impl<'a> From<GraphicsContextDependentEnabled<'a, GraphicsSamplerInfo>> for GraphicsContextDependentEnabled<'a, GraphicsSamplerState> {
     fn from(info: GraphicsContextDependentEnabled<'a, GraphicsSamplerInfo>) -> Self {
         unsafe {
-            let raw = gs_samplerstate_create(&info.info);
+            let raw = gs_samplerstate_create(&info.validated_info());
 
             ContextDependent::new(
                 GraphicsSamplerState {
@@ -638,6 +1452,132 @@ impl Drop for GraphicsSamplerState {
     }
 }
 
+// The sampler state is immutable once created, and only used within the graphics context.
+unsafe impl Send for GraphicsSamplerState {}
+unsafe impl Sync for GraphicsSamplerState {}
+
+/// A sampler state shared by all users of a `SamplerCache` requesting the same sampler info.
+///
+/// The sampler state is destroyed once the last clone is dropped.
+#[derive(Clone)]
+pub struct SharedGraphicsSamplerState {
+    inner: Arc<GraphicsSamplerState>,
+}
+
+impl Deref for SharedGraphicsSamplerState {
+    type Target = GraphicsSamplerState;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+type SamplerKey = (
+    gs_sample_filter,
+    gs_address_mode,
+    gs_address_mode,
+    gs_address_mode,
+    c_int,
+    u32,
+);
+
+impl GraphicsSamplerInfo {
+    fn cache_key(&self) -> SamplerKey {
+        (
+            self.info.filter,
+            self.info.address_u,
+            self.info.address_v,
+            self.info.address_w,
+            self.info.max_anisotropy,
+            self.info.border_color,
+        )
+    }
+}
+
+/// Shares sampler states between sources requesting the same sampler info, instead of
+/// creating a new sampler state for every instance of a source.
+///
+/// The cache only keeps weak references to the sampler states, so it never owns graphics
+/// resources itself. Each sampler state is destroyed as soon as the last
+/// `SharedGraphicsSamplerState` referring to it is dropped, which, like any other graphics
+/// resource, must happen within the graphics context.
+///
+/// ```rs
+/// let sampler = SamplerCache::global()
+///     .get(&GraphicsSamplerInfo::linear_clamp(&graphics_context), &graphics_context)
+///     .disable();
+/// ```
+pub struct SamplerCache {
+    samplers: Mutex<Vec<(SamplerKey, Weak<GraphicsSamplerState>)>>,
+}
+
+static GLOBAL_SAMPLER_CACHE: SamplerCache = SamplerCache::new();
+
+impl SamplerCache {
+    pub const fn new() -> Self {
+        Self {
+            samplers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The cache shared by all plugins of the module.
+    pub fn global() -> &'static Self {
+        &GLOBAL_SAMPLER_CACHE
+    }
+
+    /// Returns the sampler state matching `info`, creating it if no live sampler state with
+    /// the same info exists.
+    pub fn get<'a>(
+        &self,
+        info: &GraphicsSamplerInfo,
+        context: &'a GraphicsContext,
+    ) -> GraphicsContextDependentEnabled<'a, SharedGraphicsSamplerState> {
+        let key = info.cache_key();
+        let mut samplers = self.samplers.lock().unwrap_or_else(PoisonError::into_inner);
+
+        // Forget the sampler states which have already been destroyed.
+        samplers.retain(|(_, sampler)| sampler.strong_count() > 0);
+
+        let existing = samplers
+            .iter()
+            .find(|(existing_key, _)| *existing_key == key)
+            .and_then(|(_, sampler)| sampler.upgrade());
+
+        let inner = match existing {
+            Some(inner) => inner,
+            None => {
+                let raw = unsafe { gs_samplerstate_create(&info.validated_info()) };
+                let inner = Arc::new(GraphicsSamplerState { raw });
+
+                samplers.push((key, Arc::downgrade(&inner)));
+                inner
+            }
+        };
+
+        ContextDependent::new(SharedGraphicsSamplerState { inner }, context)
+    }
+
+    /// The number of sampler states currently alive in the cache.
+    pub fn len(&self) -> usize {
+        self.samplers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|(_, sampler)| sampler.strong_count() > 0)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for SamplerCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub enum GraphicsAllowDirectRendering {
     NoDirectRendering,
     AllowDirectRendering,
@@ -657,15 +1597,18 @@ impl GraphicsAllowDirectRendering {
 }
 
 macro_rules! vector_impls {
+    (@count $($component:ident)*) => (0 $( + vector_impls!(@one $component) )*);
+    (@one $component:ident) => (1);
     ($($rust_name: ident, $name:ident => $($component:ident)*,)*) => (
         $(
+        /// A vector, as used by vector shader parameters.
         #[derive(Clone)]
-        struct $rust_name {
+        pub struct $rust_name {
             raw: $name,
         }
 
         impl $rust_name {
-            fn new($( $component: f32, )*) -> Self {
+            pub fn new($( $component: f32, )*) -> Self {
                 let mut v = Self {
                     raw: $name::default(),
                 };
@@ -674,7 +1617,7 @@ macro_rules! vector_impls {
             }
 
             #[inline]
-            fn set(&mut self, $( $component: f32, )*) {
+            pub fn set(&mut self, $( $component: f32, )*) {
                 $(
                     self.raw.__bindgen_anon_1.__bindgen_anon_1.$component = $component;
                 )*
@@ -683,7 +1626,7 @@ macro_rules! vector_impls {
             $(
                 item! {
                     #[inline]
-                    fn [<$component>](&self) -> f32 {
+                    pub fn [<$component>](&self) -> f32 {
                         unsafe {
                             self.raw.__bindgen_anon_1.__bindgen_anon_1.$component
                         }
@@ -704,6 +1647,28 @@ macro_rules! vector_impls {
                 Self::new($( $component, )*)
             }
         }
+
+        impl std::fmt::Debug for $rust_name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($rust_name))
+                    $(
+                        .field(stringify!($component), &self.$component())
+                    )*
+                    .finish()
+            }
+        }
+
+        impl From<[f32; vector_impls!(@count $($component)*)]> for $rust_name {
+            fn from([$( $component, )*]: [f32; vector_impls!(@count $($component)*)]) -> Self {
+                Self::new($( $component, )*)
+            }
+        }
+
+        impl From<$rust_name> for [f32; vector_impls!(@count $($component)*)] {
+            fn from(vector: $rust_name) -> Self {
+                [$( vector.$component(), )*]
+            }
+        }
         )*
     );
 }
@@ -0,0 +1,133 @@
+use crate::context::*;
+use crate::graphics::*;
+use obs_sys::{
+    gs_stage_texture, gs_stagesurf_t, gs_stagesurface_create, gs_stagesurface_destroy,
+    gs_stagesurface_get_color_format, gs_stagesurface_get_height, gs_stagesurface_get_width,
+    gs_stagesurface_map, gs_stagesurface_unmap,
+};
+
+/// A surface in CPU-accessible memory, used to read back the contents of textures.
+///
+/// Reading back a texture is done in two steps: the texture is copied into the surface using
+/// `StageSurface::stage`, and the surface is then mapped using `StageSurface::map`. Mapping the
+/// surface right after staging stalls the pipeline until the GPU finishes the copy, which is why
+/// it is usually done a frame later. If the pixels are needed within the same frame, call
+/// `GraphicsContext::flush` between staging and mapping.
+pub struct StageSurface {
+    raw: *mut gs_stagesurf_t,
+}
+
+unsafe impl Send for StageSurface {}
+unsafe impl Sync for StageSurface {}
+
+impl StageSurface {
+    pub fn new<'a>(
+        dimensions: [usize; 2],
+        color_format: ColorFormatKind,
+        context: &'a GraphicsContext,
+    ) -> Option<GraphicsContextDependentEnabled<'a, Self>> {
+        unsafe {
+            let raw = gs_stagesurface_create(
+                dimensions[0] as u32,
+                dimensions[1] as u32,
+                color_format.into_raw(),
+            );
+
+            if raw.is_null() {
+                None
+            } else {
+                Some(ContextDependent::new(Self { raw }, context))
+            }
+        }
+    }
+
+    pub fn get_dimensions(&self) -> [usize; 2] {
+        unsafe {
+            [
+                gs_stagesurface_get_width(self.raw) as usize,
+                gs_stagesurface_get_height(self.raw) as usize,
+            ]
+        }
+    }
+
+    pub fn get_color_format(&self) -> ColorFormatKind {
+        unsafe { ColorFormatKind::from_raw(gs_stagesurface_get_color_format(self.raw)) }
+    }
+
+    /// Copies the contents of the `texture` into the surface. The texture must have the same
+    /// dimensions and color format as the surface.
+    pub fn stage(&mut self, texture: &Texture) {
+        unsafe {
+            gs_stage_texture(self.raw, texture.inner() as *mut _);
+        }
+    }
+
+    /// Maps the surface into memory, to read the staged pixels. The surface is unmapped when
+    /// the returned value is dropped.
+    pub fn map(&mut self) -> Option<MappedStageSurface<'_>> {
+        let mut data = std::ptr::null_mut();
+        let mut linesize = 0;
+
+        unsafe {
+            if gs_stagesurface_map(self.raw, &mut data, &mut linesize) && !data.is_null() {
+                let rows = gs_stagesurface_get_height(self.raw) as usize;
+
+                Some(MappedStageSurface {
+                    surface: self,
+                    data: std::slice::from_raw_parts(data, linesize as usize * rows),
+                    linesize: linesize as usize,
+                })
+            } else {
+                None
+            }
+        }
+    }
+}
+
+impl Drop for StageSurface {
+    fn drop(&mut self) {
+        unsafe {
+            gs_stagesurface_destroy(self.raw);
+        }
+    }
+}
+
+/// The pixels of a mapped `StageSurface`.
+pub struct MappedStageSurface<'a> {
+    surface: &'a mut StageSurface,
+    data: &'a [u8],
+    linesize: usize,
+}
+
+impl<'a> MappedStageSurface<'a> {
+    /// The pixel data, including any padding at the end of each row.
+    pub fn data(&self) -> &[u8] {
+        self.data
+    }
+
+    /// The number of bytes per row, including padding.
+    pub fn linesize(&self) -> usize {
+        self.linesize
+    }
+
+    /// Copies the pixel data without the padding at the end of each row.
+    pub fn to_packed_vec(&self) -> Vec<u8> {
+        let [width, height] = self.surface.get_dimensions();
+        let row_size = width * self.surface.get_color_format().get_pixel_size_in_bytes();
+        let mut packed = Vec::with_capacity(row_size * height);
+
+        for row in self.data.chunks(self.linesize).take(height) {
+            packed.extend_from_slice(&row[..row_size.min(row.len())]);
+        }
+
+        packed
+    }
+}
+
+impl<'a> Drop for MappedStageSurface<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            gs_stagesurface_unmap(self.surface.raw);
+        }
+    }
+}
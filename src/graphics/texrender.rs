@@ -0,0 +1,95 @@
+use crate::context::*;
+use crate::graphics::*;
+use crate::source::properties::Color;
+use obs_sys::{
+    gs_texrender_begin, gs_texrender_create, gs_texrender_destroy, gs_texrender_end,
+    gs_texrender_get_texture, gs_texrender_reset, gs_texrender_t,
+    gs_zstencil_format_GS_ZS_NONE,
+};
+
+/// A texture which can be rendered to, e.g. to render a source or an intermediate pass of a
+/// multi-pass effect, and then use the result as the input of an effect.
+pub struct TextureRender {
+    raw: *mut gs_texrender_t,
+    /// The texture rendered to by the last call to `render`, owned by the texture render.
+    texture: Option<Texture>,
+}
+
+unsafe impl Send for TextureRender {}
+unsafe impl Sync for TextureRender {}
+
+impl TextureRender {
+    pub fn new<'a>(
+        color_format: ColorFormatKind,
+        context: &'a GraphicsContext,
+    ) -> Option<GraphicsContextDependentEnabled<'a, Self>> {
+        Self::create(color_format).map(|texture_render| ContextDependent::new(texture_render, context))
+    }
+
+    /// Creates the texture render without wrapping it, for types which own texture renders and
+    /// are context-dependent themselves. Must be called within the graphics context.
+    pub(crate) fn create(color_format: ColorFormatKind) -> Option<Self> {
+        unsafe {
+            let raw = gs_texrender_create(color_format.into_raw(), gs_zstencil_format_GS_ZS_NONE);
+
+            if raw.is_null() {
+                None
+            } else {
+                Some(Self { raw, texture: None })
+            }
+        }
+    }
+
+    /// Renders into the texture, which is cleared with the `clear_color` first. Returns `false`
+    /// if the texture could not be bound as the render target, in which case `render` is not
+    /// called.
+    ///
+    /// The texture is resized to the `dimensions` if needed.
+    pub fn render(
+        &mut self,
+        dimensions: [u32; 2],
+        clear_color: Color,
+        context: &GraphicsContext,
+        render: impl FnOnce(&GraphicsContext),
+    ) -> bool {
+        // The texture may be recreated when resized, so it is fetched again once rendered.
+        self.texture = None;
+
+        unsafe {
+            gs_texrender_reset(self.raw);
+
+            if !gs_texrender_begin(self.raw, dimensions[0], dimensions[1]) {
+                return false;
+            }
+        }
+
+        context.clear(clear_color, None, None);
+        render(context);
+
+        unsafe {
+            gs_texrender_end(self.raw);
+
+            let raw = gs_texrender_get_texture(self.raw);
+
+            if !raw.is_null() {
+                self.texture = Some(Texture::from_raw(raw, 0));
+            }
+        }
+
+        true
+    }
+
+    /// The texture rendered to by the last successful call to `render`, if any. It is borrowed
+    /// from the texture render, as it is freed when the texture render is dropped.
+    pub fn get_texture(&self) -> Option<&Texture> {
+        self.texture.as_ref()
+    }
+}
+
+impl Drop for TextureRender {
+    fn drop(&mut self) {
+        unsafe {
+            gs_texrender_destroy(self.raw);
+        }
+    }
+}
@@ -4,6 +4,7 @@ use std::ffi::{c_void, CString};
 use std::path::Path;
 use crate::context::*;
 use crate::graphics::*;
+use crate::video::{VideoFormatKind, VideoFrame};
 use obs_sys::{
     size_t,
     gs_texture_t,
@@ -15,6 +16,7 @@ use obs_sys::{
     gs_texture_get_height,
     gs_texture_get_color_format,
     gs_texture_get_obj,
+    gs_texture_set_image,
     gs_color_format,
     gs_color_format_GS_A8,
     gs_color_format_GS_R8,
@@ -118,6 +120,41 @@ define_color_formats! {
     gs_color_format_GS_R8G8,        R8G8,        2;
 }
 
+impl ColorFormatKind {
+    /// Whether textures of the color format can be rendered to, e.g. by `TextureRender`,
+    /// or as the intermediate texture of `SourceContext::process_filter`. Compressed formats
+    /// cannot.
+    pub fn is_render_target_format(self) -> bool {
+        use ColorFormatKind::*;
+
+        !matches!(self, Unknown | DXT1 | DXT3 | DXT5)
+    }
+
+    /// Whether the color format stores floating point values, which avoids banding in
+    /// effects applied in multiple passes, and allows values outside of the `[0, 1]` range,
+    /// as used by HDR.
+    pub fn is_float(self) -> bool {
+        use ColorFormatKind::*;
+
+        matches!(self, RGBA16F | RGBA32F | RG16F | RG32F | R16F | R32F)
+    }
+
+    /// The color format a frame of the video format can be uploaded as, without conversion.
+    ///
+    /// Only packed RGB formats, and the luma-only `Y800`, have such a color format. Frames
+    /// of the YUV formats have to be converted, e.g. by uploading each plane to a separate
+    /// texture and combining them in a shader.
+    pub fn from_video_format(format: VideoFormatKind) -> Option<Self> {
+        match format {
+            VideoFormatKind::RGBA => Some(ColorFormatKind::RGBA),
+            VideoFormatKind::BGRA => Some(ColorFormatKind::BGRA),
+            VideoFormatKind::BGRX => Some(ColorFormatKind::BGRX),
+            VideoFormatKind::Y800 => Some(ColorFormatKind::R8),
+            _ => None,
+        }
+    }
+}
+
 pub const TEXTURE_FLAG_BUILD_MIPMAPS: u32 = GS_BUILD_MIPMAPS;
 pub const TEXTURE_FLAG_DYNAMIC: u32 = GS_DYNAMIC;
 pub const TEXTURE_FLAG_RENDER_TARGET: u32 = GS_RENDER_TARGET;
@@ -170,16 +207,29 @@ pub struct Texture {
 }
 
 impl<'a> Clone for GraphicsContextDependentEnabled<'a, Texture> {
+    /// Copies the texture into a new texture, see `Texture::try_clone`.
+    ///
+    /// If the new texture cannot be created, a warning is logged and the clone refers to the
+    /// same texture as the original instead, so that modifying one modifies the other. Use
+    /// `Texture::try_clone` to handle the error instead.
     fn clone(&self) -> Self {
-        let dimensions = self.get_dimensions();
-        let color_format = self.get_color_format();
-        let bytes = dimensions[0] * dimensions[1] * color_format.get_pixel_size_in_bytes();
-        let zero_data = vec![0; bytes];
-        let mut cloned = Texture::new(dimensions, color_format, &[&zero_data], self.flags, self.context());
-
-        self.copy_to(&mut cloned);
+        Texture::try_clone(self).unwrap_or_else(|error| {
+            crate::warning!(
+                "Could not copy a texture, the clone refers to the original texture instead: {}",
+                error,
+            );
 
-        cloned
+            ContextDependent::new(
+                Texture {
+                    inner: match &self.inner {
+                        TextureInner::Owned(owned) => TextureInner::Owned(owned.clone()),
+                        TextureInner::Borrowed(raw) => TextureInner::Borrowed(*raw),
+                    },
+                    flags: self.flags,
+                },
+                self.context(),
+            )
+        })
     }
 }
 
@@ -187,8 +237,10 @@ unsafe impl Send for Texture {}
 unsafe impl Sync for Texture {}
 
 impl DefaultInContext<GraphicsContext> for Texture {
+    /// Panics if the dummy texture cannot be created, which only happens when the graphics
+    /// device is unusable. Use `Texture::new_dummy` to handle the error instead.
     fn default_in_context<'a>(context: &'a GraphicsContext) -> GraphicsContextDependentEnabled<Self> {
-        Self::new_dummy(context)
+        Self::new_dummy(context).expect("Could not create a dummy texture.")
     }
 }
 
@@ -200,7 +252,7 @@ impl Texture {
         }
     }
 
-    pub fn new_dummy(context: &GraphicsContext) -> GraphicsContextDependentEnabled<Self> {
+    pub fn new_dummy(context: &GraphicsContext) -> Result<GraphicsContextDependentEnabled<Self>, GraphicsError> {
         let dimensions = [1, 1];
         let color_format = ColorFormatKind::RGBA;
         let bytes = dimensions[0] * dimensions[1] * color_format.get_pixel_size_in_bytes();
@@ -210,7 +262,10 @@ impl Texture {
     }
 
     /// For flags, see constants defined in this module
-    pub fn new<'a>(dimensions: [usize; 2], color_format: ColorFormatKind, levels: &[&[u8]], flags: u32, context: &'a GraphicsContext) -> GraphicsContextDependentEnabled<'a, Self> {
+    ///
+    /// Fails if the texture cannot be created, e.g. when running out of video memory, or when
+    /// the dimensions exceed `GraphicsContext::max_texture_size`.
+    pub fn new<'a>(dimensions: [usize; 2], color_format: ColorFormatKind, levels: &[&[u8]], flags: u32, context: &'a GraphicsContext) -> Result<GraphicsContextDependentEnabled<'a, Self>, GraphicsError> {
         let mut level_ptrs = levels.iter().map(|level_ref| {
             level_ref.as_ptr()
         }).collect::<Vec<_>>();
@@ -228,17 +283,117 @@ impl Texture {
             );
 
             if inner == std::ptr::null_mut() {
-                panic!("An error occurred while creating a texture.");
+                return Err(GraphicsError::TextureCreationFailed {
+                    dimensions,
+                    color_format,
+                });
             }
 
-            ContextDependent::new(
+            Ok(ContextDependent::new(
                 Self {
                     inner: TextureInner::Owned(Arc::new(TextureOwned(inner))),
                     flags,
                 },
                 context,
+            ))
+        }
+    }
+
+    /// Uploads the frame, e.g. retrieved using `SourceContext::get_frame`, to a new dynamic
+    /// texture, so that it can be rendered. Flipped frames are uploaded upright.
+    ///
+    /// Only the formats supported by `ColorFormatKind::from_video_format` can be uploaded,
+    /// `GraphicsError::UnsupportedVideoFormat` is returned for the planar YUV formats, such as
+    /// `NV12` and `I420`.
+    pub fn from_video_frame<'a>(
+        frame: &VideoFrame,
+        context: &'a GraphicsContext,
+    ) -> Result<GraphicsContextDependentEnabled<'a, Self>, GraphicsError> {
+        let format = frame.get_format();
+        let color_format = ColorFormatKind::from_video_format(format)
+            .ok_or(GraphicsError::UnsupportedVideoFormat(format))?;
+        let [width, height] = frame.get_dimensions();
+        let dimensions = [width as usize, height as usize];
+
+        let mut texture = unsafe {
+            let inner = gs_texture_create(
+                width,
+                height,
+                color_format.into_raw(),
+                1,
+                std::ptr::null_mut(),
+                TEXTURE_FLAG_DYNAMIC,
+            );
+
+            if inner.is_null() {
+                return Err(GraphicsError::TextureCreationFailed {
+                    dimensions,
+                    color_format,
+                });
+            }
+
+            ContextDependent::new(
+                Self {
+                    inner: TextureInner::Owned(Arc::new(TextureOwned(inner))),
+                    flags: TEXTURE_FLAG_DYNAMIC,
+                },
+                context,
             )
+        };
+
+        texture.update_from_video_frame(frame)?;
+
+        Ok(texture)
+    }
+
+    /// Replaces the contents of a texture created using `Texture::from_video_frame` with
+    /// the frame, avoiding creating a new texture for every frame.
+    ///
+    /// Fails if the frame differs in dimensions or format from the texture, in which case a
+    /// new texture has to be created.
+    pub fn update_from_video_frame(&mut self, frame: &VideoFrame) -> Result<(), GraphicsError> {
+        let format = frame.get_format();
+        let color_format = ColorFormatKind::from_video_format(format)
+            .ok_or(GraphicsError::UnsupportedVideoFormat(format))?;
+        let [width, height] = frame.get_dimensions();
+        let frame_dimensions = [width as usize, height as usize];
+
+        if self.flags & TEXTURE_FLAG_DYNAMIC == 0
+            || self.get_color_format() != color_format
+            || self.get_dimensions() != frame_dimensions {
+            return Err(GraphicsError::VideoFrameMismatch {
+                texture_dimensions: self.get_dimensions(),
+                texture_color_format: self.get_color_format(),
+                frame_dimensions,
+                frame_format: format,
+            });
+        }
+
+        let (plane, linesize) = match (frame.get_plane(0), frame.get_linesize(0)) {
+            (Some(plane), Some(linesize)) => (plane, linesize),
+            _ => return Err(GraphicsError::UnsupportedVideoFormat(format)),
+        };
+
+        unsafe {
+            gs_texture_set_image(*self.inner, plane.as_ptr(), linesize, frame.is_flipped());
         }
+
+        Ok(())
+    }
+
+    /// Copies the texture into a new texture with the same dimensions, color format and flags.
+    pub fn try_clone<'a>(
+        self: &GraphicsContextDependentEnabled<'a, Self>,
+    ) -> Result<GraphicsContextDependentEnabled<'a, Self>, GraphicsError> {
+        let dimensions = self.get_dimensions();
+        let color_format = self.get_color_format();
+        let bytes = dimensions[0] * dimensions[1] * color_format.get_pixel_size_in_bytes();
+        let zero_data = vec![0; bytes];
+        let mut cloned = Texture::new(dimensions, color_format, &[&zero_data], self.flags, self.context())?;
+
+        self.copy_to(&mut cloned);
+
+        Ok(cloned)
     }
 
     pub fn load(path: impl AsRef<Path>) -> Option<Self> {
@@ -0,0 +1,193 @@
+use super::*;
+
+/// An offset and a scale applied to texture coordinates, the "offset + scale" pattern used by
+/// crop, pan and zoom filters.
+///
+/// A texture coordinate `uv` is transformed to `uv * scale + offset`, so the transform selects
+/// the region of the source texture starting at `offset`, of the size `scale`, both in
+/// normalized coordinates. The corresponding vertex shader:
+///
+/// ```hlsl
+/// uniform float2 mul_val;
+/// uniform float2 add_val;
+///
+/// VertData VSCrop(VertData v_in)
+/// {
+///     VertData vert_out;
+///     vert_out.pos = mul(float4(v_in.pos.xyz, 1.0), ViewProj);
+///     vert_out.uv  = v_in.uv * mul_val + add_val;
+///     return vert_out;
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AffineTransform2D {
+    pub offset: [f32; 2],
+    pub scale: [f32; 2],
+}
+
+impl AffineTransform2D {
+    /// Leaves the texture coordinates unchanged.
+    pub const IDENTITY: Self = Self {
+        offset: [0.0, 0.0],
+        scale: [1.0, 1.0],
+    };
+
+    pub fn new(offset: [f32; 2], scale: [f32; 2]) -> Self {
+        Self { offset, scale }
+    }
+
+    /// Selects the region of the texture at `position` of the size `size`, in normalized
+    /// coordinates.
+    pub fn crop_to_rect(position: [f32; 2], size: [f32; 2]) -> Self {
+        Self::new(position, size)
+    }
+
+    /// Selects the region of the texture at `position` of the size `size`, in pixels of a
+    /// texture of the size `texture_size`.
+    pub fn crop_to_pixels(position: [u32; 2], size: [u32; 2], texture_size: [u32; 2]) -> Self {
+        let width = texture_size[0].max(1) as f32;
+        let height = texture_size[1].max(1) as f32;
+
+        Self::crop_to_rect(
+            [position[0] as f32 / width, position[1] as f32 / height],
+            [size[0] as f32 / width, size[1] as f32 / height],
+        )
+    }
+
+    /// Magnifies the texture `zoom` times, centered on `point`, in normalized coordinates.
+    ///
+    /// The visible region is kept within the texture, so that zooming in near an edge does not
+    /// reveal the area outside of it. A `zoom` of `1.0` or less results in the identity.
+    pub fn zoom_to_point(point: [f32; 2], zoom: f32) -> Self {
+        let size = 1.0 / zoom.max(1.0);
+        let max_offset = 1.0 - size;
+        let offset = [
+            (point[0] - size / 2.0).clamp(0.0, max_offset),
+            (point[1] - size / 2.0).clamp(0.0, max_offset),
+        ];
+
+        Self::new(offset, [size, size])
+    }
+
+    /// Transforms the normalized texture coordinates `uv`, as done by the shader.
+    pub fn transform_point(&self, uv: [f32; 2]) -> [f32; 2] {
+        [
+            uv[0] * self.scale[0] + self.offset[0],
+            uv[1] * self.scale[1] + self.offset[1],
+        ]
+    }
+
+    /// Interpolates linearly between `self` and `target`, e.g. to animate the transform.
+    /// A `progress` of `0.0` results in `self`, `1.0` in `target`.
+    pub fn lerp(&self, target: &Self, progress: f32) -> Self {
+        let lerp = |from: f32, to: f32| from + (to - from) * progress;
+
+        Self::new(
+            [lerp(self.offset[0], target.offset[0]), lerp(self.offset[1], target.offset[1])],
+            [lerp(self.scale[0], target.scale[0]), lerp(self.scale[1], target.scale[1])],
+        )
+    }
+
+    /// Sets both the offset and the scale parameters of the effect.
+    pub fn apply_to(&self, params: &mut AffineTransformParams, context: &FilterContext) {
+        params.offset.set_param_value(&self.offset, context);
+        params.scale.set_param_value(&self.scale, context);
+    }
+}
+
+impl Default for AffineTransform2D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// The parameters of an effect an `AffineTransform2D` is applied to.
+pub struct AffineTransformParams {
+    offset: GraphicsEffectParamTyped<ShaderParamTypeVec2>,
+    scale: GraphicsEffectParamTyped<ShaderParamTypeVec2>,
+}
+
+impl AffineTransformParams {
+    /// Retrieves the `add_val` (offset) and `mul_val` (scale) parameters of the effect, as
+    /// named in the crop filter shipped with OBS.
+    pub fn from_effect<'a>(
+        effect: &GraphicsContextDependentEnabled<'a, GraphicsEffect>,
+    ) -> Result<GraphicsContextDependentEnabled<'a, Self>, GraphicsError> {
+        Self::from_effect_with_names(effect, cstr!("add_val"), cstr!("mul_val"))
+    }
+
+    /// Retrieves the offset and scale `float2` parameters of the effect by their names.
+    pub fn from_effect_with_names<'a>(
+        effect: &GraphicsContextDependentEnabled<'a, GraphicsEffect>,
+        offset_name: &CStr,
+        scale_name: &CStr,
+    ) -> Result<GraphicsContextDependentEnabled<'a, Self>, GraphicsError> {
+        let offset = effect.require_param::<ShaderParamTypeVec2>(offset_name)?;
+        let scale = effect.require_param::<ShaderParamTypeVec2>(scale_name)?;
+
+        Ok(offset.zip(scale).map(|(offset, scale)| Self { offset, scale }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crop_to_pixels() {
+        let transform = AffineTransform2D::crop_to_pixels([480, 270], [960, 540], [1920, 1080]);
+
+        assert_eq!(transform, AffineTransform2D::new([0.25, 0.25], [0.5, 0.5]));
+        // The region maps onto the whole output.
+        assert_eq!(transform.transform_point([0.0, 0.0]), [0.25, 0.25]);
+        assert_eq!(transform.transform_point([1.0, 1.0]), [0.75, 0.75]);
+    }
+
+    #[test]
+    fn crop_to_pixels_of_an_empty_texture() {
+        let transform = AffineTransform2D::crop_to_pixels([0, 0], [0, 0], [0, 0]);
+
+        assert!(transform.offset.iter().chain(&transform.scale).all(|value| value.is_finite()));
+    }
+
+    #[test]
+    fn zoom_to_point() {
+        assert_eq!(
+            AffineTransform2D::zoom_to_point([0.5, 0.5], 2.0),
+            AffineTransform2D::new([0.25, 0.25], [0.5, 0.5]),
+        );
+        assert_eq!(
+            AffineTransform2D::zoom_to_point([0.5, 0.375], 4.0),
+            AffineTransform2D::new([0.375, 0.25], [0.25, 0.25]),
+        );
+    }
+
+    #[test]
+    fn zoom_to_point_stays_within_the_texture() {
+        assert_eq!(
+            AffineTransform2D::zoom_to_point([0.0, 1.0], 2.0),
+            AffineTransform2D::new([0.0, 0.5], [0.5, 0.5]),
+        );
+        assert_eq!(AffineTransform2D::zoom_to_point([0.75, 0.25], 1.0), AffineTransform2D::IDENTITY);
+        assert_eq!(AffineTransform2D::zoom_to_point([0.75, 0.25], 0.5), AffineTransform2D::IDENTITY);
+    }
+
+    #[test]
+    fn transform_point() {
+        let transform = AffineTransform2D::new([0.25, 0.5], [0.5, 2.0]);
+
+        assert_eq!(AffineTransform2D::IDENTITY.transform_point([0.3, 0.7]), [0.3, 0.7]);
+        assert_eq!(transform.transform_point([0.0, 0.0]), [0.25, 0.5]);
+        assert_eq!(transform.transform_point([0.5, 0.25]), [0.5, 1.0]);
+    }
+
+    #[test]
+    fn lerp() {
+        let from = AffineTransform2D::IDENTITY;
+        let to = AffineTransform2D::new([0.5, 0.25], [0.5, 0.25]);
+
+        assert_eq!(from.lerp(&to, 0.0), from);
+        assert_eq!(from.lerp(&to, 1.0), to);
+        assert_eq!(from.lerp(&to, 0.5), AffineTransform2D::new([0.25, 0.125], [0.75, 0.625]));
+    }
+}
@@ -52,6 +52,52 @@ impl ObsVideoInfo {
         [self.inner.output_width, self.inner.output_height]
     }
 
+    /// Whether the video settings, such as the base (canvas) or output resolution, or the
+    /// framerate, have changed since this info was retrieved.
+    ///
+    /// OBS does not notify plugins when the user changes the video settings, so plugins caching
+    /// the info should poll this periodically, e.g. in `VideoTickSource::video_tick`, and
+    /// re-fetch the info with `ObsVideoInfo::refresh` when it returns `true`:
+    ///
+    /// ```rs
+    /// if data.video_info.refresh() {
+    ///     let [width, height] = data.video_info.base_dimensions();
+    ///     // Adapt to the new canvas size
+    /// }
+    /// ```
+    pub fn changed_since(&self) -> bool {
+        match Self::get() {
+            Some(current) => !self.same_settings(&current),
+            None => false,
+        }
+    }
+
+    /// Re-fetches the video info, returning `true` if it has changed.
+    pub fn refresh(&mut self) -> bool {
+        match Self::get() {
+            Some(current) if !self.same_settings(&current) => {
+                *self = current;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn same_settings(&self, other: &Self) -> bool {
+        let (a, b) = (&self.inner, &other.inner);
+
+        a.fps_num == b.fps_num
+            && a.fps_den == b.fps_den
+            && a.base_width == b.base_width
+            && a.base_height == b.base_height
+            && a.output_width == b.output_width
+            && a.output_height == b.output_height
+            && a.output_format == b.output_format
+            && a.colorspace == b.colorspace
+            && a.range == b.range
+            && a.scale_type == b.scale_type
+    }
+
     // TODO implement the rest of the getters
 }
 
@@ -135,6 +135,20 @@ pub mod module;
 pub mod source;
 /// Tools for handling audio
 pub mod audio;
+/// Tools for handling video frames
+pub mod video;
+/// Tools for procedure and signal call data
+pub mod calldata;
+/// Tools for reading OBS data objects and arrays
+pub mod data;
+/// Tools for calling functions missing in older versions of OBS
+pub mod symbol;
+/// Bindings to the OBS frontend API
+#[cfg(feature = "frontend")]
+pub mod frontend;
+/// Tools for testing plugins without a running OBS
+#[cfg(feature = "mock")]
+pub mod testing;
 
 /// Re-exports of a bunch of popular tools
 pub mod prelude {
@@ -1,8 +1,52 @@
+use obs_sys::{LOG_DEBUG, LOG_ERROR, LOG_INFO, LOG_WARNING};
+
+/// The severity of a message logged to the OBS log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogLevel {
+    Error,
+    Warning,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    pub fn as_raw(&self) -> i32 {
+        (match self {
+            LogLevel::Error => LOG_ERROR,
+            LogLevel::Warning => LOG_WARNING,
+            LogLevel::Info => LOG_INFO,
+            LogLevel::Debug => LOG_DEBUG,
+        }) as i32
+    }
+}
+
+/// Logs the `message` to the OBS log. Prefer the logging macros, unless the level is only
+/// known at runtime.
+pub fn log(level: LogLevel, message: &str) {
+    log_raw(level.as_raw(), message);
+}
+
+#[doc(hidden)]
+pub fn log_raw(level: i32, message: &str) {
+    // Interior NUL bytes would truncate the message, replace them instead.
+    let message = std::ffi::CString::new(message.replace('\0', "\\0"))
+        .expect("NUL bytes have been replaced.");
+
+    unsafe {
+        // The message is passed as an argument, so that `%` in it is not interpreted.
+        obs_sys::blog(
+            level,
+            b"%s\0".as_ptr() as *const std::os::raw::c_char,
+            message.as_ptr(),
+        );
+    }
+}
+
 #[macro_export]
 macro_rules! obs_log {
-        ($level:expr, $($arg:tt)*) => (unsafe {
-            $crate::obs_sys::blog($level, format!("{}", format_args!($($arg)*)).as_ptr() as *const std::os::raw::c_char)
-        });
+        ($level:expr, $($arg:tt)*) => (
+            $crate::log::log_raw($level, &format!("{}", format_args!($($arg)*)))
+        );
     }
 
 #[macro_export]
@@ -1,7 +1,169 @@
 use crate::source::{traits::Sourceable, SourceInfo, SourceInfoBuilder};
-use obs_sys::{obs_module_t, obs_register_source_s, obs_source_info, size_t};
+use obs_sys::{
+    lookup_t, obs_module_load_locale, obs_module_t, obs_register_source_s, obs_source_info,
+    size_t, text_lookup_destroy, text_lookup_getstr,
+};
+use std::any::{Any, TypeId};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::marker::PhantomData;
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use cstr::cstr;
+
+/// The texts of the module in the current locale, loaded from the `data/locale` directory of
+/// the module.
+static mut MODULE_TEXT_LOOKUP: *mut lookup_t = std::ptr::null_mut();
+
+/// Loads the texts of the module for the `locale`, falling back to the `default_locale` for
+/// missing texts. Called by OBS through `obs_register_module!`.
+#[doc(hidden)]
+pub unsafe fn set_locale(module: *mut obs_module_t, default_locale: &CStr, locale: *const c_char) {
+    free_locale();
+    MODULE_TEXT_LOOKUP = obs_module_load_locale(module, default_locale.as_ptr(), locale);
+}
+
+#[doc(hidden)]
+pub unsafe fn free_locale() {
+    if !MODULE_TEXT_LOOKUP.is_null() {
+        text_lookup_destroy(MODULE_TEXT_LOOKUP);
+        MODULE_TEXT_LOOKUP = std::ptr::null_mut();
+    }
+}
+
+/// Looks up the text of the `key` in the locale files of the module, or `None` if it is
+/// missing. As `obs_module_text` returns the key itself for missing texts, a text equal to its
+/// key is considered missing too.
+fn lookup_module_text(key: &CStr) -> Option<CString> {
+    unsafe {
+        let mut text: *const c_char = std::ptr::null();
+
+        if !MODULE_TEXT_LOOKUP.is_null()
+            && text_lookup_getstr(MODULE_TEXT_LOOKUP, key.as_ptr(), &mut text)
+            && !text.is_null()
+        {
+            let text = CStr::from_ptr(text);
+
+            if text != key {
+                return Some(text.to_owned());
+            }
+        }
+
+        None
+    }
+}
+
+/// Looks up the text of the `key` in the locale files of the module, the equivalent of
+/// `obs_module_text`. Returns the key itself, if the text is missing.
+pub fn module_text(key: &CStr) -> CString {
+    lookup_module_text(key).unwrap_or_else(|| key.to_owned())
+}
+
+/// Like `module_text`, but returns the `fallback` if the text is missing, in neither the current
+/// nor the default locale, so that a partially translated module still shows readable texts
+/// instead of their keys.
+pub fn module_text_or(key: &CStr, fallback: &CStr) -> CString {
+    lookup_module_text(key).unwrap_or_else(|| fallback.to_owned())
+}
+
+/// Looks up a text of the module using `module_text`, caching it for the lifetime of the
+/// program, so that it can be returned from `GetNameSource::get_name`:
+///
+/// ```rs
+/// impl GetNameSource<Data> for ScrollFocusFilter {
+///     fn get_name() -> &'static CStr {
+///         localized_name!("ScrollFocusFilter", "Scroll Focus")
+///     }
+/// }
+/// ```
+///
+/// If the second argument is given, it is used in place of a missing text, see
+/// `module_text_or`, otherwise the key itself is used.
+///
+/// The text is looked up on first use, so it does not change when the locale of OBS is
+/// changed while it is running.
+#[macro_export]
+macro_rules! localized_name {
+    (@lookup $key:expr, $lookup:expr) => {{
+        static ONCE: ::std::sync::Once = ::std::sync::Once::new();
+        static mut TEXT: ::std::option::Option<::std::ffi::CString> = None;
+
+        unsafe {
+            ONCE.call_once(|| {
+                let key = ::std::ffi::CStr::from_bytes_with_nul(concat!($key, "\0").as_bytes())
+                    .expect("The key must not contain NUL bytes.");
+                let lookup: fn(&::std::ffi::CStr) -> ::std::ffi::CString = $lookup;
+
+                TEXT = Some(lookup(key));
+            });
+
+            TEXT.as_ref().unwrap().as_c_str()
+        }
+    }};
+    ($key:expr) => {
+        $crate::localized_name!(@lookup $key, |key| $crate::module::module_text(key))
+    };
+    ($key:expr, $fallback:expr) => {
+        $crate::localized_name!(@lookup $key, |key| {
+            let fallback = ::std::ffi::CStr::from_bytes_with_nul(concat!($fallback, "\0").as_bytes())
+                .expect("The fallback must not contain NUL bytes.");
+
+            $crate::module::module_text_or(key, fallback)
+        })
+    };
+}
+
+/// Calls `Module::load`, catching a panic, as unwinding into the module loader of OBS is
+/// undefined behavior. A panic is logged and the module fails to load, instead of crashing
+/// OBS. Called by OBS through `obs_register_module!`.
+#[doc(hidden)]
+pub fn load_module<M: Module>(module: &mut M, context: &mut LoadContext) -> bool {
+    catch_load_panic::<M>(|| module.load(context))
+}
+
+/// Calls `Module::load`, then registers the sources returned by `sources`, if loading
+/// succeeded, catching a panic like `load_module`. Called by OBS through
+/// `obs_register_module!(Module, sources = ...)`.
+#[doc(hidden)]
+pub fn load_module_with_sources<M: Module, I: IntoIterator<Item = SourceInfo>>(
+    module: &mut M,
+    context: &mut LoadContext,
+    sources: impl FnOnce(&mut M, &LoadContext) -> I,
+) -> bool {
+    catch_load_panic::<M>(|| {
+        if !module.load(context) {
+            return false;
+        }
+
+        let sources = sources(module, context);
+
+        context.register_sources(sources);
+
+        true
+    })
+}
+
+fn catch_load_panic<M: Module>(load: impl FnOnce() -> bool) -> bool {
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(load));
+
+    match result {
+        Ok(loaded) => loaded,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown panic");
+
+            crate::error!(
+                "Module {:?} panicked while loading, it will not be loaded: {}",
+                M::name(),
+                message
+            );
+
+            false
+        }
+    }
+}
 
 pub struct LoadContext {
     __marker: PhantomData<()>,
@@ -23,7 +185,10 @@ impl LoadContext {
         SourceInfoBuilder::new()
     }
 
+    /// Registers the source, after checking its configuration, see `SourceInfo::validate`.
     pub fn register_source(&mut self, source: SourceInfo) {
+        source.validate();
+
         let pointer = unsafe {
             let pointer = source.into_raw();
             obs_register_source_s(pointer, std::mem::size_of::<obs_source_info>() as size_t);
@@ -31,6 +196,63 @@ impl LoadContext {
         };
         self.sources.push(pointer);
     }
+
+    /// Registers the source under the ID `id`, instead of `Sourceable::get_id`, with its own
+    /// type data, so that the same `Sourceable` can back multiple source types, e.g. one per
+    /// shader file found in a directory:
+    ///
+    /// ```rs
+    /// for (index, path) in shader_paths.into_iter().enumerate() {
+    ///     let id = CString::new(format!("shader_source_{}", index)).unwrap();
+    ///     let source = load_context
+    ///         .create_source_builder::<ShaderSource, Data>()
+    ///         .enable_get_name()
+    ///         .enable_create()
+    ///         .build();
+    ///
+    ///     load_context.register_source_with_id(source, &id, ShaderPath(path));
+    /// }
+    /// ```
+    ///
+    /// Any type data set using `SourceInfoBuilder::with_type_data` is replaced. Each
+    /// registration owns its type data, which is dropped when OBS unregisters the source type.
+    pub fn register_source_with_id<X: Any + Send + Sync>(
+        &mut self,
+        mut source: SourceInfo,
+        id: &CStr,
+        type_data: X,
+    ) {
+        // OBS keeps referring to the ID for as long as the source type is registered, which
+        // is until OBS shuts down.
+        let id: &'static CStr = Box::leak(id.to_owned().into_boxed_c_str());
+
+        source.set_id(id);
+        source.set_type_data(type_data);
+        self.register_source(source);
+    }
+
+    /// Registers every source yielded by `sources`, in order.
+    ///
+    /// Useful for modules shipping a whole suite of sources and filters, which can
+    /// collect the built `SourceInfo`s and register them in one go:
+    ///
+    /// ```rs
+    /// let sources = vec![
+    ///     load_context.create_source_builder::<BlurFilter, BlurData>()
+    ///         .enable_get_name()
+    ///         .build(),
+    ///     load_context.create_source_builder::<SharpenFilter, SharpenData>()
+    ///         .enable_get_name()
+    ///         .build(),
+    /// ];
+    ///
+    /// load_context.register_sources(sources);
+    /// ```
+    pub fn register_sources(&mut self, sources: impl IntoIterator<Item = SourceInfo>) {
+        for source in sources {
+            self.register_source(source);
+        }
+    }
 }
 
 impl Drop for LoadContext {
@@ -46,19 +268,54 @@ impl Drop for LoadContext {
 pub trait Module {
     fn new(ctx: ModuleContext) -> Self;
     fn get_ctx(&self) -> &ModuleContext;
+    /// Called once the module is loaded by OBS. Sources are registered here, either one at a
+    /// time with `LoadContext::register_source`, or as a list using
+    /// `LoadContext::register_sources`.
     fn load(&mut self, _load_context: &mut LoadContext) -> bool {
         true
     }
+    /// Called before the module is unloaded by OBS. The module-global values, see
+    /// `ModuleContext::set_global`, are dropped afterwards.
     fn unload(&mut self) {}
     fn post_load(&mut self) {}
     fn description() -> &'static CStr;
     fn name() -> &'static CStr;
     fn author() -> &'static CStr;
+    /// The locale whose texts are used for those missing in the current locale, see
+    /// `module_text`.
+    fn default_locale() -> &'static CStr {
+        cstr!("en-US")
+    }
 }
 
+/// Exports the functions OBS looks up to load the module `$t`, which registers its sources
+/// in `Module::load`:
+///
+/// ```rs
+/// obs_register_module!(BlurModule);
+/// ```
+///
+/// A module shipping a list of sources can instead return them from a function, which is
+/// called with the `LoadContext` after `Module::load` succeeded, and whose sources are
+/// registered using `LoadContext::register_sources`:
+///
+/// ```rs
+/// fn sources(_module: &mut BlurModule, load_context: &LoadContext) -> Vec<SourceInfo> {
+///     vec![
+///         load_context.create_source_builder::<BoxBlurFilter, BoxBlurData>()
+///             .enable_get_name()
+///             .build(),
+///         load_context.create_source_builder::<GaussianBlurFilter, GaussianBlurData>()
+///             .enable_get_name()
+///             .build(),
+///     ]
+/// }
+///
+/// obs_register_module!(BlurModule, sources = sources);
+/// ```
 #[macro_export]
 macro_rules! obs_register_module {
-    ($t:ty) => {
+    (@register $t:ty, $load:expr) => {
         static mut OBS_MODULE: Option<$t> = None;
         static mut LOAD_CONTEXT: Option<$crate::module::LoadContext> = None;
 
@@ -89,7 +346,9 @@ macro_rules! obs_register_module {
         pub unsafe extern "C" fn obs_module_load() -> bool {
             let mut module = OBS_MODULE.as_mut().expect("Could not get current module!");
             let mut context = unsafe { $crate::module::LoadContext::new() };
-            let ret = module.load(&mut context);
+            let ret = ($load)(module, &mut context);
+            // Kept even if loading failed, as OBS refers to the sources registered before
+            // a panic.
             LOAD_CONTEXT = Some(context);
 
             ret
@@ -100,6 +359,8 @@ macro_rules! obs_register_module {
         pub unsafe extern "C" fn obs_module_unload() {
             let mut module = OBS_MODULE.as_mut().expect("Could not get current module!");
             module.unload();
+            $crate::module::clear_globals();
+            $crate::source::destroy_tint_effect();
         }
 
         #[allow(missing_safety_doc)]
@@ -109,6 +370,18 @@ macro_rules! obs_register_module {
             module.post_load();
         }
 
+        #[allow(missing_safety_doc)]
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_set_locale(locale: *const std::os::raw::c_char) {
+            $crate::module::set_locale(obs_current_module(), <$t>::default_locale(), locale);
+        }
+
+        #[allow(missing_safety_doc)]
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_free_locale() {
+            $crate::module::free_locale();
+        }
+
         #[allow(missing_safety_doc)]
         #[no_mangle]
         pub unsafe extern "C" fn obs_module_name() -> *const std::os::raw::c_char {
@@ -127,6 +400,27 @@ macro_rules! obs_register_module {
             <$t>::author().as_ptr()
         }
     };
+    ($t:ty) => {
+        $crate::obs_register_module!(@register $t, $crate::module::load_module);
+    };
+    ($t:ty, sources = $sources:expr) => {
+        $crate::obs_register_module!(@register $t, |module: &mut $t, context: &mut $crate::module::LoadContext| {
+            $crate::module::load_module_with_sources(module, context, $sources)
+        });
+    };
+}
+
+/// The module-global values, see `ModuleContext::set_global`.
+static MODULE_GLOBALS: Mutex<Vec<(TypeId, Arc<dyn Any + Send + Sync>)>> = Mutex::new(Vec::new());
+
+/// Drops the module-global values, after `Module::unload`. Called by OBS through
+/// `obs_register_module!`.
+#[doc(hidden)]
+pub fn clear_globals() {
+    let globals = std::mem::take(&mut *MODULE_GLOBALS.lock().unwrap_or_else(PoisonError::into_inner));
+
+    // Dropped outside of the lock, in case a destructor accesses the globals.
+    drop(globals);
 }
 
 pub struct ModuleContext {
@@ -147,4 +441,49 @@ impl ModuleContext {
     pub unsafe fn get_raw(&self) -> *mut obs_module_t {
         self.raw
     }
+
+    /// Stores a module-global value of the type `T`, replacing the previous one, if any, e.g.
+    /// a resource shared by all sources of the module, created in `Module::load`:
+    ///
+    /// ```rs
+    /// fn load(&mut self, load_context: &mut LoadContext) -> bool {
+    ///     self.context.set_global(SharedRuntime::new());
+    ///     // ...
+    /// }
+    ///
+    /// // Anywhere, e.g. in `CreatableSource::create`
+    /// let runtime = ModuleContext::global::<SharedRuntime>().unwrap();
+    /// ```
+    ///
+    /// The value is kept until the module is unloaded, and dropped after `Module::unload`,
+    /// so it is still available there. It is shared between threads, so any mutable state
+    /// must be synchronized, e.g. using a `Mutex`. Graphics resources must not be stored
+    /// without care, as they are dropped outside of the graphics context.
+    pub fn set_global<T: Any + Send + Sync>(&self, value: T) {
+        let value: Arc<dyn Any + Send + Sync> = Arc::new(value);
+        let previous = {
+            let mut globals = MODULE_GLOBALS.lock().unwrap_or_else(PoisonError::into_inner);
+
+            match globals.iter_mut().find(|(type_id, _)| *type_id == TypeId::of::<T>()) {
+                Some((_, global)) => Some(std::mem::replace(global, value)),
+                None => {
+                    globals.push((TypeId::of::<T>(), value));
+                    None
+                }
+            }
+        };
+
+        drop(previous);
+    }
+
+    /// The module-global value of the type `T`, see `set_global`. Returns `None` if no such
+    /// value has been set, or if the module has been unloaded.
+    pub fn global<T: Any + Send + Sync>() -> Option<Arc<T>> {
+        let globals = MODULE_GLOBALS.lock().unwrap_or_else(PoisonError::into_inner);
+
+        globals
+            .iter()
+            .find(|(type_id, _)| *type_id == TypeId::of::<T>())
+            .and_then(|(_, global)| global.clone().downcast::<T>().ok())
+    }
 }
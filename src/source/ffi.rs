@@ -1,19 +1,50 @@
-use super::properties::{Properties, SettingsContext};
+use super::properties::SettingsContext;
 use super::traits::*;
-use super::{EnumActiveContext, EnumAllContext, SourceContext};
-use std::ffi::c_void;
+use super::{EnumActiveContext, EnumAllContext, SourceContext, SourceType, TypeData, TypeDataBox};
+use std::ffi::{c_void, CStr, CString};
 use std::os::raw::c_char;
 use crate::graphics::*;
 use crate::context::*;
+use crate::audio::{AudioMixRequest, FilterAudioData};
+use crate::video::VideoFrame;
 
 use obs_sys::{
-    gs_effect_t, obs_data_t, obs_properties, obs_properties_create, obs_source_audio_mix,
-    obs_source_enum_proc_t, obs_source_t, size_t,
+    gs_effect_t, obs_audio_data, obs_data_t, obs_properties, obs_source_audio_mix,
+    obs_source_enum_proc_t, obs_source_get_name, obs_source_t, size_t, obs_source_frame,
+};
+#[cfg(debug_assertions)]
+use obs_sys::{
+    obs_filter_get_target, obs_source_get_base_height, obs_source_get_base_width,
+    obs_source_get_type,
 };
 
 pub(crate) struct DataWrapper<D> {
     pub(crate) settings: Option<SettingsContext>,
     pub(crate) data: Option<D>,
+    pub(crate) source: *mut obs_source_t,
+    /// Whether an empty size of a filter has already been reported, to avoid repeating
+    /// the warning every frame.
+    pub(crate) reported_empty_size: bool,
+    /// The name of the source used to prefix log messages, along with the raw name it was
+    /// decoded from, to detect renames.
+    pub(crate) cached_name: Option<(Option<CString>, String)>,
+    /// The last reported size, for sources registered with
+    /// `SourceInfoBuilder::enable_cached_size`.
+    pub(crate) size_hint: SizeHint,
+}
+
+/// The width and height last reported by a source, `None` when they have to be recomputed.
+#[derive(Default)]
+pub(crate) struct SizeHint {
+    pub(crate) width: Option<u32>,
+    pub(crate) height: Option<u32>,
+}
+
+impl SizeHint {
+    pub(crate) fn mark_dirty(&mut self) {
+        self.width = None;
+        self.height = None;
+    }
 }
 
 impl<D> Default for DataWrapper<D> {
@@ -21,19 +52,94 @@ impl<D> Default for DataWrapper<D> {
         Self {
             settings: None,
             data: None,
+            source: std::ptr::null_mut(),
+            reported_empty_size: false,
+            cached_name: None,
+            size_hint: SizeHint::default(),
         }
     }
 }
 
 impl<D> DataWrapper<D> {
-    pub fn new(settings: SettingsContext) -> Self {
+    /// Returns the name of the source, only re-reading it when the source has been renamed.
+    pub(crate) fn name(&mut self) -> &str {
+        let raw = if self.source.is_null() {
+            std::ptr::null()
+        } else {
+            unsafe { obs_source_get_name(self.source) }
+        };
+
+        let raw = if raw.is_null() {
+            None
+        } else {
+            Some(unsafe { CStr::from_ptr(raw) })
+        };
+
+        // The contents are compared, as the renamed string may reuse the address of the old one.
+        let outdated = match &self.cached_name {
+            Some((cached_raw, _)) => cached_raw.as_deref() != raw,
+            None => true,
+        };
+
+        if outdated {
+            let name = match raw {
+                Some(raw) => raw.to_string_lossy().into_owned(),
+                None => String::from("<unnamed>"),
+            };
+
+            self.cached_name = Some((raw.map(CStr::to_owned), name));
+        }
+
+        &self.cached_name.as_ref().unwrap().1
+    }
+
+    pub fn new(settings: SettingsContext, source: *mut obs_source_t) -> Self {
         Self {
             settings: Some(settings),
             data: None,
+            source,
+            reported_empty_size: false,
+            cached_name: None,
+            size_hint: SizeHint::default(),
         }
     }
 }
 
+/// A filter reporting a size of 0 is culled, even though its target has a valid size.
+/// Not implementing `GetWidthSource`/`GetHeightSource` makes OBS use the size of the target
+/// instead, which is usually what was intended.
+#[cfg(debug_assertions)]
+unsafe fn check_filter_size<D>(
+    data: *mut c_void,
+    dimension: &str,
+    value: u32,
+    get_target_value: unsafe extern "C" fn(*mut obs_source_t) -> u32,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+
+    if value != 0 || wrapper.reported_empty_size || wrapper.source.is_null() {
+        return;
+    }
+
+    if let Some(SourceType::FILTER) = SourceType::from_native(obs_source_get_type(wrapper.source)) {
+        let target = obs_filter_get_target(wrapper.source);
+
+        if !target.is_null() && get_target_value(target) != 0 {
+            wrapper.reported_empty_size = true;
+
+            crate::warning!(
+                "The filter `{}` reports a {} of 0, while its target does not, which causes the filter to be culled.",
+                std::any::type_name::<D>(),
+                dimension,
+            );
+        }
+    }
+}
+
+pub unsafe extern "C" fn free_type_data(type_data: *mut c_void) {
+    drop(Box::from_raw(type_data as *mut TypeDataBox));
+}
+
 pub unsafe extern "C" fn get_name<D, F: GetNameSource<D>>(
     _type_data: *mut c_void,
 ) -> *const c_char {
@@ -42,19 +148,58 @@ pub unsafe extern "C" fn get_name<D, F: GetNameSource<D>>(
 
 pub unsafe extern "C" fn get_width<D, F: GetWidthSource<D>>(data: *mut c_void) -> u32 {
     let context = PluginContext::<D>::from(data);
-    F::get_width(context)
+    let width = F::get_width(context);
+
+    #[cfg(debug_assertions)]
+    check_filter_size::<D>(data, "width", width, obs_source_get_base_width);
+
+    width
 }
 
 pub unsafe extern "C" fn get_height<D, F: GetHeightSource<D>>(data: *mut c_void) -> u32 {
     let context = PluginContext::<D>::from(data);
-    F::get_height(context)
+    let height = F::get_height(context);
+
+    #[cfg(debug_assertions)]
+    check_filter_size::<D>(data, "height", height, obs_source_get_base_height);
+
+    height
+}
+
+pub unsafe extern "C" fn get_width_cached<D, F: GetWidthSource<D>>(data: *mut c_void) -> u32 {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+
+    match wrapper.size_hint.width {
+        Some(width) => width,
+        None => {
+            let width = get_width::<D, F>(data);
+            let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+            wrapper.size_hint.width = Some(width);
+            width
+        }
+    }
+}
+
+pub unsafe extern "C" fn get_height_cached<D, F: GetHeightSource<D>>(data: *mut c_void) -> u32 {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+
+    match wrapper.size_hint.height {
+        Some(height) => height,
+        None => {
+            let height = get_height::<D, F>(data);
+            let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+            wrapper.size_hint.height = Some(height);
+            height
+        }
+    }
 }
 
 pub unsafe extern "C" fn create_default_data<D>(
-    _settings: *mut obs_data_t,
-    _source: *mut obs_source_t,
+    settings: *mut obs_data_t,
+    source: *mut obs_source_t,
 ) -> *mut c_void {
-    let data = Box::new(DataWrapper::<D>::default());
+    let settings = SettingsContext::from_raw(settings);
+    let data = Box::new(DataWrapper::<D>::new(settings, source));
     Box::into_raw(data) as *mut c_void
 }
 
@@ -63,7 +208,7 @@ pub unsafe extern "C" fn create<D, F: CreatableSource<D>>(
     source: *mut obs_source_t,
 ) -> *mut c_void {
     let settings = SettingsContext::from_raw(settings);
-    let mut wrapper = DataWrapper::new(settings);
+    let mut wrapper = DataWrapper::new(settings, source);
 
     let source = SourceContext { source };
 
@@ -79,10 +224,32 @@ pub unsafe extern "C" fn destroy<D>(data: *mut c_void) {
     drop(wrapper);
 }
 
+pub unsafe extern "C" fn get_defaults<D, F: GetDefaultsSource<D>>(settings: *mut obs_data_t) {
+    let mut settings = SettingsContext::from_raw(settings);
+    F::get_defaults(&mut settings);
+}
+
+pub unsafe extern "C" fn get_defaults2<D, F: GetDefaults2Source<D>>(
+    type_data: *mut c_void,
+    settings: *mut obs_data_t,
+) {
+    let type_data = TypeData::from_raw(type_data);
+    let mut settings = SettingsContext::from_raw(settings);
+    F::get_defaults2(&type_data, &mut settings);
+}
+
 pub unsafe extern "C" fn update<D, F: UpdateSource<D>>(
     data: *mut c_void,
     settings: *mut obs_data_t,
 ) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+
+    // Refresh the settings, so that they are not stale when accessed from callbacks which
+    // do not receive them from OBS, such as `get_properties`.
+    wrapper.settings = Some(SettingsContext::from_raw(settings));
+    // The size usually depends on the settings.
+    wrapper.size_hint.mark_dirty();
+
     let context = PluginContext::<D>::from(data);
     F::update(context);
 }
@@ -93,23 +260,50 @@ pub unsafe extern "C" fn video_render<D, F: VideoRenderSource<D>>(
 ) {
     let context = PluginContext::<D>::from(data);
     let mut graphics_context = GraphicsContext::get_current().unwrap();
+    GraphicsResourceGraveyard::global().drain(&graphics_context);
     F::video_render(context, &mut graphics_context);
 }
 
 pub unsafe extern "C" fn audio_render<D, F: AudioRenderSource<D>>(
     data: *mut ::std::os::raw::c_void,
-    _ts_out: *mut u64,
-    _audio_output: *mut obs_source_audio_mix,
-    _mixers: u32,
-    _channels: size_t,
-    _sample_rate: size_t,
+    ts_out: *mut u64,
+    audio_output: *mut obs_source_audio_mix,
+    mixers: u32,
+    channels: size_t,
+    sample_rate: size_t,
 ) -> bool {
-    // TODO
+    let context = PluginContext::<D>::from(data);
+    let mut request = AudioMixRequest::from_raw(
+        ts_out,
+        audio_output,
+        mixers,
+        channels as usize,
+        sample_rate as usize,
+    );
+
+    // If false, OBS marks the source as `audio_pending`
+    F::audio_render(context, &mut request) && request.has_timestamp()
+}
+
+pub unsafe extern "C" fn filter_audio<D, F: FilterAudioSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    audio: *mut obs_audio_data,
+) -> *mut obs_audio_data {
+    let context = PluginContext::<D>::from(data);
+    let mut audio_data = FilterAudioData::from_raw(audio);
+    F::filter_audio(context, &mut audio_data);
+
+    audio
+}
 
+pub unsafe extern "C" fn filter_video<D, F: FilterVideoSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    frame: *mut obs_source_frame,
+) -> *mut obs_source_frame {
     let context = PluginContext::<D>::from(data);
-    F::audio_render(context);
+    F::filter_video(context, VideoFrame::from_raw_mut(frame));
 
-    true // indicates success. if false, marks the source as `audio_pending`
+    frame
 }
 
 pub unsafe extern "C" fn get_properties<D, F: GetPropertiesSource<D>>(
@@ -125,6 +319,21 @@ pub unsafe extern "C" fn get_properties<D, F: GetPropertiesSource<D>>(
     properties_ptr
 }
 
+pub unsafe extern "C" fn get_properties_apply_settings<
+    D,
+    F: GetPropertiesSource<D> + ApplySettingsProperties<D>,
+>(
+    data: *mut ::std::os::raw::c_void,
+) -> *mut obs_properties {
+    let context = PluginContext::<D>::from(data);
+    let mut properties = F::get_properties(context);
+
+    let context = PluginContext::<D>::from(data);
+    F::apply_settings(context, &mut properties);
+
+    properties.leak()
+}
+
 pub unsafe extern "C" fn activate<D, F: ActivateSource<D>>(
     data: *mut ::std::os::raw::c_void,
 ) {
@@ -159,21 +368,23 @@ pub unsafe extern "C" fn hide<D, F: HideSource<D>>(
 
 pub unsafe extern "C" fn enum_active_sources<D, F: EnumActiveSource<D>>(
     data: *mut ::std::os::raw::c_void,
-    _enum_callback: obs_source_enum_proc_t,
-    _param: *mut ::std::os::raw::c_void,
+    enum_callback: obs_source_enum_proc_t,
+    param: *mut ::std::os::raw::c_void,
 ) {
+    let wrapper: &DataWrapper<D> = &*(data as *const DataWrapper<D>);
+    let enum_context = EnumActiveContext::new(wrapper.source, enum_callback, param);
     let context = PluginContext::<D>::from(data);
-    let enum_context = EnumActiveContext {};
     F::enum_active_sources(context, &enum_context);
 }
 
 pub unsafe extern "C" fn enum_all_sources<D, F: EnumAllSource<D>>(
     data: *mut ::std::os::raw::c_void,
-    _enum_callback: obs_source_enum_proc_t,
-    _param: *mut ::std::os::raw::c_void,
+    enum_callback: obs_source_enum_proc_t,
+    param: *mut ::std::os::raw::c_void,
 ) {
+    let wrapper: &DataWrapper<D> = &*(data as *const DataWrapper<D>);
+    let enum_context = EnumAllContext::new(wrapper.source, enum_callback, param);
     let context = PluginContext::<D>::from(data);
-    let enum_context = EnumAllContext {};
     F::enum_all_sources(context, &enum_context);
 }
 
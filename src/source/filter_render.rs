@@ -0,0 +1,121 @@
+//! Manual control over the rendering of filters, for filters which cannot be expressed using
+//! the closure of `SourceContext::process_filter`.
+
+use super::{render_target_format, SourceContext, SourceType};
+use crate::context::*;
+use crate::graphics::*;
+use std::ffi::CStr;
+use obs_sys::{
+    obs_source_get_type, obs_source_process_filter_begin, obs_source_process_filter_end,
+    obs_source_process_filter_tech_end, obs_source_skip_video_filter,
+};
+
+/// The rendering of a filter between `obs_source_process_filter_begin` and
+/// `obs_source_process_filter_end`, started using `FilterRenderSession::begin`.
+///
+/// Once begun, the input of the filter has been rendered to an intermediate texture, and the
+/// session must be ended using `end` or `end_tech`, which draw the input using the effect.
+/// In between, effect parameters can be set, and auxiliary textures rendered, e.g. using
+/// `TextureRender::render`:
+///
+/// ```rs
+/// if let Some(mut session) = FilterRenderSession::begin(source, ColorFormatKind::RGBA, GraphicsAllowDirectRendering::NoDirectRendering) {
+///     data.mask.render([cx, cy], Color([0.0; 4]), session.context().graphics(), |graphics_context| {
+///         graphics_context.draw_effect(&mask_effect, cstr!("Draw"), &shapes, [cx, cy]);
+///     });
+///
+///     if let Some(mask) = data.mask.get_texture() {
+///         data.mask_param.set_param_value(mask, session.context());
+///     }
+///
+///     session.end(effect, (cx, cy));
+/// }
+/// ```
+///
+/// A session dropped without being ended draws the input of the filter unmodified instead, and
+/// logs a warning, so that every begin is paired with exactly one end.
+pub struct FilterRenderSession<'a> {
+    source: &'a mut SourceContext,
+    context: FilterContext,
+    format: ColorFormatKind,
+    ended: bool,
+}
+
+impl<'a> FilterRenderSession<'a> {
+    /// Renders the input of the filter to an intermediate texture of the `format`, see
+    /// `SourceContext::process_filter`. Returns `None` if the source is not a filter, or if
+    /// the filter should not render in this frame, e.g. because its target is not visible.
+    pub fn begin(
+        source: &'a mut SourceContext,
+        format: ColorFormatKind,
+        direct: GraphicsAllowDirectRendering,
+    ) -> Option<Self> {
+        unsafe {
+            match SourceType::from_native(obs_source_get_type(source.source)) {
+                Some(SourceType::FILTER) => {}
+                _ => return None,
+            }
+
+            let format = render_target_format(format);
+
+            if !obs_source_process_filter_begin(source.source, format.into_raw(), direct.as_raw()) {
+                return None;
+            }
+
+            Some(Self {
+                source,
+                context: FilterContext::enter().unwrap(),
+                format,
+                ended: false,
+            })
+        }
+    }
+
+    /// The context to set the parameters of effects with, within the session.
+    pub fn context(&mut self) -> &mut FilterContext {
+        &mut self.context
+    }
+
+    /// The color format of the intermediate texture, which may differ from the requested one,
+    /// see `SourceContext::process_filter_with_format`.
+    pub fn format(&self) -> ColorFormatKind {
+        self.format
+    }
+
+    /// Draws the input of the filter using the `Draw` technique of the `effect`, with the
+    /// dimensions `(cx, cy)`, ending the session.
+    pub fn end(mut self, effect: &mut GraphicsEffect, (cx, cy): (u32, u32)) {
+        self.ended = true;
+
+        unsafe {
+            obs_source_process_filter_end(self.source.source, effect.as_ptr(), cx, cy);
+        }
+    }
+
+    /// Like `end`, but draws using the `technique` of the `effect`.
+    pub fn end_tech(mut self, effect: &mut GraphicsEffect, technique: &CStr, (cx, cy): (u32, u32)) {
+        self.ended = true;
+
+        unsafe {
+            obs_source_process_filter_tech_end(
+                self.source.source,
+                effect.as_ptr(),
+                cx,
+                cy,
+                technique.as_ptr(),
+            );
+        }
+    }
+}
+
+impl<'a> Drop for FilterRenderSession<'a> {
+    fn drop(&mut self) {
+        if !self.ended {
+            crate::warning!("A `FilterRenderSession` was dropped without being ended, the filter is skipped.");
+
+            unsafe {
+                obs_source_skip_video_filter(self.source.source);
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Input events, which can be forwarded to interactive sources, such as browser sources.
+//!
+//! A source composing other sources may forward the input it receives to the appropriate child:
+//!
+//! ```rs
+//! let event = MouseEvent {
+//!     modifiers: InteractionFlags::empty(),
+//!     // Translated into the coordinate space of the child
+//!     x: x - child_offset[0],
+//!     y: y - child_offset[1],
+//! };
+//!
+//! child.send_mouse_click(&event, MouseButton::Left, false, 1);
+//! ```
+
+use super::SourceContext;
+use bitflags::bitflags;
+use std::ffi::CString;
+
+use obs_sys::{
+    obs_interaction_flags_INTERACT_ALT_KEY, obs_interaction_flags_INTERACT_CAPS_KEY,
+    obs_interaction_flags_INTERACT_COMMAND_KEY, obs_interaction_flags_INTERACT_CONTROL_KEY,
+    obs_interaction_flags_INTERACT_IS_KEY_PAD, obs_interaction_flags_INTERACT_IS_LEFT,
+    obs_interaction_flags_INTERACT_IS_RIGHT, obs_interaction_flags_INTERACT_MOUSE_LEFT,
+    obs_interaction_flags_INTERACT_MOUSE_MIDDLE, obs_interaction_flags_INTERACT_MOUSE_RIGHT,
+    obs_interaction_flags_INTERACT_NUMLOCK_KEY, obs_interaction_flags_INTERACT_SHIFT_KEY,
+    obs_key_event, obs_mouse_button_type, obs_mouse_button_type_MOUSE_LEFT,
+    obs_mouse_button_type_MOUSE_MIDDLE, obs_mouse_button_type_MOUSE_RIGHT, obs_mouse_event,
+    obs_source_send_focus, obs_source_send_key_click, obs_source_send_mouse_click,
+    obs_source_send_mouse_move, obs_source_send_mouse_wheel,
+};
+
+bitflags! {
+    /// The modifier keys and mouse buttons held during an input event.
+    pub struct InteractionFlags: u32 {
+        const CAPS_KEY = obs_interaction_flags_INTERACT_CAPS_KEY;
+        const SHIFT_KEY = obs_interaction_flags_INTERACT_SHIFT_KEY;
+        const CONTROL_KEY = obs_interaction_flags_INTERACT_CONTROL_KEY;
+        const ALT_KEY = obs_interaction_flags_INTERACT_ALT_KEY;
+        const MOUSE_LEFT = obs_interaction_flags_INTERACT_MOUSE_LEFT;
+        const MOUSE_MIDDLE = obs_interaction_flags_INTERACT_MOUSE_MIDDLE;
+        const MOUSE_RIGHT = obs_interaction_flags_INTERACT_MOUSE_RIGHT;
+        const COMMAND_KEY = obs_interaction_flags_INTERACT_COMMAND_KEY;
+        const NUMLOCK_KEY = obs_interaction_flags_INTERACT_NUMLOCK_KEY;
+        const IS_KEY_PAD = obs_interaction_flags_INTERACT_IS_KEY_PAD;
+        const IS_LEFT = obs_interaction_flags_INTERACT_IS_LEFT;
+        const IS_RIGHT = obs_interaction_flags_INTERACT_IS_RIGHT;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+impl MouseButton {
+    pub fn as_raw(&self) -> obs_mouse_button_type {
+        match self {
+            MouseButton::Left => obs_mouse_button_type_MOUSE_LEFT,
+            MouseButton::Middle => obs_mouse_button_type_MOUSE_MIDDLE,
+            MouseButton::Right => obs_mouse_button_type_MOUSE_RIGHT,
+        }
+    }
+
+    #[allow(non_upper_case_globals)]
+    pub fn from_raw(raw: obs_mouse_button_type) -> Option<Self> {
+        match raw {
+            obs_mouse_button_type_MOUSE_LEFT => Some(MouseButton::Left),
+            obs_mouse_button_type_MOUSE_MIDDLE => Some(MouseButton::Middle),
+            obs_mouse_button_type_MOUSE_RIGHT => Some(MouseButton::Right),
+            _ => None,
+        }
+    }
+}
+
+/// A mouse event, with the position relative to the top left corner of the source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MouseEvent {
+    pub modifiers: InteractionFlags,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl MouseEvent {
+    pub fn from_raw(raw: &obs_mouse_event) -> Self {
+        Self {
+            modifiers: InteractionFlags::from_bits_truncate(raw.modifiers),
+            x: raw.x,
+            y: raw.y,
+        }
+    }
+
+    pub fn as_raw(&self) -> obs_mouse_event {
+        obs_mouse_event {
+            modifiers: self.modifiers.bits(),
+            x: self.x,
+            y: self.y,
+        }
+    }
+}
+
+/// A keyboard event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub modifiers: InteractionFlags,
+    /// The text produced by the key press, if any.
+    pub text: Option<CString>,
+    pub native_modifiers: u32,
+    pub native_scancode: u32,
+    pub native_vkey: u32,
+}
+
+impl SourceContext {
+    /// Sends a mouse click to the source.
+    ///
+    /// The coordinates of the `event` must already be relative to this source; translating
+    /// them from the coordinate space of a parent source is the responsibility of the caller.
+    pub fn send_mouse_click(
+        &mut self,
+        event: &MouseEvent,
+        button: MouseButton,
+        mouse_up: bool,
+        click_count: u32,
+    ) {
+        let event = event.as_raw();
+
+        unsafe {
+            obs_source_send_mouse_click(
+                self.source,
+                &event,
+                button.as_raw() as i32,
+                mouse_up,
+                click_count,
+            );
+        }
+    }
+
+    /// Sends a mouse move to the source. See `send_mouse_click` regarding the coordinates.
+    pub fn send_mouse_move(&mut self, event: &MouseEvent, mouse_leave: bool) {
+        let event = event.as_raw();
+
+        unsafe {
+            obs_source_send_mouse_move(self.source, &event, mouse_leave);
+        }
+    }
+
+    /// Sends a mouse wheel scroll to the source. See `send_mouse_click` regarding the
+    /// coordinates.
+    pub fn send_mouse_wheel(&mut self, event: &MouseEvent, delta: [i32; 2]) {
+        let event = event.as_raw();
+
+        unsafe {
+            obs_source_send_mouse_wheel(self.source, &event, delta[0], delta[1]);
+        }
+    }
+
+    /// Notifies the source about it gaining or losing the focus.
+    pub fn send_focus(&mut self, focus: bool) {
+        unsafe {
+            obs_source_send_focus(self.source, focus);
+        }
+    }
+
+    /// Sends a key press or release to the source.
+    pub fn send_key_click(&mut self, event: &KeyEvent, key_up: bool) {
+        let event = obs_key_event {
+            modifiers: event.modifiers.bits(),
+            // OBS does not modify the text, despite the mutable pointer.
+            text: event
+                .text
+                .as_ref()
+                .map(|text| text.as_ptr() as *mut _)
+                .unwrap_or(std::ptr::null_mut()),
+            native_modifiers: event.native_modifiers,
+            native_scancode: event.native_scancode,
+            native_vkey: event.native_vkey,
+        };
+
+        unsafe {
+            obs_source_send_key_click(self.source, &event, key_up);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Remote control of media sources, such as the media source or the VLC source, from other
+//! plugins.
+//!
+//! All of the methods only work with sources that declare the `OBS_SOURCE_CONTROLLABLE_MEDIA`
+//! output flag, and do nothing for other sources.
+
+use super::SourceContext;
+
+use obs_sys::{
+    obs_media_state, obs_media_state_OBS_MEDIA_STATE_BUFFERING,
+    obs_media_state_OBS_MEDIA_STATE_ENDED, obs_media_state_OBS_MEDIA_STATE_ERROR,
+    obs_media_state_OBS_MEDIA_STATE_OPENING,
+    obs_media_state_OBS_MEDIA_STATE_PAUSED, obs_media_state_OBS_MEDIA_STATE_PLAYING,
+    obs_media_state_OBS_MEDIA_STATE_STOPPED, obs_source_media_get_duration,
+    obs_source_media_get_state, obs_source_media_get_time, obs_source_media_next,
+    obs_source_media_play_pause, obs_source_media_previous, obs_source_media_restart,
+    obs_source_media_set_time, obs_source_media_stop,
+};
+
+/// The playback state of a media source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MediaState {
+    None,
+    Playing,
+    Opening,
+    Buffering,
+    Paused,
+    Stopped,
+    Ended,
+    Error,
+}
+
+impl MediaState {
+    pub fn from_raw(raw: obs_media_state) -> Self {
+        match raw {
+            obs_media_state_OBS_MEDIA_STATE_PLAYING => MediaState::Playing,
+            obs_media_state_OBS_MEDIA_STATE_OPENING => MediaState::Opening,
+            obs_media_state_OBS_MEDIA_STATE_BUFFERING => MediaState::Buffering,
+            obs_media_state_OBS_MEDIA_STATE_PAUSED => MediaState::Paused,
+            obs_media_state_OBS_MEDIA_STATE_STOPPED => MediaState::Stopped,
+            obs_media_state_OBS_MEDIA_STATE_ENDED => MediaState::Ended,
+            obs_media_state_OBS_MEDIA_STATE_ERROR => MediaState::Error,
+            _ => MediaState::None,
+        }
+    }
+}
+
+impl SourceContext {
+    /// Pauses the media if `pause` is `true`, resumes it otherwise.
+    ///
+    /// Note: only works with sources that are controllable media.
+    pub fn media_play_pause(&mut self, pause: bool) {
+        unsafe {
+            obs_source_media_play_pause(self.source, pause);
+        }
+    }
+
+    /// Note: only works with sources that are controllable media.
+    pub fn media_restart(&mut self) {
+        unsafe {
+            obs_source_media_restart(self.source);
+        }
+    }
+
+    /// Note: only works with sources that are controllable media.
+    pub fn media_stop(&mut self) {
+        unsafe {
+            obs_source_media_stop(self.source);
+        }
+    }
+
+    /// Skips to the next item of a playlist.
+    ///
+    /// Note: only works with sources that are controllable media.
+    pub fn media_next(&mut self) {
+        unsafe {
+            obs_source_media_next(self.source);
+        }
+    }
+
+    /// Skips to the previous item of a playlist.
+    ///
+    /// Note: only works with sources that are controllable media.
+    pub fn media_previous(&mut self) {
+        unsafe {
+            obs_source_media_previous(self.source);
+        }
+    }
+
+    /// The duration of the media, in milliseconds.
+    ///
+    /// Note: only works with sources that are controllable media.
+    pub fn media_duration(&self) -> i64 {
+        unsafe { obs_source_media_get_duration(self.source) }
+    }
+
+    /// The playback position, in milliseconds.
+    ///
+    /// Note: only works with sources that are controllable media.
+    pub fn media_time(&self) -> i64 {
+        unsafe { obs_source_media_get_time(self.source) }
+    }
+
+    /// Seeks to the playback position `ms`, in milliseconds.
+    ///
+    /// Note: only works with sources that are controllable media.
+    pub fn media_set_time(&mut self, ms: i64) {
+        unsafe {
+            obs_source_media_set_time(self.source, ms);
+        }
+    }
+
+    /// Note: returns `MediaState::None` for sources that are not controllable media.
+    pub fn media_state(&self) -> MediaState {
+        unsafe { MediaState::from_raw(obs_source_media_get_state(self.source)) }
+    }
+}
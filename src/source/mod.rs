@@ -1,15 +1,33 @@
 #![allow(non_upper_case_globals)]
 
 use paste::item;
+use bitflags::bitflags;
+use cstr::cstr;
 use crate::context::*;
+use crate::video::{
+    DeinterlaceFieldOrder, DeinterlaceMode, OutputVideoFrame, VideoFrameRef, VideoRotation,
+};
 
-mod ffi;
+pub(crate) mod ffi;
 
+pub mod filter_render;
+pub mod interaction;
+pub mod media;
+pub mod multipass;
+mod private;
 pub mod properties;
+pub mod signal;
 pub mod traits;
+pub mod transition;
 
+pub use filter_render::*;
+pub use interaction::*;
+pub use media::*;
+pub use multipass::*;
 pub use properties::*;
+pub use signal::*;
 pub use traits::*;
+pub use transition::*;
 
 use obs_sys::{
     obs_filter_get_target, obs_source_get_base_height, obs_source_get_base_width,
@@ -17,16 +35,48 @@ use obs_sys::{
     obs_source_process_filter_end, obs_source_skip_video_filter, obs_source_t, obs_source_type,
     obs_source_type_OBS_SOURCE_TYPE_FILTER, obs_source_type_OBS_SOURCE_TYPE_INPUT,
     obs_source_type_OBS_SOURCE_TYPE_SCENE, obs_source_type_OBS_SOURCE_TYPE_TRANSITION,
-    obs_source_update, obs_source_update_properties, OBS_SOURCE_VIDEO, obs_icon_type_OBS_ICON_TYPE_UNKNOWN
+    obs_source_update, obs_source_update_properties, OBS_SOURCE_VIDEO, OBS_SOURCE_AUDIO, obs_icon_type_OBS_ICON_TYPE_UNKNOWN,
+    obs_source_get_proc_handler, obs_source_get_signal_handler, proc_handler_add,
+    signal_handler_connect, calldata_t, obs_get_source_by_name, obs_source_addref,
+    obs_source_release, obs_weak_source_t, obs_source_get_weak_source, obs_weak_source_get_source,
+    obs_weak_source_release, obs_source_inc_showing, obs_source_dec_showing, obs_source_duplicate,
+    obs_source_get_name, obs_source_get_frame, obs_source_audio_active,
+    obs_source_set_async_rotation,
+    obs_source_set_audio_active, obs_source_muted, obs_source_set_muted,
+    obs_source_get_flags, obs_source_set_flags, OBS_SOURCE_FLAG_FORCE_MONO,
+    obs_source_get_output_flags, obs_source_get_width,
+    obs_source_get_height, obs_set_output_source, obs_get_output_source, MAX_CHANNELS,
+    obs_filter_get_parent, obs_source_get_ref, obs_source_get_id, obs_enum_sources,
+    obs_source_process_filter_tech_end, gs_blend_state_push, gs_blend_state_pop, gs_blend_function,
+    gs_blend_type_GS_BLEND_ONE, gs_blend_type_GS_BLEND_INVSRCALPHA, obs_source_enum_proc_t,
+    obs_source_video_render, OBS_SOURCE_COMPOSITE, obs_source_get_speaker_layout,
+    obs_source_set_deinterlace_mode, obs_source_get_deinterlace_mode,
+    obs_source_set_deinterlace_field_order, obs_source_get_deinterlace_field_order,
+    obs_source_enum_filters, obs_source_get_filter_by_name, OBS_SOURCE_ASYNC,
+    OBS_SOURCE_ASYNC_VIDEO, OBS_SOURCE_CUSTOM_DRAW, OBS_SOURCE_INTERACTION,
+    OBS_SOURCE_DO_NOT_SELF_MONITOR, OBS_SOURCE_CAP_DISABLED, OBS_SOURCE_MONITOR_BY_DEFAULT,
+    OBS_SOURCE_SUBMIX, OBS_SOURCE_CONTROLLABLE_MEDIA, OBS_SOURCE_DO_NOT_DUPLICATE,
+    OBS_SOURCE_DEPRECATED, gs_projection_push, gs_projection_pop, gs_effect_t, gs_effect_create,
+    gs_effect_destroy,
+    gs_effect_get_param_by_name, gs_effect_set_vec4, vec4, vec4__bindgen_ty_1,
 };
 
 use super::{
+    audio::{Audio, AudioOutputInfo, SpeakerLayoutKind},
+    calldata::Calldata,
+    data::DataObject,
     graphics::{
         GraphicsAllowDirectRendering, ColorFormatKind, GraphicsEffect, GraphicsContext, FilterContext,
+        GraphicsContextDependentEnabled, GraphicsError, Texture, TextureRender,
     },
 };
 
 use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::any::Any;
+use std::sync::{Mutex, PoisonError};
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+use std::ffi::{c_void, CStr};
 
 /// OBS source type
 ///
@@ -60,14 +110,84 @@ impl SourceType {
     }
 }
 
+bitflags! {
+    /// Flags of a source instance, see `SourceContext::get_flags`.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_get_flags)
+    pub struct SourceFlags: u32 {
+        /// Downmixes the audio of the source to mono.
+        const FORCE_MONO = OBS_SOURCE_FLAG_FORCE_MONO;
+    }
+}
+
+bitflags! {
+    /// The capabilities of a source type, see `SourceInfoBuilder::with_output_flags`.
+    ///
+    /// `VIDEO`, `AUDIO` and `COMPOSITE` are derived from the enabled callbacks by
+    /// `SourceInfoBuilder::build`, and rarely need to be set manually.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_info.output_flags)
+    pub struct OutputFlags: u32 {
+        /// The source renders video, using `VideoRenderSource`, or outputs asynchronous video
+        /// frames, along with `ASYNC`.
+        const VIDEO = OBS_SOURCE_VIDEO;
+        /// The source outputs audio.
+        const AUDIO = OBS_SOURCE_AUDIO;
+        /// The source outputs its video asynchronously, using `SourceContext::output_video`,
+        /// instead of rendering it.
+        const ASYNC = OBS_SOURCE_ASYNC;
+        /// Shorthand for `ASYNC | VIDEO`.
+        const ASYNC_VIDEO = OBS_SOURCE_ASYNC_VIDEO;
+        /// The source draws itself, without OBS setting up an effect, see
+        /// `VideoRenderSource`, which then receives no effect to use.
+        const CUSTOM_DRAW = OBS_SOURCE_CUSTOM_DRAW;
+        /// The source handles mouse and keyboard interaction.
+        const INTERACTION = OBS_SOURCE_INTERACTION;
+        /// The source renders other sources, see `CompositeSource`.
+        const COMPOSITE = OBS_SOURCE_COMPOSITE;
+        /// Sources of this type are not duplicated, e.g. because they hold exclusive access to
+        /// a device. Duplicating a scene references the same source instead of copying it, and
+        /// `SourceRef::duplicate` returns `None`.
+        const DO_NOT_DUPLICATE = OBS_SOURCE_DO_NOT_DUPLICATE;
+        /// The source type is obsolete, it is hidden from the list of sources the user can
+        /// add, but existing sources of the type keep working.
+        const DEPRECATED = OBS_SOURCE_DEPRECATED;
+        /// The source does not monitor its own audio, e.g. when it captures the audio output.
+        const DO_NOT_SELF_MONITOR = OBS_SOURCE_DO_NOT_SELF_MONITOR;
+        /// The source type is shown, but cannot be added by the user.
+        const CAP_DISABLED = OBS_SOURCE_CAP_DISABLED;
+        /// The audio of the source is monitored by default.
+        const MONITOR_BY_DEFAULT = OBS_SOURCE_MONITOR_BY_DEFAULT;
+        /// The source mixes the audio of other sources.
+        const SUBMIX = OBS_SOURCE_SUBMIX;
+        /// The media playback of the source can be controlled, e.g. paused or restarted.
+        const CONTROLLABLE_MEDIA = OBS_SOURCE_CONTROLLABLE_MEDIA;
+    }
+}
+
 /// Context wrapping an OBS source - video / audio elements which are displayed to the screen.
 ///
+/// # Thread safety
+///
+/// `SourceContext`, `SourceRef` and `WeakSourceRef` are neither `Send` nor `Sync`. Many
+/// operations on sources, such as rendering or processing filters, must only happen on the
+/// thread OBS invokes the corresponding callback on, and the source may be destroyed as soon as
+/// the callbacks stop, so these handles must stay on the thread they were obtained on.
+///
+/// To interact with a source from another thread, e.g. a worker thread of the plugin, use
+/// `SourceContext::to_send`, which returns a `SendSource`. It keeps the source alive and only
+/// exposes the operations OBS synchronizes internally.
+///
 /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_t)
 pub struct SourceContext {
     source: *mut obs_source_t,
 }
 
 impl SourceContext {
+    pub(crate) fn as_raw(&self) -> *mut obs_source_t {
+        self.source
+    }
+
     /// Run a function on the next source in the filter chain.
     ///
     /// Note: only works with sources that are filters.
@@ -83,20 +203,331 @@ impl SourceContext {
         }
     }
 
+    /// Run a function on the source the filter is applied to, which, unlike the target, is the
+    /// same for all filters of the source. The source is not kept alive beyond the call.
+    ///
+    /// Returns `None` if the source is not a filter, or is not applied to any source.
+    ///
+    /// Note: only works with sources that are filters.
+    pub fn do_with_parent<R, F: FnOnce(&SourceContext) -> R>(&self, func: F) -> Option<R> {
+        unsafe {
+            if let Some(SourceType::FILTER) =
+                SourceType::from_native(obs_source_get_type(self.source))
+            {
+                let parent = obs_filter_get_parent(self.source);
+
+                if !parent.is_null() {
+                    return Some(func(&SourceContext { source: parent }));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Creates a handle to the source, which may be moved to other threads, see `SendSource`.
+    /// The handle keeps the source alive until dropped.
+    pub fn to_send(&self) -> SendSource {
+        unsafe {
+            obs_source_addref(self.source);
+        }
+
+        SendSource {
+            inner: SourceRef {
+                inner: SourceContext { source: self.source },
+            },
+        }
+    }
+
+    /// Returns an owning reference to the source the filter is applied to, see
+    /// `do_with_parent`.
+    ///
+    /// Note: only works with sources that are filters.
+    pub fn get_parent(&self) -> Option<SourceRef> {
+        self.do_with_parent(|parent| unsafe { SourceRef::from_raw(obs_source_get_ref(parent.source)) })
+            .flatten()
+    }
+
+    /// Returns owning references to the filters applied to the source, in the order they
+    /// are applied in.
+    ///
+    /// To access the other filters of the same source from within a filter, call this on the
+    /// source the filter is applied to, see `get_parent`.
+    pub fn get_filters(&self) -> Vec<SourceRef> {
+        unsafe extern "C" fn enum_filter(
+            _parent: *mut obs_source_t,
+            child: *mut obs_source_t,
+            param: *mut c_void,
+        ) {
+            let filters = &mut *(param as *mut Vec<SourceRef>);
+
+            if let Some(filter) = SourceRef::from_raw(obs_source_get_ref(child)) {
+                filters.push(filter);
+            }
+        }
+
+        let mut filters = Vec::new();
+
+        unsafe {
+            obs_source_enum_filters(
+                self.source,
+                Some(enum_filter),
+                &mut filters as *mut Vec<SourceRef> as *mut c_void,
+            );
+        }
+
+        filters
+    }
+
+    /// Returns the first filter of the type `id` applied to the source, e.g. for filters of
+    /// a suite of filters to coordinate with each other.
+    pub fn find_filter(&self, id: &CStr) -> Option<SourceRef> {
+        let id = id.to_string_lossy();
+
+        self.get_filters()
+            .into_iter()
+            .find(|filter| filter.get_type_id().as_deref() == Some(id.as_ref()))
+    }
+
+    /// Returns the filter applied to the source with the user-facing `name`.
+    pub fn get_filter_by_name(&self, name: &CStr) -> Option<SourceRef> {
+        unsafe { SourceRef::from_raw(obs_source_get_filter_by_name(self.source, name.as_ptr())) }
+    }
+
+    /// The id of the type of the source, e.g. `v4l2_input` or `image_source`.
+    pub fn get_type_id(&self) -> Option<String> {
+        unsafe {
+            let id = obs_source_get_id(self.source);
+
+            if id.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(id).to_string_lossy().into_owned())
+            }
+        }
+    }
+
     /// Return a unique id for the filter
     pub fn id(&self) -> usize {
         self.source as usize
     }
 
+    /// The user-facing name of the source.
+    pub fn name(&self) -> Option<String> {
+        unsafe {
+            let name = obs_source_get_name(self.source);
+
+            if name.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(name).to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    /// Retrieves the current frame of an asynchronous video source, such as a media source
+    /// or a video capture device, without rendering it. The frame is released when the returned
+    /// reference is dropped.
+    ///
+    /// Returns `None` for synchronous sources, or when no frame is available. When called by a
+    /// filter, use `do_with_target` to get the frame of the filtered source.
+    pub fn get_frame(&mut self) -> Option<VideoFrameRef> {
+        unsafe { VideoFrameRef::from_raw(self.source, obs_source_get_frame(self.source)) }
+    }
+
+    /// Outputs a frame of an asynchronous video source, which is displayed once its timestamp
+    /// is reached.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_output_video2)
+    pub fn output_video(&mut self, frame: &OutputVideoFrame) {
+        unsafe {
+            frame.output(self.source);
+        }
+    }
+
+    /// Sets the rotation OBS applies to the frames output by an asynchronous video source,
+    /// e.g. for cameras mounted sideways, or videos with rotation metadata. Unlike the flip
+    /// of a frame, see `OutputVideoFrame::with_flip`, the rotation applies to all subsequent
+    /// frames, until changed.
+    ///
+    /// The width and height of the source are swapped for rotations by 90 and 270 degrees.
+    pub fn set_async_rotation(&mut self, rotation: VideoRotation) {
+        unsafe {
+            obs_source_set_async_rotation(self.source, rotation.as_degrees() as _);
+        }
+    }
+
+    /// Sets the deinterlacing OBS applies to the frames output by an asynchronous video
+    /// source, e.g. a capture card receiving an interlaced signal.
+    pub fn set_deinterlace_mode(&mut self, mode: DeinterlaceMode) {
+        unsafe {
+            obs_source_set_deinterlace_mode(self.source, mode.into_raw());
+        }
+    }
+
+    pub fn get_deinterlace_mode(&self) -> DeinterlaceMode {
+        unsafe { DeinterlaceMode::from_raw(obs_source_get_deinterlace_mode(self.source)) }
+    }
+
+    /// Sets which field of the interlaced frames comes first. Only relevant if a
+    /// deinterlacing mode is set, see `set_deinterlace_mode`.
+    pub fn set_deinterlace_field_order(&mut self, field_order: DeinterlaceFieldOrder) {
+        unsafe {
+            obs_source_set_deinterlace_field_order(self.source, field_order.into_raw());
+        }
+    }
+
+    pub fn get_deinterlace_field_order(&self) -> DeinterlaceFieldOrder {
+        unsafe {
+            DeinterlaceFieldOrder::from_raw(obs_source_get_deinterlace_field_order(self.source))
+        }
+    }
+
+    /// Renders the video of the source within the current render target, e.g. to render
+    /// a child source within `VideoRenderSource::video_render` of a composite source, see
+    /// `CompositeSource`.
+    ///
+    /// The source is rendered at the origin with its base size, so transformations, such as
+    /// `gs_matrix_translate3f`, must be applied beforehand to position it.
+    pub fn video_render(&mut self, _context: &GraphicsContext) {
+        unsafe {
+            obs_source_video_render(self.source);
+        }
+    }
+
+    /// Whether the source currently outputs audio, see `set_audio_active`.
+    pub fn is_audio_active(&self) -> bool {
+        unsafe { obs_source_audio_active(self.source) }
+    }
+
+    /// Enables or disables the audio output of the source, e.g. for a source which only
+    /// conditionally produces audio.
+    ///
+    /// Unlike `set_muted`, which reflects the user-facing mute toggle of the mixer, this is
+    /// meant to be controlled by the source itself. An inactive source is hidden from the audio
+    /// mixer and is neither mixed into the output nor monitored, regardless of its mute state.
+    pub fn set_audio_active(&mut self, active: bool) {
+        unsafe {
+            obs_source_set_audio_active(self.source, active);
+        }
+    }
+
+    /// Whether the source has been muted by the user.
+    pub fn is_muted(&self) -> bool {
+        unsafe { obs_source_muted(self.source) }
+    }
+
+    /// Mutes or unmutes the source, as if done by the user in the audio mixer.
+    pub fn set_muted(&mut self, muted: bool) {
+        unsafe {
+            obs_source_set_muted(self.source, muted);
+        }
+    }
+
+    /// The characteristics of the audio of the source, such as the sample rate and the
+    /// speaker layout, e.g. for an audio filter to know the channel layout of the audio it
+    /// processes. For filters, the audio of the source they are applied to is described.
+    ///
+    /// Returns `None` for sources without audio.
+    pub fn get_audio_output_info(&self) -> Option<AudioOutputInfo> {
+        unsafe {
+            let mut source = self.source;
+
+            if obs_source_get_output_flags(source) & OBS_SOURCE_AUDIO == 0 {
+                return None;
+            }
+
+            if let Some(SourceType::FILTER) = SourceType::from_native(obs_source_get_type(source)) {
+                source = obs_filter_get_parent(source);
+
+                if source.is_null() {
+                    return None;
+                }
+            }
+
+            // OBS resamples the audio of all sources to the sample rate of the global output.
+            let layout = SpeakerLayoutKind::from_raw(obs_source_get_speaker_layout(source));
+
+            Some(Audio::get().get_output_info().with_speaker_layout(layout))
+        }
+    }
+
+    pub fn get_flags(&self) -> SourceFlags {
+        unsafe { SourceFlags::from_bits_truncate(obs_source_get_flags(self.source)) }
+    }
+
+    /// Sets the flags of the source. Bits not covered by `SourceFlags` are left untouched.
+    pub fn set_flags(&mut self, flags: SourceFlags) {
+        unsafe {
+            let unknown = obs_source_get_flags(self.source) & !SourceFlags::all().bits();
+            obs_source_set_flags(self.source, unknown | flags.bits());
+        }
+    }
+
+    /// The capabilities of the source type, e.g. for a filter to know whether the source it
+    /// is applied to renders video. Flags not covered by `OutputFlags` are omitted.
+    pub fn get_output_flags(&self) -> OutputFlags {
+        unsafe { OutputFlags::from_bits_truncate(obs_source_get_output_flags(self.source)) }
+    }
+
+    /// Whether the source outputs video, as opposed to being audio-only.
+    fn has_video(&self) -> bool {
+        unsafe { obs_source_get_output_flags(self.source) & OBS_SOURCE_VIDEO != 0 }
+    }
+
+    /// The width of the source, after its filters have been applied. Returns 0 for audio-only
+    /// sources.
+    pub fn get_width(&self) -> u32 {
+        if self.has_video() {
+            unsafe { obs_source_get_width(self.source) }
+        } else {
+            0
+        }
+    }
+
+    /// The height of the source, after its filters have been applied. Returns 0 for
+    /// audio-only sources.
+    pub fn get_height(&self) -> u32 {
+        if self.has_video() {
+            unsafe { obs_source_get_height(self.source) }
+        } else {
+            0
+        }
+    }
+
+    /// The width of the source, without its filters applied. Returns 0 for audio-only sources.
     pub fn get_base_width(&self) -> u32 {
-        unsafe { obs_source_get_base_width(self.source) }
+        if self.has_video() {
+            unsafe { obs_source_get_base_width(self.source) }
+        } else {
+            0
+        }
     }
 
+    /// The height of the source, without its filters applied. Returns 0 for audio-only
+    /// sources.
     pub fn get_base_height(&self) -> u32 {
-        unsafe { obs_source_get_base_height(self.source) }
+        if self.has_video() {
+            unsafe { obs_source_get_base_height(self.source) }
+        } else {
+            0
+        }
     }
 
-    /// Skips the video filter if it's invalid
+    /// Renders the target of the filter unmodified, e.g. when the filter is bypassed or its
+    /// parameters are invalid. Without it, the filter has to run its effect every frame.
+    ///
+    /// It must be called from `video_render` instead of `process_filter`, not in addition to it.
+    ///
+    /// ```rs
+    /// if data.bypass {
+    ///     data.source.skip_video_filter();
+    /// } else {
+    ///     data.source.process_filter(/* ... */);
+    /// }
+    /// ```
+    ///
+    /// Note: only works with sources that are filters.
     pub fn skip_video_filter(&mut self) {
         unsafe {
             obs_source_skip_video_filter(self.source);
@@ -106,8 +537,18 @@ impl SourceContext {
     /// Run a function to do drawing - if the source is a filter.
     /// This function is wrapped by calls that automatically handle effect-based filter processing.
     ///
+    /// The input of the filter is rendered to an intermediate texture of the color `format`.
+    /// `ColorFormatKind::RGBA` suits most filters. Precision-sensitive filters, e.g. HDR
+    /// filters or filters whose output is processed further, may use `RGBA16F` to avoid
+    /// banding, at the cost of twice the video memory and bandwidth, or `RGBA32F`, at four
+    /// times the cost. Formats which cannot be rendered to, see
+    /// `ColorFormatKind::is_render_target_format`, are replaced by `RGBA` with a warning.
+    ///
     /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_process_filter_begin)
     ///
+    /// To render custom content between rendering the input and drawing the output of the
+    /// filter, see `FilterRenderSession`.
+    ///
     /// Note: only works with sources that are filters.
     pub fn process_filter<F: FnOnce(&mut FilterContext, &mut GraphicsEffect)>(
         &mut self,
@@ -116,22 +557,170 @@ impl SourceContext {
         format: ColorFormatKind,
         direct: GraphicsAllowDirectRendering,
         func: F,
+    ) {
+        self.process_filter_with_format(effect, (cx, cy), format, direct, |context, effect, _format| {
+            func(context, effect)
+        })
+    }
+
+    /// Like `process_filter`, but also passes the color format of the intermediate texture
+    /// the input of the filter has been rendered to, to the `func`. The format may differ from
+    /// the requested `format`, if it cannot be rendered to.
+    ///
+    /// Useful for effects whose math depends on the format, e.g. to only clamp the colors of
+    /// formats which cannot represent values outside of the `[0, 1]` range.
+    ///
+    /// Note: only works with sources that are filters.
+    pub fn process_filter_with_format<F: FnOnce(&mut FilterContext, &mut GraphicsEffect, ColorFormatKind)>(
+        &mut self,
+        effect: &mut GraphicsEffect,
+        (cx, cy): (u32, u32),
+        format: ColorFormatKind,
+        direct: GraphicsAllowDirectRendering,
+        func: F,
     ) {
         unsafe {
             if let Some(SourceType::FILTER) =
                 SourceType::from_native(obs_source_get_type(self.source))
             {
+                let format = render_target_format(format);
+
                 if obs_source_process_filter_begin(self.source, format.into_raw(), direct.as_raw()) {
                     let mut context = FilterContext::enter().unwrap();
-                    func(&mut context, effect);
+                    func(&mut context, effect, format);
                     obs_source_process_filter_end(self.source, effect.as_ptr(), cx, cy);
                 }
             }
         }
     }
 
-    /// Update the source settings based on a settings context.
+    /// Like `process_filter`, but for filters whose output is translucent.
+    ///
+    /// OBS renders sources with premultiplied alpha, so blending the output of a filter as
+    /// straight alpha darkens its translucent edges. This draws using the `DrawAlphaDivide`
+    /// technique of the effect, which should divide the color of its input by the alpha
+    /// before processing it, and blends the premultiplied result accordingly.
+    ///
+    /// Use `process_filter` for filters whose output is opaque, or whose effect does not
+    /// modify the color based on the alpha. If the effect does not declare the
+    /// `DrawAlphaDivide` technique, its `Draw` technique is used instead.
+    ///
+    /// Note: only works with sources that are filters.
+    pub fn process_filter_premultiplied<F: FnOnce(&mut FilterContext, &mut GraphicsEffect)>(
+        &mut self,
+        effect: &mut GraphicsEffect,
+        (cx, cy): (u32, u32),
+        format: ColorFormatKind,
+        direct: GraphicsAllowDirectRendering,
+        func: F,
+    ) {
+        let technique = cstr!("DrawAlphaDivide");
+
+        if effect.get_technique_by_name(technique).is_none() {
+            return self.process_filter(effect, (cx, cy), format, direct, func);
+        }
+
+        unsafe {
+            if let Some(SourceType::FILTER) =
+                SourceType::from_native(obs_source_get_type(self.source))
+            {
+                let format = render_target_format(format);
+
+                if obs_source_process_filter_begin(self.source, format.into_raw(), direct.as_raw()) {
+                    let mut context = FilterContext::enter().unwrap();
+                    func(&mut context, effect);
+
+                    gs_blend_state_push();
+                    gs_blend_function(gs_blend_type_GS_BLEND_ONE, gs_blend_type_GS_BLEND_INVSRCALPHA);
+                    obs_source_process_filter_tech_end(
+                        self.source,
+                        effect.as_ptr(),
+                        cx,
+                        cy,
+                        technique.as_ptr(),
+                    );
+                    gs_blend_state_pop();
+                }
+            }
+        }
+    }
+
+    /// Renders the input of the filter unchanged, except for multiplying its colors by the
+    /// `tint`, without requiring an effect file, e.g. for a simple color filter, or for an
+    /// audio filter which leaves the video untouched, in `VideoRenderSource::video_render`:
+    ///
+    /// ```rs
+    /// context.source().render_default(Color([1.0, 0.5, 0.5, 1.0]));
+    /// ```
+    ///
+    /// The alpha of the `tint` scales the opacity of the output. The default effect of OBS,
+    /// `OBS_EFFECT_DEFAULT`, has no parameter to tint its output with, so a minimal effect
+    /// with a `color` parameter multiplying the sampled `image`, compiled on first use and
+    /// shared by all filters of the module, is used instead.
+    ///
+    /// Note: only works with sources that are filters.
+    pub fn render_default(&mut self, tint: Color) {
+        unsafe {
+            if let Some(SourceType::FILTER) =
+                SourceType::from_native(obs_source_get_type(self.source))
+            {
+                let effect = tint_effect();
+
+                if effect.is_null() {
+                    obs_source_skip_video_filter(self.source);
+                    return;
+                }
+
+                let target = obs_filter_get_target(self.source);
+                let cx = obs_source_get_base_width(target);
+                let cy = obs_source_get_base_height(target);
+
+                if obs_source_process_filter_begin(
+                    self.source,
+                    ColorFormatKind::RGBA.into_raw(),
+                    GraphicsAllowDirectRendering::AllowDirectRendering.as_raw(),
+                ) {
+                    let color = gs_effect_get_param_by_name(effect, cstr!("color").as_ptr());
+                    let tint = vec4 {
+                        __bindgen_anon_1: vec4__bindgen_ty_1 { ptr: tint.0 },
+                    };
+
+                    gs_effect_set_vec4(color, &tint);
+                    obs_source_process_filter_end(self.source, effect, cx, cy);
+                }
+            }
+        }
+    }
+
+    /// Update the source settings based on a settings context, see `update_from_settings`.
     pub fn update_source_settings(&mut self, settings: &SettingsContext) {
+        self.update_from_settings(settings);
+    }
+
+    /// Applies the `settings` to the settings of the source, and triggers its `update`
+    /// callback, e.g. from a button applying a preset:
+    ///
+    /// ```rs
+    /// let mut preset = DataObject::new();
+    /// preset.set_double(cstr!("zoom"), 2.0);
+    ///
+    /// source.update_from_object(&preset);
+    /// ```
+    ///
+    /// Only the values set in `settings` are changed, the other settings of the source keep
+    /// their values. For sources with video, OBS defers the `update` callback to the next
+    /// video tick, so the source is not yet updated once this function returns. Other sources
+    /// are updated immediately.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_update)
+    pub fn update_from_settings(&mut self, settings: &SettingsContext) {
+        unsafe {
+            obs_source_update(self.source, settings.as_raw());
+        }
+    }
+
+    /// Like `update_from_settings`, but for settings stored as a data object, e.g. a preset.
+    pub fn update_from_object(&mut self, settings: &DataObject) {
         unsafe {
             obs_source_update(self.source, settings.as_raw());
         }
@@ -143,40 +732,752 @@ impl SourceContext {
             obs_source_update_properties(self.source);
         }
     }
+
+    /// Exposes a procedure on the source's procedure handler, making it callable externally,
+    /// e.g. by obs-websocket or scripts.
+    ///
+    /// The declaration follows the OBS procedure declaration format. For example, a slideshow
+    /// source could expose `void next()`, or `void set_index(int index)` and read the parameter
+    /// using `Calldata::get_int`.
+    ///
+    /// The callback lives as long as the source and is dropped once the source is destroyed.
+    /// Procedures are called from whichever thread calls them, so the callback must be `Send`,
+    /// and concurrent calls are serialized.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-libobs-callback.html#c.proc_handler_add)
+    pub fn add_proc<F: FnMut(&mut Calldata) + Send + 'static>(&mut self, decl: &CStr, callback: F) {
+        let callback: Box<ProcCallback> = Box::new(Mutex::new(Box::new(callback)));
+        let callback_ptr = Box::into_raw(callback);
+
+        unsafe {
+            proc_handler_add(
+                obs_source_get_proc_handler(self.source),
+                decl.as_ptr(),
+                Some(global_proc_callback),
+                callback_ptr as *mut _,
+            );
+
+            signal_handler_connect(
+                obs_source_get_signal_handler(self.source),
+                cstr!("destroy").as_ptr(),
+                Some(global_proc_callback_destroy),
+                callback_ptr as *mut _,
+            );
+        }
+    }
 }
 
-pub struct EnumActiveContext {}
+type ProcCallback = Mutex<Box<dyn FnMut(&mut Calldata) + Send>>;
 
-pub struct EnumAllContext {}
+unsafe extern "C" fn global_proc_callback(data: *mut c_void, calldata: *mut calldata_t) {
+    let callback = &*(data as *const ProcCallback);
+    let mut callback = callback.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut calldata = Calldata::from_raw(calldata);
 
-pub struct SourceInfo {
-    info: Box<obs_source_info>,
+    (callback)(&mut calldata);
 }
 
-impl SourceInfo {
-    /// # Safety
-    /// Creates a raw pointer from a box and could cause UB is misused.
-    pub unsafe fn into_raw(self) -> *mut obs_source_info {
-        Box::into_raw(self.info)
-    }
+unsafe extern "C" fn global_proc_callback_destroy(data: *mut c_void, _calldata: *mut calldata_t) {
+    drop(Box::from_raw(data as *mut ProcCallback));
 }
 
-/// The SourceInfoBuilder that handles creating the [SourceInfo](https://obsproject.com/docs/reference-sources.html#c.obs_source_info) object.
-///
-/// For each trait that is implemented for the Source, it needs to be enabled using this builder.
-/// If an struct called `FocusFilter` implements `CreateSource` and `GetNameSource` it would need
-/// to enable those features.
+/// Finds a source by its name, as shown to the user.
 ///
-/// ```rs
-/// let source = load_context
-///  .create_source_builder::<FocusFilter, ()>()
-///  .enable_get_name()
-///  .enable_create()
-///  .build();
-/// ```
+/// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_get_source_by_name)
+pub fn get_by_name(name: &CStr) -> Option<SourceRef> {
+    unsafe { SourceRef::from_raw(obs_get_source_by_name(name.as_ptr())) }
+}
+
+/// Returns all input sources, e.g. to let the user pick one, see
+/// `PropertyDescriptorSpecializationSourceList`. Scenes, filters and transitions are not
+/// included.
 ///
-pub struct SourceInfoBuilder<T: Sourceable, D> {
-    __source: PhantomData<T>,
+/// See [OBS documentation](https://obsproject.com/docs/reference-core.html#c.obs_enum_sources)
+pub fn enum_sources() -> Vec<SourceRef> {
+    unsafe extern "C" fn enum_source(param: *mut c_void, source: *mut obs_source_t) -> bool {
+        let sources = &mut *(param as *mut Vec<SourceRef>);
+
+        if let Some(source) = SourceRef::from_raw(obs_source_get_ref(source)) {
+            sources.push(source);
+        }
+
+        true
+    }
+
+    let mut sources = Vec::new();
+
+    unsafe {
+        obs_enum_sources(Some(enum_source), &mut sources as *mut Vec<SourceRef> as *mut c_void);
+    }
+
+    sources
+}
+
+/// The number of global output channels, see `set_output_channel`.
+pub const OUTPUT_CHANNEL_COUNT: u32 = MAX_CHANNELS;
+
+/// Sets the source output on a global output channel, or clears the channel if `source` is
+/// `None`.
+///
+/// By convention, channel 0 holds the main transition (and with it the current scene), channels
+/// 1 and 2 the desktop audio devices, and channels 3 to 5 the microphone/auxiliary audio devices,
+/// as set up by the OBS frontend. Plugins should use a higher channel to avoid replacing those.
+///
+/// Panics if the channel is not lower than `OUTPUT_CHANNEL_COUNT`.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-core.html#c.obs_set_output_source)
+pub fn set_output_channel(channel: u32, source: Option<&SourceRef>) {
+    assert!(channel < OUTPUT_CHANNEL_COUNT, "Invalid output channel {}.", channel);
+
+    let source = source.map(|source| source.source).unwrap_or(std::ptr::null_mut());
+
+    unsafe {
+        obs_set_output_source(channel, source);
+    }
+}
+
+/// The source output on a global output channel, see `set_output_channel`. Returns `None` if
+/// the channel is empty or invalid.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-core.html#c.obs_get_output_source)
+pub fn get_output_channel(channel: u32) -> Option<SourceRef> {
+    if channel >= OUTPUT_CHANNEL_COUNT {
+        return None;
+    }
+
+    unsafe { SourceRef::from_raw(obs_get_output_source(channel)) }
+}
+
+/// An owning reference to a source, which keeps the source alive until dropped.
+///
+/// Dereferences to `SourceContext`, to provide access to the source.
+/// To hold onto a source long-term without keeping it alive, use `SourceRef::downgrade`.
+pub struct SourceRef {
+    inner: SourceContext,
+}
+
+impl SourceRef {
+    /// # Safety
+    /// Takes ownership of a reference to a source, which must have been acquired from OBS
+    /// (e.g. the result of `obs_get_source_by_name`). Returns `None` if the pointer is null.
+    pub unsafe fn from_raw(source: *mut obs_source_t) -> Option<Self> {
+        if source.is_null() {
+            None
+        } else {
+            Some(Self {
+                inner: SourceContext { source },
+            })
+        }
+    }
+
+    /// Creates a weak reference to the source, which does not keep the source alive.
+    pub fn downgrade(&self) -> WeakSourceRef {
+        WeakSourceRef {
+            raw: unsafe { obs_source_get_weak_source(self.inner.source) },
+        }
+    }
+
+    /// Creates a copy of the source, with the same settings and filters, named `name`.
+    /// A private source is not listed among the sources of OBS, nor saved.
+    ///
+    /// Returns `None` if the source type has the `OutputFlags::DO_NOT_DUPLICATE` flag, for
+    /// which OBS would return the same source instead of a copy.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_duplicate)
+    pub fn duplicate(&self, name: &CStr, private: bool) -> Option<SourceRef> {
+        if self.get_output_flags().contains(OutputFlags::DO_NOT_DUPLICATE) {
+            return None;
+        }
+
+        unsafe {
+            SourceRef::from_raw(obs_source_duplicate(self.inner.source, name.as_ptr(), private))
+        }
+    }
+
+    /// Renders the source, scaled to the `dimensions`, into a new texture, e.g. for a preview
+    /// or a thumbnail of the source. The source is marked as showing while being rendered, see
+    /// `inc_showing`.
+    ///
+    /// Sources without video, or which could not be rendered, result in a transparent texture.
+    /// Fails if the texture cannot be created.
+    ///
+    /// ```rs
+    /// let graphics_context = GraphicsContext::try_enter().unwrap();
+    /// let thumbnail = source.render_to_texture([160, 90], &graphics_context)?;
+    /// ```
+    pub fn render_to_texture<'a>(
+        &self,
+        dimensions: [u32; 2],
+        context: &'a GraphicsContext,
+    ) -> Result<GraphicsContextDependentEnabled<'a, Texture>, GraphicsError> {
+        let color_format = ColorFormatKind::RGBA;
+        let texture_dimensions = [dimensions[0] as usize, dimensions[1] as usize];
+        let bytes = texture_dimensions[0] * texture_dimensions[1] * color_format.get_pixel_size_in_bytes();
+        let zero_data = vec![0; bytes];
+        let mut texture = Texture::new(texture_dimensions, color_format, &[&zero_data], 0, context)?;
+        let source_dimensions = [self.get_width(), self.get_height()];
+
+        if source_dimensions[0] == 0 || source_dimensions[1] == 0 {
+            return Ok(texture);
+        }
+
+        let mut texture_render = match TextureRender::new(color_format, context) {
+            Some(texture_render) => texture_render,
+            None => return Ok(texture),
+        };
+        let _showing = self.inc_showing();
+        let source = self.inner.source;
+        let rendered = texture_render.render(dimensions, Color([0.0; 4]), context, |context| unsafe {
+            gs_projection_push();
+            context.set_ortho(source_dimensions);
+            obs_source_video_render(source);
+            gs_projection_pop();
+        });
+
+        if rendered {
+            if let Some(rendered_texture) = texture_render.get_texture() {
+                rendered_texture.copy_to(&mut texture);
+            }
+        }
+
+        Ok(texture)
+    }
+
+    /// Marks the source as showing until the returned guard is dropped (or
+    /// `ShowingGuard::dec_showing` is called).
+    ///
+    /// Should be used when rendering the source offscreen, as sources may pause their updates
+    /// while they consider themselves not visible.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_inc_showing)
+    pub fn inc_showing(&self) -> ShowingGuard {
+        unsafe {
+            obs_source_inc_showing(self.inner.source);
+        }
+
+        ShowingGuard {
+            source: self.clone(),
+        }
+    }
+}
+
+/// Keeps a source marked as showing, balancing the `obs_source_inc_showing` call with
+/// `obs_source_dec_showing` when dropped.
+pub struct ShowingGuard {
+    source: SourceRef,
+}
+
+impl ShowingGuard {
+    pub fn source(&self) -> &SourceRef {
+        &self.source
+    }
+
+    /// Explicitly ends the showing period, equivalent to dropping the guard.
+    pub fn dec_showing(self) {}
+}
+
+impl Drop for ShowingGuard {
+    fn drop(&mut self) {
+        unsafe {
+            obs_source_dec_showing(self.source.source);
+        }
+    }
+}
+
+impl Clone for SourceRef {
+    fn clone(&self) -> Self {
+        unsafe {
+            obs_source_addref(self.inner.source);
+        }
+
+        Self {
+            inner: SourceContext {
+                source: self.inner.source,
+            },
+        }
+    }
+}
+
+impl Deref for SourceRef {
+    type Target = SourceContext;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for SourceRef {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl Drop for SourceRef {
+    fn drop(&mut self) {
+        unsafe {
+            obs_source_release(self.inner.source);
+        }
+    }
+}
+
+/// A weak reference to a source, which does not keep the source alive.
+///
+/// Safe to hold onto long-term, e.g. in the data of a source, and upgraded to a `SourceRef`
+/// when the source needs to be accessed.
+pub struct WeakSourceRef {
+    raw: *mut obs_weak_source_t,
+}
+
+impl WeakSourceRef {
+    /// Returns `None` if the source has already been destroyed.
+    pub fn upgrade(&self) -> Option<SourceRef> {
+        unsafe { SourceRef::from_raw(obs_weak_source_get_source(self.raw)) }
+    }
+}
+
+impl Drop for WeakSourceRef {
+    fn drop(&mut self) {
+        unsafe {
+            obs_weak_source_release(self.raw);
+        }
+    }
+}
+
+/// An owning reference to a source, which may be moved to and shared between threads,
+/// created using `SourceContext::to_send`.
+///
+/// Only the operations which OBS synchronizes internally are exposed:
+///
+/// ```rs
+/// let source = context.source().to_send();
+///
+/// std::thread::spawn(move || {
+///     while let Ok(message) = receiver.recv() {
+///         source.set_muted(message.mute);
+///     }
+/// });
+/// ```
+pub struct SendSource {
+    inner: SourceRef,
+}
+
+// OBS reference counts sources atomically, and the exposed operations lock the source or
+// access it atomically.
+unsafe impl Send for SendSource {}
+unsafe impl Sync for SendSource {}
+
+impl SendSource {
+    /// See `SourceContext::name`.
+    pub fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
+
+    /// See `SourceContext::get_type_id`.
+    pub fn get_type_id(&self) -> Option<String> {
+        self.inner.get_type_id()
+    }
+
+    /// See `SourceContext::get_width`.
+    pub fn get_width(&self) -> u32 {
+        self.inner.get_width()
+    }
+
+    /// See `SourceContext::get_height`.
+    pub fn get_height(&self) -> u32 {
+        self.inner.get_height()
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.inner.is_muted()
+    }
+
+    /// See `SourceContext::set_muted`.
+    pub fn set_muted(&self, muted: bool) {
+        unsafe {
+            obs_source_set_muted(self.inner.source, muted);
+        }
+    }
+
+    /// Converts the handle back to a `SourceRef`, which is bound to the current thread, to
+    /// access the rest of the operations, e.g. after sending the handle back to the thread
+    /// it was created on.
+    ///
+    /// # Safety
+    /// The operations of the `SourceRef` must only be used on the threads where OBS allows
+    /// them.
+    pub unsafe fn into_source_ref(self) -> SourceRef {
+        self.inner
+    }
+}
+
+impl Clone for SendSource {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// Tracks whether a source is currently active and/or showing.
+///
+/// A source is *active* when it is displayed in the program output, and *showing* when it is
+/// displayed anywhere -- in the program, in the studio mode preview or in a projector.
+///
+/// Embed it in the source data and update it from the `ActivateSource`, `DeactivateSource`,
+/// `ShowSource` and `HideSource` callbacks. A source that only runs a worker thread while it is
+/// in the program output would check `is_active`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SourceActivationState {
+    active: bool,
+    showing: bool,
+}
+
+/// The combinations of the active and showing states of a source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SourceActivationKind {
+    /// Neither in the program output nor shown anywhere else.
+    Hidden,
+    /// Shown, but not in the program output, e.g. only in the studio mode preview.
+    ShowingInactive,
+    /// In the program output and showing.
+    Active,
+    /// Active, but not (yet) showing. OBS shows a source before activating it and
+    /// deactivates it before hiding it, so this state only occurs if a source does not
+    /// track all four callbacks.
+    ActiveHidden,
+}
+
+impl SourceActivationState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// To be called from `ActivateSource::activate`.
+    pub fn activate(&mut self) {
+        self.active = true;
+    }
+
+    /// To be called from `DeactivateSource::deactivate`.
+    pub fn deactivate(&mut self) {
+        self.active = false;
+    }
+
+    /// To be called from `ShowSource::show`.
+    pub fn show(&mut self) {
+        self.showing = true;
+    }
+
+    /// To be called from `HideSource::hide`.
+    pub fn hide(&mut self) {
+        self.showing = false;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.showing
+    }
+
+    pub fn kind(&self) -> SourceActivationKind {
+        match (self.active, self.showing) {
+            (false, false) => SourceActivationKind::Hidden,
+            (false, true) => SourceActivationKind::ShowingInactive,
+            (true, true) => SourceActivationKind::Active,
+            (true, false) => SourceActivationKind::ActiveHidden,
+        }
+    }
+}
+
+/// The data shared by all instances of a source type, as registered in
+/// `obs_source_info.type_data` using `SourceInfoBuilder::with_type_data`.
+pub struct TypeData {
+    raw: *mut c_void,
+}
+
+/// The type of the value stored in `obs_source_info.type_data` by
+/// `SourceInfoBuilder::with_type_data`.
+pub(crate) type TypeDataBox = Box<dyn Any + Send + Sync>;
+
+impl TypeData {
+    pub(crate) unsafe fn from_raw(raw: *mut c_void) -> Self {
+        Self { raw }
+    }
+
+    /// Returns the type data, if it was registered with the type `X`.
+    pub fn get<X: Any>(&self) -> Option<&X> {
+        if self.raw.is_null() {
+            None
+        } else {
+            unsafe { (*(self.raw as *const TypeDataBox)).downcast_ref::<X>() }
+        }
+    }
+
+    /// # Safety
+    /// Returns the raw type data pointer, which may be null if no type data was registered.
+    pub unsafe fn as_ptr(&self) -> *mut c_void {
+        self.raw
+    }
+}
+
+/// Passed to `EnumActiveSource::enum_active_sources`, to report the child sources which are
+/// currently active, e.g. the ones rendered by a composite source.
+pub struct EnumActiveContext {
+    parent: *mut obs_source_t,
+    callback: obs_source_enum_proc_t,
+    param: *mut c_void,
+}
+
+impl EnumActiveContext {
+    pub(crate) fn new(parent: *mut obs_source_t, callback: obs_source_enum_proc_t, param: *mut c_void) -> Self {
+        Self { parent, callback, param }
+    }
+
+    /// Reports the `child` to OBS.
+    pub fn enum_child(&self, child: &SourceContext) {
+        if let Some(callback) = self.callback {
+            unsafe {
+                callback(self.parent, child.source, self.param);
+            }
+        }
+    }
+}
+
+/// Passed to `EnumAllSource::enum_all_sources`, to report all child sources, including
+/// inactive ones.
+pub struct EnumAllContext {
+    parent: *mut obs_source_t,
+    callback: obs_source_enum_proc_t,
+    param: *mut c_void,
+}
+
+impl EnumAllContext {
+    pub(crate) fn new(parent: *mut obs_source_t, callback: obs_source_enum_proc_t, param: *mut c_void) -> Self {
+        Self { parent, callback, param }
+    }
+
+    /// Reports the `child` to OBS.
+    pub fn enum_child(&self, child: &SourceContext) {
+        if let Some(callback) = self.callback {
+            unsafe {
+                callback(self.parent, child.source, self.param);
+            }
+        }
+    }
+}
+
+pub struct SourceInfo {
+    info: Box<obs_source_info>,
+}
+
+impl SourceInfo {
+    /// # Safety
+    /// Creates a raw pointer from a box and could cause UB is misused.
+    pub unsafe fn into_raw(self) -> *mut obs_source_info {
+        Box::into_raw(self.info)
+    }
+
+    /// Checks that the output flags are consistent with the enabled callbacks, logging a
+    /// warning for each mismatch, which would otherwise cause callbacks never to be called, or
+    /// the source to be missing from the lists of sources. Called by
+    /// `LoadContext::register_source`.
+    ///
+    /// Returns whether no mismatches were found.
+    pub fn validate(&self) -> bool {
+        let info = &self.info;
+        let flags = OutputFlags::from_bits_truncate(info.output_flags);
+        let source_type = SourceType::from_native(info.type_);
+        let is_filter = matches!(source_type, Some(SourceType::FILTER));
+        let is_scene = matches!(source_type, Some(SourceType::SCENE));
+        let id = if info.id.is_null() {
+            Default::default()
+        } else {
+            unsafe { CStr::from_ptr(info.id) }.to_string_lossy()
+        };
+        let mut valid = true;
+        let mut report = |message: &str| {
+            valid = false;
+            crate::warning!("The source `{}` is misconfigured: {}", id, message);
+        };
+
+        if flags.contains(OutputFlags::ASYNC) {
+            if info.video_render.is_some() {
+                report("it outputs asynchronous video, so `video_render` is never called.");
+            }
+
+            if !flags.contains(OutputFlags::VIDEO) {
+                report("it is flagged as `ASYNC`, but not as `VIDEO`, so its frames are never shown.");
+            }
+        } else if flags.contains(OutputFlags::VIDEO) && info.video_render.is_none() {
+            report("it is flagged as `VIDEO`, but does not enable `video_render`, so it renders nothing.");
+        }
+
+        if flags.contains(OutputFlags::CUSTOM_DRAW) && info.video_render.is_none() {
+            report("it is flagged as `CUSTOM_DRAW`, but does not enable `video_render`.");
+        }
+
+        if flags.contains(OutputFlags::COMPOSITE) && info.enum_active_sources.is_none() {
+            report("it is flagged as `COMPOSITE`, but does not enumerate its children using `enum_active_sources`.");
+        }
+
+        if is_filter && flags.contains(OutputFlags::AUDIO) && info.filter_audio.is_none() {
+            report("it is an audio filter, but does not enable `filter_audio`, so the audio passes through unchanged.");
+        }
+
+        if !is_scene && !flags.intersects(OutputFlags::VIDEO | OutputFlags::AUDIO) {
+            report("it outputs neither video nor audio, so it is not listed among the sources. Enable `video_render` or an audio callback.");
+        }
+
+        valid
+    }
+
+    /// Replaces the ID the source is registered under, see
+    /// `LoadContext::register_source_with_id`.
+    pub(crate) fn set_id(&mut self, id: &'static CStr) {
+        self.info.id = id.as_ptr();
+    }
+
+    /// Replaces the type data, see `SourceInfoBuilder::with_type_data`.
+    pub(crate) fn set_type_data<X: Any + Send + Sync>(&mut self, type_data: X) {
+        set_type_data(&mut self.info, type_data);
+    }
+}
+
+/// The effect used by `SourceContext::render_default`. The rendered input is premultiplied by
+/// its alpha, so the tint is premultiplied as well.
+const TINT_EFFECT: &CStr = cstr!(r#"
+uniform float4x4 ViewProj;
+uniform texture2d image;
+uniform float4 color = {1.0, 1.0, 1.0, 1.0};
+
+sampler_state def_sampler {
+    Filter   = Linear;
+    AddressU = Clamp;
+    AddressV = Clamp;
+};
+
+struct VertInOut {
+    float4 pos : POSITION;
+    float2 uv  : TEXCOORD0;
+};
+
+VertInOut VSDefault(VertInOut vert_in)
+{
+    VertInOut vert_out;
+    vert_out.pos = mul(float4(vert_in.pos.xyz, 1.0), ViewProj);
+    vert_out.uv  = vert_in.uv;
+    return vert_out;
+}
+
+float4 PSTint(VertInOut vert_in) : TARGET
+{
+    return image.Sample(def_sampler, vert_in.uv) * float4(color.rgb * color.a, color.a);
+}
+
+technique Draw
+{
+    pass
+    {
+        vertex_shader = VSDefault(vert_in);
+        pixel_shader  = PSTint(vert_in);
+    }
+}
+"#);
+
+/// The compiled `TINT_EFFECT`, null until first used, see `tint_effect`.
+static TINT_EFFECT_RAW: AtomicPtr<gs_effect_t> = AtomicPtr::new(std::ptr::null_mut());
+
+/// Compiles the effect used by `SourceContext::render_default` on first use. Returns null if
+/// the effect could not be compiled. Must be called within the graphics context.
+///
+/// The effect is destroyed when the module is unloaded, see `destroy_tint_effect`.
+unsafe fn tint_effect() -> *mut gs_effect_t {
+    let effect = TINT_EFFECT_RAW.load(Ordering::Acquire);
+
+    if !effect.is_null() {
+        return effect;
+    }
+
+    let effect = gs_effect_create(TINT_EFFECT.as_ptr(), cstr!("render_default_tint").as_ptr(), std::ptr::null_mut());
+
+    if effect.is_null() {
+        crate::warning!("Could not compile the effect of `SourceContext::render_default`.");
+    } else {
+        TINT_EFFECT_RAW.store(effect, Ordering::Release);
+    }
+
+    effect
+}
+
+/// Destroys the effect used by `SourceContext::render_default`, if it has been created. Called
+/// by OBS through `obs_register_module!`, when the module is unloaded.
+#[doc(hidden)]
+pub fn destroy_tint_effect() {
+    let effect = TINT_EFFECT_RAW.swap(std::ptr::null_mut(), Ordering::AcqRel);
+
+    if effect.is_null() {
+        return;
+    }
+
+    // If the graphics subsystem has already been shut down, OBS has destroyed the effect along
+    // with it.
+    if let Ok(_graphics_context) = GraphicsContext::try_enter() {
+        unsafe {
+            gs_effect_destroy(effect);
+        }
+    }
+}
+
+/// Replaces a color `format` which cannot be rendered to by `RGBA`, warning about it once.
+pub(crate) fn render_target_format(format: ColorFormatKind) -> ColorFormatKind {
+    static REPORTED: AtomicBool = AtomicBool::new(false);
+
+    if format.is_render_target_format() {
+        return format;
+    }
+
+    if !REPORTED.swap(true, Ordering::Relaxed) {
+        crate::warning!(
+            "The color format {:?} cannot be rendered to, using {:?} instead.",
+            format,
+            ColorFormatKind::RGBA,
+        );
+    }
+
+    ColorFormatKind::RGBA
+}
+
+/// Replaces the type data of the `info`, freeing the previous one.
+fn set_type_data<X: Any + Send + Sync>(info: &mut obs_source_info, type_data: X) {
+    unsafe {
+        if !info.type_data.is_null() {
+            ffi::free_type_data(info.type_data);
+        }
+    }
+
+    let type_data: Box<TypeDataBox> = Box::new(Box::new(type_data));
+
+    info.type_data = Box::into_raw(type_data) as *mut c_void;
+    info.free_type_data = Some(ffi::free_type_data);
+}
+
+/// The SourceInfoBuilder that handles creating the [SourceInfo](https://obsproject.com/docs/reference-sources.html#c.obs_source_info) object.
+///
+/// For each trait that is implemented for the Source, it needs to be enabled using this builder.
+/// If an struct called `FocusFilter` implements `CreateSource` and `GetNameSource` it would need
+/// to enable those features.
+///
+/// ```rs
+/// let source = load_context
+///  .create_source_builder::<FocusFilter, ()>()
+///  .enable_get_name()
+///  .enable_create()
+///  .build();
+/// ```
+///
+pub struct SourceInfoBuilder<T: Sourceable, D> {
+    __source: PhantomData<T>,
     __data: PhantomData<D>,
     info: obs_source_info,
 }
@@ -240,17 +1541,83 @@ impl<T: Sourceable, D> SourceInfoBuilder<T, D> {
         }
     }
 
+    /// Registers data shared by all instances of the source type, such as the path of the
+    /// shader, when the same source is registered once per shader file.
+    ///
+    /// The data is accessible using `PluginContext::type_data`, and is dropped when OBS
+    /// unregisters the source type.
+    pub fn with_type_data<X: Any + Send + Sync>(mut self, type_data: X) -> Self {
+        set_type_data(&mut self.info, type_data);
+        self
+    }
+
+    /// Adds output flags to the ones derived from the enabled callbacks, e.g.
+    /// `OutputFlags::ASYNC_VIDEO` for sources outputting frames using
+    /// `SourceContext::output_video`.
+    pub fn with_output_flags(mut self, flags: OutputFlags) -> Self {
+        self.info.output_flags |= flags.bits();
+        self
+    }
+
+    /// Finishes the registration, deriving the output flags from the enabled callbacks:
+    /// sources and filters which render video are flagged as video sources, and those which
+    /// render or filter audio are flagged as audio sources. Filters enabling `filter_video`
+    /// are flagged as asynchronous video filters.
+    ///
+    /// A source enabling only audio callbacks, e.g. `enable_filter_audio` for an audio filter,
+    /// is an audio-only source, and is not listed among the video sources and filters.
     pub fn build(mut self) -> SourceInfo {
         if self.info.video_render.is_some() {
             self.info.output_flags |= OBS_SOURCE_VIDEO;
         }
 
+        if self.info.audio_render.is_some() || self.info.filter_audio.is_some() {
+            self.info.output_flags |= OBS_SOURCE_AUDIO;
+        }
+
+        if self.info.filter_video.is_some() {
+            self.info.output_flags |= OBS_SOURCE_ASYNC_VIDEO;
+        }
+
         SourceInfo {
             info: Box::new(self.info),
         }
     }
 }
 
+impl<D, T: CompositeSource<D> + EnumActiveSource<D>> SourceInfoBuilder<T, D> {
+    /// Registers the source as a composite source, which renders other sources, its children,
+    /// enumerated by `EnumActiveSource::enum_active_sources`, so that OBS marks them as active
+    /// and showing along with the composite source.
+    pub fn enable_composite(mut self) -> Self {
+        self.info.output_flags |= OBS_SOURCE_COMPOSITE;
+        self.info.enum_active_sources = Some(ffi::enum_active_sources::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Sourceable + GetWidthSource<D> + GetHeightSource<D>> SourceInfoBuilder<T, D> {
+    /// Enables `GetWidthSource` and `GetHeightSource`, caching the reported size, for sources
+    /// whose size is expensive to compute and changes rarely. OBS requests the size many times
+    /// per frame.
+    ///
+    /// The cached size is only recomputed after `update`, or after it has been invalidated
+    /// using `PluginContext::mark_size_dirty`, e.g. when the size of a loaded image changes.
+    pub fn enable_cached_size(mut self) -> Self {
+        self.info.get_width = Some(ffi::get_width_cached::<D, T>);
+        self.info.get_height = Some(ffi::get_height_cached::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Sourceable + GetPropertiesSource<D> + ApplySettingsProperties<D>> SourceInfoBuilder<T, D> {
+    /// Enables `GetPropertiesSource` along with the `ApplySettingsProperties` hook.
+    pub fn enable_apply_settings(mut self) -> Self {
+        self.info.get_properties = Some(ffi::get_properties_apply_settings::<D, T>);
+        self
+    }
+}
+
 macro_rules! impl_source_builder {
     ($($f:ident => $t:ident)*) => ($(
         item! {
@@ -269,9 +1636,13 @@ impl_source_builder! {
     get_width => GetWidthSource
     get_height => GetHeightSource
     create => CreatableSource
+    get_defaults => GetDefaultsSource
+    get_defaults2 => GetDefaults2Source
     update => UpdateSource
     video_render => VideoRenderSource
     audio_render => AudioRenderSource
+    filter_audio => FilterAudioSource
+    filter_video => FilterVideoSource
     get_properties => GetPropertiesSource
     activate => ActivateSource
     deactivate => DeactivateSource
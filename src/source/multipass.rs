@@ -0,0 +1,203 @@
+//! Filters which apply an effect multiple times, each pass reading the result of the previous
+//! one, such as a separable Gaussian blur, which blurs horizontally and then vertically.
+
+use super::{SourceContext, SourceType};
+use crate::context::*;
+use crate::graphics::*;
+use crate::source::properties::Color;
+use cstr::cstr;
+use obs_sys::{
+    gs_blend_function, gs_blend_state_pop, gs_blend_state_push, gs_blend_type_GS_BLEND_ONE,
+    gs_blend_type_GS_BLEND_ZERO, gs_draw_sprite, gs_effect_get_param_by_name, gs_effect_loop,
+    gs_effect_set_texture, gs_effect_t, gs_ortho, obs_base_effect_OBS_EFFECT_DEFAULT,
+    obs_get_base_effect, obs_source_get_type, obs_source_process_filter_begin,
+    obs_source_process_filter_end,
+};
+
+/// Runs an effect multiple times on the input of a filter, ping-ponging between two
+/// intermediate textures, and submits the result of the last pass as the output of the filter.
+///
+/// Each pass draws the `Draw` technique of the effect, with the result of the previous pass,
+/// or the input of the filter for the first pass, bound to its `image` parameter. The last
+/// pass renders directly to the output of the filter, so `n` passes only need `n - 1`
+/// intermediate renders.
+///
+/// A separable Gaussian blur, whose effect samples `image` along the direction of `texel_step`,
+/// see the `gaussian-blur-filter` plugin for the complete filter:
+///
+/// ```rs
+/// // In `create`
+/// let multi_pass = MultiPassFilter::new(ColorFormatKind::RGBA, &graphics_context).unwrap();
+///
+/// // In `video_render`
+/// let mut multi_pass = data.multi_pass.as_enabled_mut(graphics_context);
+/// let mut effect = data.effect.as_enabled_mut(graphics_context);
+/// let mut texel_step = data.texel_step.as_enabled_mut(graphics_context);
+/// let [width, height] = [data.source.get_width(), data.source.get_height()];
+///
+/// multi_pass.render(&mut data.source, &mut effect, 2, [width, height], |pass, context, _effect| {
+///     let step = if pass == 0 {
+///         [1.0 / width as f32, 0.0]
+///     } else {
+///         [0.0, 1.0 / height as f32]
+///     };
+///
+///     texel_step.set_param_value(&step, context);
+/// });
+/// ```
+pub struct MultiPassFilter {
+    color_format: ColorFormatKind,
+    targets: [TextureRender; 2],
+}
+
+impl MultiPassFilter {
+    /// Creates the intermediate textures of the `color_format`, which is also the format the
+    /// input of the filter is rendered in. Returns `None` if the textures could not be created,
+    /// or if the color format cannot be rendered to.
+    ///
+    /// Effects applied in many passes accumulate rounding errors in 8-bit formats, so consider
+    /// `ColorFormatKind::RGBA16F`, which doubles the video memory used by the textures, see
+    /// `SourceContext::process_filter`.
+    pub fn new(
+        color_format: ColorFormatKind,
+        context: &GraphicsContext,
+    ) -> Option<GraphicsContextDependentEnabled<'_, Self>> {
+        if !color_format.is_render_target_format() {
+            return None;
+        }
+
+        let targets = [TextureRender::create(color_format)?, TextureRender::create(color_format)?];
+
+        Some(ContextDependent::new(Self { color_format, targets }, context))
+    }
+
+    /// Applies `passes` passes of the `effect` to the input of the filter `source`, and
+    /// submits the result. Before each pass, `set_params` is called with the index of the pass,
+    /// to set the parameters of the effect for that pass.
+    ///
+    /// If `passes` is 0, the filter is skipped. Returns `false` if the filter could not be
+    /// rendered, e.g. because its target is not rendering.
+    ///
+    /// Note: only works with sources that are filters, within `VideoRenderSource::video_render`.
+    pub fn render<F: FnMut(usize, &mut FilterContext, &mut GraphicsEffect)>(
+        &mut self,
+        source: &mut SourceContext,
+        effect: &mut GraphicsEffect,
+        passes: usize,
+        dimensions: [u32; 2],
+        mut set_params: F,
+    ) -> bool {
+        if passes == 0 {
+            source.skip_video_filter();
+            return true;
+        }
+
+        unsafe {
+            if !matches!(SourceType::from_native(obs_source_get_type(source.source)), Some(SourceType::FILTER)) {
+                return false;
+            }
+
+            if !obs_source_process_filter_begin(
+                source.source,
+                self.color_format.into_raw(),
+                GraphicsAllowDirectRendering::NoDirectRendering.as_raw(),
+            ) {
+                return false;
+            }
+        }
+
+        let mut context = FilterContext::enter().unwrap();
+        let clear_color = Color([0.0, 0.0, 0.0, 0.0]);
+        let [width, height] = dimensions;
+
+        // Render the input of the filter into the first intermediate texture.
+        let rendered = self.targets[0].render(dimensions, clear_color.clone(), context.graphics(), |_| unsafe {
+            gs_ortho(0.0, width as f32, 0.0, height as f32, -100.0, 100.0);
+            gs_blend_state_push();
+            gs_blend_function(gs_blend_type_GS_BLEND_ONE, gs_blend_type_GS_BLEND_ZERO);
+            obs_source_process_filter_end(
+                source.source,
+                obs_get_base_effect(obs_base_effect_OBS_EFFECT_DEFAULT),
+                width,
+                height,
+            );
+            gs_blend_state_pop();
+        });
+
+        if !rendered {
+            // The filter has begun, so it must be ended, which draws its input unmodified.
+            unsafe {
+                obs_source_process_filter_end(
+                    source.source,
+                    obs_get_base_effect(obs_base_effect_OBS_EFFECT_DEFAULT),
+                    width,
+                    height,
+                );
+            }
+
+            return false;
+        }
+
+        // The filter has ended, so the remaining passes may fail without further cleanup,
+        // leaving the output of the filter empty for this frame.
+
+        // The index of the texture holding the result of the previous pass.
+        let mut current = 0;
+
+        for pass in 0..passes - 1 {
+            let (first, second) = self.targets.split_at_mut(1);
+            let (input, output) = if current == 0 {
+                (&first[0], &mut second[0])
+            } else {
+                (&second[0], &mut first[0])
+            };
+            let input = match input.get_texture() {
+                Some(input) => input,
+                None => return false,
+            };
+
+            set_params(pass, &mut context, effect);
+
+            let rendered = output.render(dimensions, clear_color.clone(), context.graphics(), |_| unsafe {
+                gs_ortho(0.0, width as f32, 0.0, height as f32, -100.0, 100.0);
+                gs_blend_state_push();
+                gs_blend_function(gs_blend_type_GS_BLEND_ONE, gs_blend_type_GS_BLEND_ZERO);
+                draw_pass(effect.as_ptr(), input, dimensions);
+                gs_blend_state_pop();
+            });
+
+            if !rendered {
+                return false;
+            }
+
+            current = 1 - current;
+        }
+
+        // Render the last pass to the output of the filter, with the regular blending.
+        let input = match self.targets[current].get_texture() {
+            Some(input) => input,
+            None => return false,
+        };
+
+        set_params(passes - 1, &mut context, effect);
+
+        unsafe {
+            draw_pass(effect.as_ptr(), input, dimensions);
+        }
+
+        true
+    }
+}
+
+/// Draws the `Draw` technique of the `effect`, with the `input` bound to its `image` parameter.
+unsafe fn draw_pass(effect: *mut gs_effect_t, input: &Texture, [width, height]: [u32; 2]) {
+    let image = gs_effect_get_param_by_name(effect, cstr!("image").as_ptr());
+
+    if !image.is_null() {
+        gs_effect_set_texture(image, input.inner() as *mut _);
+    }
+
+    while gs_effect_loop(effect, cstr!("Draw").as_ptr()) {
+        gs_draw_sprite(input.inner() as *mut _, 0, width, height);
+    }
+}
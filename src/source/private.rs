@@ -0,0 +1,97 @@
+//! Persistent state of sources, stored alongside the scene collection, but hidden from the user,
+//! unlike the settings of the source.
+
+use super::traits::PluginContext;
+use super::SourceContext;
+use crate::data::DataObject;
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+#[cfg(feature = "serde")]
+use cstr::cstr;
+use obs_sys::obs_source_get_private_settings;
+
+impl SourceContext {
+    /// The private settings of the source, which are saved and loaded along with the scene
+    /// collection, but are not shown to the user.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_get_private_settings)
+    pub fn get_private_settings(&self) -> DataObject {
+        unsafe {
+            DataObject::from_raw(obs_source_get_private_settings(self.source))
+                .expect("The private settings of a source are never null.")
+        }
+    }
+
+    /// Stores the `state` in the private settings of the source, serialized as JSON, so that
+    /// it persists across sessions, e.g. a calibration learned by the source:
+    ///
+    /// ```rs
+    /// #[derive(Serialize, Deserialize, Default)]
+    /// struct Calibration {
+    ///     offset: f32,
+    ///     #[serde(default)]
+    ///     gain: f32,
+    /// }
+    ///
+    /// // In `create`
+    /// let calibration = source.load_private::<Calibration>().unwrap_or_default();
+    ///
+    /// // Whenever the calibration changes
+    /// source.save_private(&calibration);
+    /// ```
+    ///
+    /// Each source stores a single state, under the key `rust_private_state` of the private
+    /// settings, so saving overwrites the previously saved state.
+    #[cfg(feature = "serde")]
+    pub fn save_private<T: Serialize>(&mut self, state: &T) {
+        match serde_json::to_string(state) {
+            Ok(json) => match std::ffi::CString::new(json) {
+                Ok(json) => self.get_private_settings().set_string(cstr!("rust_private_state"), &json),
+                Err(_) => crate::warning!("Could not save the private state of a source, as its JSON contains a nul character."),
+            },
+            Err(error) => crate::warning!("Could not serialize the private state of a source: {}", error),
+        }
+    }
+
+    /// Loads the state stored by `save_private`.
+    ///
+    /// Returns `None` if no state has been saved yet, or if the saved state cannot be
+    /// deserialized as `T`, e.g. because it was saved by an incompatible version of the plugin,
+    /// in which case a warning is logged. To keep loading states of older versions, only add
+    /// fields annotated with `#[serde(default)]`.
+    #[cfg(feature = "serde")]
+    pub fn load_private<T: DeserializeOwned>(&self) -> Option<T> {
+        let json = self.get_private_settings().get_string(cstr!("rust_private_state"))?;
+
+        match serde_json::from_str(&json) {
+            Ok(state) => Some(state),
+            Err(error) => {
+                crate::warning!(
+                    "Discarding the private state of a source, as it could not be deserialized as `{}`: {}",
+                    std::any::type_name::<T>(),
+                    error,
+                );
+                None
+            }
+        }
+    }
+}
+
+impl<'a, D> PluginContext<'a, D> {
+    /// See `SourceContext::get_private_settings`.
+    pub fn get_private_settings(&self) -> DataObject {
+        self.source().get_private_settings()
+    }
+
+    /// See `SourceContext::save_private`.
+    #[cfg(feature = "serde")]
+    pub fn save_private<T: Serialize>(&mut self, state: &T) {
+        self.source().save_private(state)
+    }
+
+    /// See `SourceContext::load_private`.
+    #[cfg(feature = "serde")]
+    pub fn load_private<T: DeserializeOwned>(&self) -> Option<T> {
+        self.source().load_private()
+    }
+}
@@ -1,4 +1,5 @@
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
+use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
@@ -9,12 +10,26 @@ use obs_sys::{
     obs_data_set_bool, obs_data_set_double, obs_data_set_int, obs_data_set_string,
     obs_data_set_default_bool, obs_data_set_default_double, obs_data_set_default_int, obs_data_set_default_string,
     obs_properties_add_float, obs_properties_add_float_slider, obs_properties_add_int, obs_properties_add_int_slider, obs_properties_add_bool, obs_properties_add_text, obs_properties_add_path, obs_properties_add_color,
-    obs_properties_add_button2,
+    obs_properties_add_button2, obs_properties_apply_settings, obs_data_get_array, obs_data_get_obj, obs_data_set_obj,
+    obs_data_set_array,
+    obs_properties_set_flags, obs_properties_get_flags, OBS_PROPERTIES_DEFER_UPDATE,
+    obs_properties_add_list, obs_property_list_add_string, obs_combo_type_OBS_COMBO_TYPE_LIST,
+    obs_combo_type_OBS_COMBO_TYPE_EDITABLE, obs_combo_format_OBS_COMBO_FORMAT_STRING,
+    obs_properties_add_group, obs_group_type_OBS_GROUP_NORMAL, obs_properties_remove_by_name,
+    obs_data_apply, obs_properties_get, obs_property_get_type, obs_property_int_type,
+    obs_property_float_type, obs_property_int_set_limits, obs_property_float_set_limits,
+    obs_property_type_OBS_PROPERTY_INT, obs_property_type_OBS_PROPERTY_FLOAT,
+    obs_number_type_OBS_NUMBER_SLIDER,
 };
+use bitflags::bitflags;
+use crate::data::{DataArray, DataKeys, DataObject, ObsDataType};
+use crate::source::{OutputFlags, SourceRef};
 use std::sync::Arc;
 use std::ffi::{CStr, CString, OsString};
 use std::os::raw::{c_char, c_longlong};
 use serde_json::Value;
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
 
 pub mod property_descriptors {
     use super::*;
@@ -33,6 +48,16 @@ pub mod property_descriptors {
 
         unsafe fn get_property_value(name: *const c_char, data: *mut obs_data_t, default_value: &Self::ValueType) -> Self::ValueType;
         unsafe fn set_property_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType);
+        unsafe fn set_property_default_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType);
+
+        /// Reads the value from its JSON representation in mocked settings, see
+        /// `testing::MockSettings`.
+        #[cfg(feature = "mock")]
+        fn from_json(value: Option<&Value>, default_value: &Self::ValueType) -> Self::ValueType;
+
+        /// Converts the value to its JSON representation in mocked settings.
+        #[cfg(feature = "mock")]
+        fn to_json(value: Self::ValueType) -> Value;
     }
 
     #[derive(Clone, Debug)]
@@ -64,6 +89,44 @@ pub mod property_descriptors {
         unsafe fn set_property_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
             obs_data_set_bool(data, name, value);
         }
+
+        unsafe fn set_property_default_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
+            obs_data_set_default_bool(data, name, value);
+        }
+
+        #[cfg(feature = "mock")]
+        fn from_json(value: Option<&Value>, default_value: &Self::ValueType) -> Self::ValueType {
+            value.and_then(Value::as_bool).unwrap_or(*default_value)
+        }
+
+        #[cfg(feature = "mock")]
+        fn to_json(value: Self::ValueType) -> Value {
+            Value::from(value)
+        }
+    }
+
+    /// Checks that `min < max` and `step > 0`, logging a warning otherwise. OBS accepts
+    /// invalid ranges, but the resulting input fields and sliders do not work.
+    pub(crate) unsafe fn validate_number_range<T: PartialOrd + Display + Default>(
+        name: *const c_char,
+        min: T,
+        max: T,
+        step: T,
+    ) -> bool {
+        // Written so that NaN values are rejected as well.
+        let valid = min < max && step > T::default();
+
+        if !valid {
+            crate::warning!(
+                "The number property `{}` has an invalid range: min = {}, max = {}, step = {}. The range must satisfy `min < max` and `step > 0`.",
+                CStr::from_ptr(name).to_string_lossy(),
+                min,
+                max,
+                step,
+            );
+        }
+
+        valid
     }
 
     #[derive(Clone, Debug)]
@@ -81,6 +144,10 @@ pub mod property_descriptors {
             description: *const c_char,
             properties: *mut obs_properties_t,
         ) -> *mut obs_property_t {
+            if !validate_number_range(name, self.min, self.max, self.step) {
+                return std::ptr::null_mut();
+            }
+
             if self.slider {
                 obs_properties_add_int_slider(
                     properties,
@@ -114,6 +181,20 @@ pub mod property_descriptors {
         unsafe fn set_property_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
             obs_data_set_int(data, name, value as c_longlong);
         }
+
+        unsafe fn set_property_default_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
+            obs_data_set_default_int(data, name, value as c_longlong);
+        }
+
+        #[cfg(feature = "mock")]
+        fn from_json(value: Option<&Value>, default_value: &Self::ValueType) -> Self::ValueType {
+            value.and_then(Value::as_i64).map(|value| value as i32).unwrap_or(*default_value)
+        }
+
+        #[cfg(feature = "mock")]
+        fn to_json(value: Self::ValueType) -> Value {
+            Value::from(value)
+        }
     }
 
     #[derive(Clone, Debug)]
@@ -131,6 +212,10 @@ pub mod property_descriptors {
             description: *const c_char,
             properties: *mut obs_properties_t,
         ) -> *mut obs_property_t {
+            if !validate_number_range(name, self.min, self.max, self.step) {
+                return std::ptr::null_mut();
+            }
+
             if self.slider {
                 obs_properties_add_float_slider(
                     properties,
@@ -164,6 +249,20 @@ pub mod property_descriptors {
         unsafe fn set_property_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
             obs_data_set_double(data, name, value);
         }
+
+        unsafe fn set_property_default_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
+            obs_data_set_default_double(data, name, value);
+        }
+
+        #[cfg(feature = "mock")]
+        fn from_json(value: Option<&Value>, default_value: &Self::ValueType) -> Self::ValueType {
+            value.and_then(Value::as_f64).unwrap_or(*default_value)
+        }
+
+        #[cfg(feature = "mock")]
+        fn to_json(value: Self::ValueType) -> Value {
+            Value::from(value)
+        }
     }
 
     #[repr(u32)]
@@ -206,6 +305,22 @@ pub mod property_descriptors {
         unsafe fn set_property_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
             obs_data_set_string(data, name, value.as_ptr());
         }
+
+        unsafe fn set_property_default_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
+            obs_data_set_default_string(data, name, value.as_ptr());
+        }
+
+        #[cfg(feature = "mock")]
+        fn from_json(value: Option<&Value>, default_value: &Self::ValueType) -> Self::ValueType {
+            value.and_then(Value::as_str)
+                .and_then(|value| CString::new(value).ok())
+                .unwrap_or_else(|| default_value.clone())
+        }
+
+        #[cfg(feature = "mock")]
+        fn to_json(value: Self::ValueType) -> Value {
+            Value::from(value.to_string_lossy().into_owned())
+        }
     }
 
     #[repr(u32)]
@@ -261,6 +376,24 @@ pub mod property_descriptors {
                 .expect("Could not convert string to C string.");
             obs_data_set_string(data, name, c_string.as_ptr());
         }
+
+        unsafe fn set_property_default_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
+            let c_string = CString::new(value.to_string_lossy().as_ref())
+                .expect("Could not convert string to C string.");
+            obs_data_set_default_string(data, name, c_string.as_ptr());
+        }
+
+        #[cfg(feature = "mock")]
+        fn from_json(value: Option<&Value>, default_value: &Self::ValueType) -> Self::ValueType {
+            value.and_then(Value::as_str)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| default_value.clone())
+        }
+
+        #[cfg(feature = "mock")]
+        fn to_json(value: Self::ValueType) -> Value {
+            Value::from(value.to_string_lossy().into_owned())
+        }
     }
 
     pub unsafe extern "C" fn button_callback_global(
@@ -334,6 +467,53 @@ pub mod property_descriptors {
         pub fn reversed(&self) -> Color {
             Color([self.0[3], self.0[2], self.0[1], self.0[0]])
         }
+
+        /// Formats the color as `#RRGGBBAA`, e.g. `#FF8000FF` for opaque orange. Components
+        /// are clamped to the `[0, 1]` range and rounded to the nearest byte.
+        pub fn to_hex(&self) -> String {
+            let [r, g, b, a] = self.0.map(|component| (component.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8);
+
+            format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+        }
+
+        /// Parses a color formatted as `#RRGGBBAA`, or as `#RRGGBB` for an opaque color. The `#`
+        /// is optional, and the digits are case-insensitive. Returns `None` for any other
+        /// format.
+        pub fn from_hex(hex: &str) -> Option<Color> {
+            let digits = hex.strip_prefix('#').unwrap_or(hex);
+
+            if !digits.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+                return None;
+            }
+
+            let rgba = match digits.len() {
+                6 => u32::from_str_radix(digits, 16).ok()? << 8 | u8::MAX as u32,
+                8 => u32::from_str_radix(digits, 16).ok()?,
+                _ => return None,
+            };
+
+            Some(Color::from(rgba).reversed())
+        }
+    }
+
+    /// Serialized as a `#RRGGBBAA` string, see `Color::to_hex`.
+    #[cfg(feature = "serde")]
+    impl Serialize for Color {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&self.to_hex())
+        }
+    }
+
+    /// Deserialized from a `#RRGGBBAA` or `#RRGGBB` string, see `Color::from_hex`.
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for Color {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let hex = String::deserialize(deserializer)?;
+
+            Color::from_hex(&hex).ok_or_else(|| {
+                serde::de::Error::custom(format!("invalid color `{}`, expected `#RRGGBBAA`", hex))
+            })
+        }
     }
 
     impl FromStr for Color {
@@ -424,13 +604,382 @@ pub mod property_descriptors {
         unsafe fn set_property_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
             obs_data_set_int(data, name, u32::from(value) as i64)
         }
+
+        unsafe fn set_property_default_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
+            obs_data_set_default_int(data, name, u32::from(value) as i64)
+        }
+
+        #[cfg(feature = "mock")]
+        fn from_json(value: Option<&Value>, default_value: &Self::ValueType) -> Self::ValueType {
+            value.and_then(Value::as_u64)
+                .map(|value| Color::from(value as u32))
+                .unwrap_or_else(|| default_value.clone())
+        }
+
+        #[cfg(feature = "mock")]
+        fn to_json(value: Self::ValueType) -> Value {
+            Value::from(u32::from(value))
+        }
     }
 
-    // TODO: Implement the property kinds below
+    /// A dropdown list of strings. The value of the property is the value of the selected item.
     #[derive(Clone, Debug)]
     pub struct PropertyDescriptorSpecializationList {
-        // TODO
+        /// Whether the user may type in a value not present among the items.
+        pub editable: bool,
+        /// The label and the value of each item.
+        pub items: Vec<(CString, CString)>,
+    }
+
+    impl PropertyDescriptorSpecialization for PropertyDescriptorSpecializationList {
+        unsafe fn create_property(
+            &self,
+            name: *const c_char,
+            description: *const c_char,
+            properties: *mut obs_properties_t,
+        ) -> *mut obs_property_t {
+            let combo_type = if self.editable {
+                obs_combo_type_OBS_COMBO_TYPE_EDITABLE
+            } else {
+                obs_combo_type_OBS_COMBO_TYPE_LIST
+            };
+            let property = obs_properties_add_list(
+                properties,
+                name,
+                description,
+                combo_type,
+                obs_combo_format_OBS_COMBO_FORMAT_STRING,
+            );
+
+            for (label, value) in &self.items {
+                obs_property_list_add_string(property, label.as_ptr(), value.as_ptr());
+            }
+
+            property
+        }
+    }
+
+    impl ValuePropertyDescriptorSpecialization for PropertyDescriptorSpecializationList {
+        type ValueType = CString;
+
+        unsafe fn get_property_value(name: *const c_char, data: *mut obs_data_t, default_value: &Self::ValueType) -> Self::ValueType {
+            PropertyDescriptorSpecializationString::get_property_value(name, data, default_value)
+        }
+
+        unsafe fn set_property_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
+            PropertyDescriptorSpecializationString::set_property_value(name, data, value)
+        }
+
+        unsafe fn set_property_default_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
+            PropertyDescriptorSpecializationString::set_property_default_value(name, data, value)
+        }
+
+        #[cfg(feature = "mock")]
+        fn from_json(value: Option<&Value>, default_value: &Self::ValueType) -> Self::ValueType {
+            PropertyDescriptorSpecializationString::from_json(value, default_value)
+        }
+
+        #[cfg(feature = "mock")]
+        fn to_json(value: Self::ValueType) -> Value {
+            PropertyDescriptorSpecializationString::to_json(value)
+        }
+    }
+
+    /// A dropdown list of the input sources, e.g. to select the source a side-chain filter
+    /// reacts to. The value of the property is the name of the selected source, see
+    /// `SettingsContext::get_source_property` to resolve it.
+    ///
+    /// The list is populated whenever the properties are created, so it reflects the sources
+    /// present when the properties are opened.
+    #[derive(Clone, Debug)]
+    pub struct PropertyDescriptorSpecializationSourceList {
+        /// Only the sources with all of these flags are listed, e.g. `OutputFlags::AUDIO` for
+        /// sources with audio.
+        pub required_flags: OutputFlags,
+        /// Whether to add an empty item, to select no source.
+        pub allow_none: bool,
+    }
+
+    impl PropertyDescriptorSpecialization for PropertyDescriptorSpecializationSourceList {
+        unsafe fn create_property(
+            &self,
+            name: *const c_char,
+            description: *const c_char,
+            properties: *mut obs_properties_t,
+        ) -> *mut obs_property_t {
+            let property = obs_properties_add_list(
+                properties,
+                name,
+                description,
+                obs_combo_type_OBS_COMBO_TYPE_LIST,
+                obs_combo_format_OBS_COMBO_FORMAT_STRING,
+            );
+
+            if self.allow_none {
+                let none = CString::default();
+
+                obs_property_list_add_string(property, none.as_ptr(), none.as_ptr());
+            }
+
+            for source in crate::source::enum_sources() {
+                if !source.get_output_flags().contains(self.required_flags) {
+                    continue;
+                }
+
+                if let Some(source_name) = source.name().and_then(|source_name| CString::new(source_name).ok()) {
+                    obs_property_list_add_string(property, source_name.as_ptr(), source_name.as_ptr());
+                }
+            }
+
+            property
+        }
+    }
+
+    impl ValuePropertyDescriptorSpecialization for PropertyDescriptorSpecializationSourceList {
+        type ValueType = CString;
+
+        unsafe fn get_property_value(name: *const c_char, data: *mut obs_data_t, default_value: &Self::ValueType) -> Self::ValueType {
+            PropertyDescriptorSpecializationString::get_property_value(name, data, default_value)
+        }
+
+        unsafe fn set_property_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
+            PropertyDescriptorSpecializationString::set_property_value(name, data, value)
+        }
+
+        unsafe fn set_property_default_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
+            PropertyDescriptorSpecializationString::set_property_default_value(name, data, value)
+        }
+
+        #[cfg(feature = "mock")]
+        fn from_json(value: Option<&Value>, default_value: &Self::ValueType) -> Self::ValueType {
+            PropertyDescriptorSpecializationString::from_json(value, default_value)
+        }
+
+        #[cfg(feature = "mock")]
+        fn to_json(value: Self::ValueType) -> Value {
+            PropertyDescriptorSpecializationString::to_json(value)
+        }
+    }
+
+    /// A type with a fixed set of values, which can be selected from a dropdown list.
+    ///
+    /// Usually implemented for enums using `#[derive(ObsEnumProperty)]`.
+    pub trait EnumProperty: Sized + Clone + Debug {
+        /// The value stored in the settings, and the label shown to the user, of each item.
+        fn items() -> Vec<(&'static CStr, &'static CStr)>;
+        /// The value stored in the settings for `self`.
+        fn to_value(&self) -> &'static CStr;
+        /// Parses the value stored in the settings.
+        fn from_value(value: &CStr) -> Option<Self>;
+    }
+
+    /// The error returned when parsing a value not corresponding to any item of an
+    /// `EnumProperty`.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct ParseEnumPropertyError {
+        pub value: String,
+    }
+
+    impl std::fmt::Display for ParseEnumPropertyError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "`{}` is not a valid value of the list property.", self.value)
+        }
+    }
+
+    impl std::error::Error for ParseEnumPropertyError {}
+
+    /// A dropdown list with one item per value of the `EnumProperty` `E`.
+    ///
+    /// ```rs
+    /// #[derive(Clone, Copy, Debug, ObsEnumProperty)]
+    /// enum Mode {
+    ///     Fast,
+    ///     #[obs(label = "High Quality")]
+    ///     Accurate,
+    /// }
+    ///
+    /// let property_mode = PropertyDescriptor {
+    ///     name: CString::new("mode").unwrap(),
+    ///     description: CString::new("Mode").unwrap(),
+    ///     specialization: PropertyDescriptorSpecializationEnum::<Mode>::new(),
+    /// };
+    ///
+    /// let mode: Mode = settings.get_property_value(&property_mode, &Mode::Fast);
+    /// ```
+    pub struct PropertyDescriptorSpecializationEnum<E: EnumProperty> {
+        __marker: PhantomData<E>,
+    }
+
+    impl<E: EnumProperty> PropertyDescriptorSpecializationEnum<E> {
+        pub fn new() -> Self {
+            Self {
+                __marker: PhantomData,
+            }
+        }
+    }
+
+    impl<E: EnumProperty> Default for PropertyDescriptorSpecializationEnum<E> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<E: EnumProperty> Clone for PropertyDescriptorSpecializationEnum<E> {
+        fn clone(&self) -> Self {
+            Self::new()
+        }
+    }
+
+    impl<E: EnumProperty> Debug for PropertyDescriptorSpecializationEnum<E> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("PropertyDescriptorSpecializationEnum")
+                .field("items", &E::items())
+                .finish()
+        }
+    }
+
+    impl<E: EnumProperty> PropertyDescriptorSpecialization for PropertyDescriptorSpecializationEnum<E> {
+        unsafe fn create_property(
+            &self,
+            name: *const c_char,
+            description: *const c_char,
+            properties: *mut obs_properties_t,
+        ) -> *mut obs_property_t {
+            let property = obs_properties_add_list(
+                properties,
+                name,
+                description,
+                obs_combo_type_OBS_COMBO_TYPE_LIST,
+                obs_combo_format_OBS_COMBO_FORMAT_STRING,
+            );
+
+            for (value, label) in E::items() {
+                obs_property_list_add_string(property, label.as_ptr(), value.as_ptr());
+            }
+
+            property
+        }
+    }
+
+    impl<E: EnumProperty> ValuePropertyDescriptorSpecialization for PropertyDescriptorSpecializationEnum<E> {
+        type ValueType = E;
+
+        unsafe fn get_property_value(name: *const c_char, data: *mut obs_data_t, default_value: &Self::ValueType) -> Self::ValueType {
+            obs_data_set_default_string(data, name, default_value.to_value().as_ptr());
+
+            let value = obs_data_get_string(data, name);
+
+            if value.is_null() {
+                default_value.clone()
+            } else {
+                E::from_value(CStr::from_ptr(value)).unwrap_or_else(|| default_value.clone())
+            }
+        }
+
+        unsafe fn set_property_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
+            obs_data_set_string(data, name, value.to_value().as_ptr());
+        }
+
+        unsafe fn set_property_default_value(name: *const c_char, data: *mut obs_data_t, value: Self::ValueType) {
+            obs_data_set_default_string(data, name, value.to_value().as_ptr());
+        }
+
+        #[cfg(feature = "mock")]
+        fn from_json(value: Option<&Value>, default_value: &Self::ValueType) -> Self::ValueType {
+            value.and_then(Value::as_str)
+                .and_then(|value| CString::new(value).ok())
+                .and_then(|value| E::from_value(&value))
+                .unwrap_or_else(|| default_value.clone())
+        }
+
+        #[cfg(feature = "mock")]
+        fn to_json(value: Self::ValueType) -> Value {
+            Value::from(value.to_value().to_string_lossy().into_owned())
+        }
+    }
+
+    /// The options of a property, as specified by the `#[obs(...)]` attributes of a field of
+    /// a struct deriving `ObsProperties`.
+    #[derive(Clone, Debug, Default)]
+    pub struct PropertyOptions {
+        pub min: Option<f64>,
+        pub max: Option<f64>,
+        pub step: Option<f64>,
+        pub slider: bool,
+    }
+
+    /// A type usable as the type of a field of a struct deriving `ObsProperties`.
+    pub trait PropertyType: Sized {
+        type Specialization: ValuePropertyDescriptorSpecialization<ValueType = Self>;
+
+        fn specialization(options: &PropertyOptions) -> Self::Specialization;
+    }
+
+    impl PropertyType for bool {
+        type Specialization = PropertyDescriptorSpecializationBool;
+
+        fn specialization(_options: &PropertyOptions) -> Self::Specialization {
+            PropertyDescriptorSpecializationBool {}
+        }
     }
+
+    impl PropertyType for i32 {
+        type Specialization = PropertyDescriptorSpecializationI32;
+
+        fn specialization(options: &PropertyOptions) -> Self::Specialization {
+            PropertyDescriptorSpecializationI32 {
+                min: options.min.map(|min| min as i32).unwrap_or(std::i32::MIN),
+                max: options.max.map(|max| max as i32).unwrap_or(std::i32::MAX),
+                step: options.step.map(|step| step as i32).unwrap_or(1),
+                slider: options.slider,
+            }
+        }
+    }
+
+    impl PropertyType for f64 {
+        type Specialization = PropertyDescriptorSpecializationF64;
+
+        fn specialization(options: &PropertyOptions) -> Self::Specialization {
+            PropertyDescriptorSpecializationF64 {
+                min: options.min.unwrap_or(std::f64::MIN),
+                max: options.max.unwrap_or(std::f64::MAX),
+                step: options.step.unwrap_or(0.01),
+                slider: options.slider,
+            }
+        }
+    }
+
+    impl PropertyType for CString {
+        type Specialization = PropertyDescriptorSpecializationString;
+
+        fn specialization(_options: &PropertyOptions) -> Self::Specialization {
+            PropertyDescriptorSpecializationString {
+                string_type: StringType::Default,
+            }
+        }
+    }
+
+    impl PropertyType for PathBuf {
+        type Specialization = PropertyDescriptorSpecializationPath;
+
+        fn specialization(_options: &PropertyOptions) -> Self::Specialization {
+            PropertyDescriptorSpecializationPath {
+                path_type: PathType::File,
+                filter: CString::default(),
+                default_path: CString::default(),
+            }
+        }
+    }
+
+    impl PropertyType for Color {
+        type Specialization = PropertyDescriptorSpecializationColor;
+
+        fn specialization(_options: &PropertyOptions) -> Self::Specialization {
+            PropertyDescriptorSpecializationColor
+        }
+    }
+
+    // TODO: Implement the property kinds below
     #[derive(Clone, Debug)]
     pub struct PropertyDescriptorSpecializationFont {}
     #[derive(Clone, Debug)]
@@ -444,6 +993,42 @@ pub mod property_descriptors {
 }
 
 pub use property_descriptors::*;
+pub use obs_wrapper_derive::{ObsEnumProperty, ObsProperties};
+
+/// A struct whose fields are each backed by a property, usually implemented using
+/// `#[derive(ObsProperties)]`.
+///
+/// ```rs
+/// impl GetPropertiesSource<Data> for MyFilter {
+///     fn get_properties(_context: PluginContext<Data>) -> Properties {
+///         Settings::properties()
+///     }
+/// }
+///
+/// impl UpdateSource<Data> for MyFilter {
+///     fn update(mut context: PluginContext<Data>) {
+///         let settings = Settings::from_settings(context.settings_mut());
+///         // ...
+///     }
+/// }
+/// ```
+pub trait ObsProperties: Sized {
+    /// Adds a property for each of the fields.
+    fn add_properties(properties: &mut Properties);
+
+    /// Sets the default values of the properties, e.g. from `GetDefaultsSource::get_defaults`.
+    fn set_defaults(settings: &mut SettingsContext);
+
+    /// Reads the values of the properties from the settings.
+    fn from_settings(settings: &mut SettingsContext) -> Self;
+
+    /// Creates the properties, to be returned from `GetPropertiesSource::get_properties`.
+    fn properties() -> Properties {
+        let mut properties = Properties::new();
+        Self::add_properties(&mut properties);
+        properties
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct PropertyDescriptor<T: PropertyDescriptorSpecialization> {
@@ -452,6 +1037,15 @@ pub struct PropertyDescriptor<T: PropertyDescriptorSpecialization> {
     pub specialization: T,
 }
 
+bitflags! {
+    /// Flags influencing the behaviour of the properties view.
+    pub struct PropertiesFlags: u32 {
+        /// Only calls `UpdateSource::update` once the properties dialog is applied, instead of
+        /// on every change, which is useful when updating is expensive.
+        const DEFER_UPDATE = OBS_PROPERTIES_DEFER_UPDATE;
+    }
+}
+
 pub struct Properties {
     inner: *mut obs_properties_t,
 }
@@ -481,6 +1075,20 @@ impl Properties {
         inner
     }
 
+    /// Sets the flags of the properties. The flags are retained when the properties are
+    /// returned from `GetPropertiesSource::get_properties`.
+    pub fn set_flags(&mut self, flags: PropertiesFlags) {
+        unsafe {
+            obs_properties_set_flags(self.inner, flags.bits());
+        }
+    }
+
+    pub fn get_flags(&self) -> PropertiesFlags {
+        unsafe {
+            PropertiesFlags::from_bits_truncate(obs_properties_get_flags(self.inner))
+        }
+    }
+
     pub fn add_property<T: PropertyDescriptorSpecialization>(&mut self, descriptor: &PropertyDescriptor<T>) {
         unsafe {
             descriptor.specialization.create_property(
@@ -490,6 +1098,124 @@ impl Properties {
             );
         }
     }
+
+    /// Adds the `group` of properties, displayed as a collapsible box. The settings of the
+    /// properties in the group are stored alongside the other settings, not in a nested object.
+    pub fn add_group(&mut self, name: &CStr, description: &CStr, group: Properties) {
+        unsafe {
+            obs_properties_add_group(
+                self.inner,
+                name.as_ptr(),
+                description.as_ptr(),
+                obs_group_type_OBS_GROUP_NORMAL,
+                group.leak(),
+            );
+        }
+    }
+
+    /// Adds a button, which opens the `url` in the default web browser when clicked, e.g. to
+    /// link to the documentation of the plugin.
+    pub fn add_url_button(&mut self, name: &CStr, description: &CStr, url: &str) {
+        let url = url.to_string();
+
+        self.add_property(&PropertyDescriptor {
+            name: name.to_owned(),
+            description: description.to_owned(),
+            specialization: PropertyDescriptorSpecializationButton::new(Box::new(move || {
+                if let Err(error) = crate::util::open_url(&url) {
+                    crate::warning!("Could not open the URL `{}`: {}", url, error);
+                }
+
+                // The layout of the properties is unaffected.
+                false
+            })),
+        });
+    }
+
+    fn get_raw(&self, name: &CStr) -> Option<*mut obs_property_t> {
+        let property = unsafe { obs_properties_get(self.inner, name.as_ptr()) };
+
+        if property.is_null() {
+            None
+        } else {
+            Some(property)
+        }
+    }
+
+    /// Returns whether the number property with the given name is displayed as a slider,
+    /// rather than as an input field, or `None` if there is no such number property.
+    ///
+    /// OBS does not allow changing the display style of an existing property. To toggle it,
+    /// set `slider` of the descriptor before adding the property.
+    pub fn is_slider(&self, name: &CStr) -> Option<bool> {
+        let property = self.get_raw(name)?;
+
+        unsafe {
+            match obs_property_get_type(property) {
+                obs_property_type_OBS_PROPERTY_INT => {
+                    Some(obs_property_int_type(property) == obs_number_type_OBS_NUMBER_SLIDER)
+                }
+                obs_property_type_OBS_PROPERTY_FLOAT => {
+                    Some(obs_property_float_type(property) == obs_number_type_OBS_NUMBER_SLIDER)
+                }
+                _ => None,
+            }
+        }
+    }
+
+    /// Changes the range of the integer property with the given name, e.g. depending on the
+    /// size of the source. Invalid ranges are rejected with a warning.
+    ///
+    /// Returns whether the range was changed.
+    pub fn set_int_limits(&mut self, name: &CStr, min: i32, max: i32, step: i32) -> bool {
+        match self.get_raw(name) {
+            Some(property) => unsafe {
+                if obs_property_get_type(property) != obs_property_type_OBS_PROPERTY_INT
+                    || !property_descriptors::validate_number_range(name.as_ptr(), min, max, step) {
+                    return false;
+                }
+
+                obs_property_int_set_limits(property, min, max, step);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Changes the range of the float property with the given name. Invalid ranges are
+    /// rejected with a warning.
+    ///
+    /// Returns whether the range was changed.
+    pub fn set_float_limits(&mut self, name: &CStr, min: f64, max: f64, step: f64) -> bool {
+        match self.get_raw(name) {
+            Some(property) => unsafe {
+                if obs_property_get_type(property) != obs_property_type_OBS_PROPERTY_FLOAT
+                    || !property_descriptors::validate_number_range(name.as_ptr(), min, max, step) {
+                    return false;
+                }
+
+                obs_property_float_set_limits(property, min, max, step);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Removes the property with the given name, if any.
+    pub fn remove(&mut self, name: &CStr) {
+        unsafe {
+            obs_properties_remove_by_name(self.inner, name.as_ptr());
+        }
+    }
+
+    /// Invokes the modified callbacks of all properties with the given settings.
+    ///
+    /// OBS does this automatically after `GetPropertiesSource::get_properties` returns.
+    pub fn apply_settings(&mut self, settings: &SettingsContext) {
+        unsafe {
+            obs_properties_apply_settings(self.inner, settings.as_raw());
+        }
+    }
 }
 
 impl Drop for Properties {
@@ -500,9 +1226,99 @@ impl Drop for Properties {
     }
 }
 
+type PropertiesOperation = Arc<dyn Fn(&mut Properties) + Send + Sync>;
+
+/// Records the operations used to create `Properties`, so that they can be composed before
+/// being created.
+///
+/// `Properties` can be neither cloned nor merged, as OBS does not support moving properties
+/// between `obs_properties_t`. Instead, a base builder can be cloned and extended, e.g. for
+/// each variant of a source:
+///
+/// ```rs
+/// let base = PropertiesBuilder::new()
+///     .add_property(property_color);
+///
+/// let text = base.clone()
+///     .add_property(property_text);
+///
+/// let image = base.clone()
+///     .add_property(property_path)
+///     .extend(&effects);
+///
+/// let properties = text.build();
+/// ```
+#[derive(Clone, Default)]
+pub struct PropertiesBuilder {
+    operations: Vec<PropertiesOperation>,
+}
+
+impl PropertiesBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_operation(mut self, operation: impl Fn(&mut Properties) + Send + Sync + 'static) -> Self {
+        self.operations.push(Arc::new(operation));
+        self
+    }
+
+    pub fn add_property<T>(self, descriptor: PropertyDescriptor<T>) -> Self
+    where
+        T: PropertyDescriptorSpecialization + Send + Sync + 'static,
+    {
+        self.with_operation(move |properties| properties.add_property(&descriptor))
+    }
+
+    /// Adds the properties of a struct implementing `ObsProperties`.
+    pub fn add_properties<T: ObsProperties + 'static>(self) -> Self {
+        self.with_operation(T::add_properties)
+    }
+
+    pub fn add_group(self, name: CString, description: CString, group: PropertiesBuilder) -> Self {
+        self.with_operation(move |properties| {
+            properties.add_group(&name, &description, group.build())
+        })
+    }
+
+    /// Removes the property with the given name, if it was added before.
+    pub fn remove(self, name: CString) -> Self {
+        self.with_operation(move |properties| properties.remove(&name))
+    }
+
+    pub fn set_flags(self, flags: PropertiesFlags) -> Self {
+        self.with_operation(move |properties| properties.set_flags(flags))
+    }
+
+    /// Appends the operations of `other`, merging the two sets of properties.
+    pub fn extend(mut self, other: &PropertiesBuilder) -> Self {
+        self.operations.extend(other.operations.iter().cloned());
+        self
+    }
+
+    /// Adds custom properties, for those not covered by the other methods.
+    pub fn add_with(self, operation: impl Fn(&mut Properties) + Send + Sync + 'static) -> Self {
+        self.with_operation(operation)
+    }
+
+    /// Creates the properties, e.g. to be returned from `GetPropertiesSource::get_properties`.
+    pub fn build(&self) -> Properties {
+        let mut properties = Properties::new();
+
+        for operation in &self.operations {
+            operation(&mut properties);
+        }
+
+        properties
+    }
+}
+
 pub struct SettingsContext {
     settings: *mut obs_data_t,
     init_data: Option<Value>,
+    /// The in-memory settings used instead of `settings`, if mocked.
+    #[cfg(feature = "mock")]
+    mock: Option<crate::testing::MockSettings>,
 }
 
 impl SettingsContext {
@@ -510,9 +1326,30 @@ impl SettingsContext {
         SettingsContext {
             settings,
             init_data: None,
+            #[cfg(feature = "mock")]
+            mock: None,
         }
     }
 
+    /// Creates settings backed by the in-memory `mock`, for testing without a running OBS.
+    ///
+    /// Only property values are supported, `get_array` and `get_obj` always return `None`,
+    /// and `set_obj` has no effect.
+    #[cfg(feature = "mock")]
+    pub fn from_mock(mock: crate::testing::MockSettings) -> Self {
+        SettingsContext {
+            settings: std::ptr::null_mut(),
+            init_data: None,
+            mock: Some(mock),
+        }
+    }
+
+    /// The in-memory settings, if the settings were created using `SettingsContext::from_mock`.
+    #[cfg(feature = "mock")]
+    pub fn mock_mut(&mut self) -> Option<&mut crate::testing::MockSettings> {
+        self.mock.as_mut()
+    }
+
     pub(crate) unsafe fn as_raw(&self) -> *mut obs_data_t {
         self.settings
     }
@@ -539,14 +1376,370 @@ impl SettingsContext {
     }
 
     pub fn get_property_value<T: ValuePropertyDescriptorSpecialization>(&mut self, descriptor: &PropertyDescriptor<T>, default_value: &T::ValueType) -> T::ValueType {
+        #[cfg(feature = "mock")]
+        {
+            if let Some(mock) = &self.mock {
+                return T::from_json(mock.get(&descriptor.name.to_string_lossy()), default_value);
+            }
+        }
+
         unsafe {
             <T as ValuePropertyDescriptorSpecialization>::get_property_value(descriptor.name.as_ptr(), self.settings, default_value)
         }
     }
 
     pub fn set_property_value<T: ValuePropertyDescriptorSpecialization>(&mut self, descriptor: &PropertyDescriptor<T>, value: T::ValueType) {
+        #[cfg(feature = "mock")]
+        {
+            if let Some(mock) = &mut self.mock {
+                return mock.set(&descriptor.name.to_string_lossy(), T::to_json(value));
+            }
+        }
+
         unsafe {
             <T as ValuePropertyDescriptorSpecialization>::set_property_value(descriptor.name.as_ptr(), self.settings, value);
         }
     }
+
+    /// The type of the setting `name`, or `None` if the setting has neither a value, nor
+    /// a default value.
+    pub fn item_type(&self, name: &CStr) -> Option<ObsDataType> {
+        #[cfg(feature = "mock")]
+        {
+            if let Some(mock) = &self.mock {
+                return mock.get(&name.to_string_lossy()).map(ObsDataType::from_json);
+            }
+        }
+
+        if self.settings.is_null() {
+            return None;
+        }
+
+        unsafe { crate::data::get_item_type(self.settings, name) }
+    }
+
+    /// Iterates over the names of all settings, including those which only have a default
+    /// value, e.g. to serialize or compare settings generically:
+    ///
+    /// ```rs
+    /// for key in settings.keys() {
+    ///     let key = CString::new(key).unwrap();
+    ///     println!("{:?}: {:?}", key, settings.item_type(&key));
+    /// }
+    /// ```
+    pub fn keys(&self) -> DataKeys<'_> {
+        #[cfg(feature = "mock")]
+        {
+            if let Some(mock) = &self.mock {
+                return DataKeys::from_names(mock.keys());
+            }
+        }
+
+        unsafe { DataKeys::from_raw(self.settings) }
+    }
+
+    /// Sets the values of all settings of `other` in `self`, overwriting the current values,
+    /// e.g. to apply a preset:
+    ///
+    /// ```rs
+    /// context.settings_mut().merge(&preset);
+    /// context.source().update_from_settings(context.settings());
+    /// ```
+    pub fn merge(&mut self, other: &SettingsContext) {
+        #[cfg(feature = "mock")]
+        {
+            if let (Some(mock), Some(other)) = (&mut self.mock, &other.mock) {
+                return mock.merge(other);
+            }
+        }
+
+        if !self.settings.is_null() && !other.settings.is_null() {
+            unsafe {
+                obs_data_apply(self.settings, other.settings);
+            }
+        }
+
+        self.init_data = None;
+    }
+
+    /// Like `merge`, but for a preset stored as a data object.
+    pub fn merge_object(&mut self, other: &DataObject) {
+        if !self.settings.is_null() {
+            unsafe {
+                obs_data_apply(self.settings, other.as_raw());
+            }
+        }
+
+        self.init_data = None;
+    }
+
+    /// Uses the values of all settings of `other` as the default values of `self`, so that
+    /// the settings the user has not changed take the values of `other`. Array-valued settings
+    /// are skipped, as OBS does not support default values of arrays.
+    pub fn apply_defaults(&mut self, other: &SettingsContext) {
+        #[cfg(feature = "mock")]
+        {
+            if let (Some(mock), Some(other)) = (&mut self.mock, &other.mock) {
+                return mock.apply_defaults(other);
+            }
+        }
+
+        if !self.settings.is_null() && !other.settings.is_null() {
+            unsafe {
+                crate::data::apply_defaults(self.settings, other.settings);
+            }
+        }
+
+        self.init_data = None;
+    }
+
+    /// Returns the array-valued setting `name`, such as the value of an editable list.
+    pub fn get_array(&self, name: &CStr) -> Option<DataArray> {
+        if self.settings.is_null() {
+            return None;
+        }
+
+        unsafe { DataArray::from_raw(obs_data_get_array(self.settings, name.as_ptr())) }
+    }
+
+    /// Returns the nested object setting `name`.
+    pub fn get_obj(&self, name: &CStr) -> Option<DataObject> {
+        if self.settings.is_null() {
+            return None;
+        }
+
+        unsafe { DataObject::from_raw(obs_data_get_obj(self.settings, name.as_ptr())) }
+    }
+
+    /// Stores the `value` as the array-valued setting `name`. The array is shared, not copied.
+    pub fn set_array(&mut self, name: &CStr, value: &DataArray) {
+        #[cfg(feature = "mock")]
+        {
+            if self.mock.is_some() {
+                return;
+            }
+        }
+
+        if !self.settings.is_null() {
+            unsafe {
+                obs_data_set_array(self.settings, name.as_ptr(), value.as_raw());
+            }
+        }
+
+        self.init_data = None;
+    }
+
+    /// Returns the array-valued setting `name`, with each of its objects deserialized as `T`,
+    /// e.g. a list of regions:
+    ///
+    /// ```rs
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Region {
+    ///     name: String,
+    ///     x: u32,
+    ///     y: u32,
+    /// }
+    ///
+    /// let mut regions = settings.get_object_array::<Region>(cstr!("regions"));
+    /// regions.retain(|region| region.x < width && region.y < height);
+    /// settings.set_object_array(cstr!("regions"), &regions);
+    /// ```
+    ///
+    /// Returns an empty `Vec` if the setting is missing or is not an array. Objects which
+    /// cannot be deserialized are skipped, and a warning is logged.
+    #[cfg(feature = "serde")]
+    pub fn get_object_array<T: DeserializeOwned>(&self, name: &CStr) -> Vec<T> {
+        let values: Vec<Value> = {
+            #[cfg(feature = "mock")]
+            let mock_values = self.mock.as_ref().map(|mock| match mock.get(&name.to_string_lossy()) {
+                Some(Value::Array(values)) => values.clone(),
+                _ => Vec::new(),
+            });
+            #[cfg(not(feature = "mock"))]
+            let mock_values = None;
+
+            match mock_values {
+                Some(values) => values,
+                None => self
+                    .get_array(name)
+                    .map(|array| {
+                        array
+                            .iter()
+                            .filter_map(|item| serde_json::from_str(&item.to_json()).ok())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            }
+        };
+
+        values
+            .into_iter()
+            .filter_map(|value| match serde_json::from_value(value) {
+                Ok(item) => Some(item),
+                Err(error) => {
+                    crate::warning!(
+                        "Skipping an item of the setting {:?}, as it could not be deserialized as `{}`: {}",
+                        name,
+                        std::any::type_name::<T>(),
+                        error,
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Stores the `items` as the array-valued setting `name`, each serialized as an object,
+    /// see `get_object_array`.
+    ///
+    /// Items which cannot be serialized, or which do not serialize as an object, e.g. numbers,
+    /// are skipped, and a warning is logged.
+    #[cfg(feature = "serde")]
+    pub fn set_object_array<T: Serialize>(&mut self, name: &CStr, items: &[T]) {
+        let values = items.iter().filter_map(|item| match serde_json::to_value(item) {
+            Ok(value @ Value::Object(_)) => Some(value),
+            Ok(_) => {
+                crate::warning!(
+                    "Skipping an item of the setting {:?}, as `{}` is not serialized as an object.",
+                    name,
+                    std::any::type_name::<T>(),
+                );
+                None
+            }
+            Err(error) => {
+                crate::warning!("Skipping an item of the setting {:?}, as it could not be serialized: {}", name, error);
+                None
+            }
+        });
+
+        #[cfg(feature = "mock")]
+        {
+            if let Some(mock) = &mut self.mock {
+                return mock.set(&name.to_string_lossy(), Value::Array(values.collect()));
+            }
+        }
+
+        let mut array = DataArray::new();
+
+        for value in values {
+            if let Some(object) = DataObject::from_json(&value.to_string()) {
+                array.push(&object);
+            }
+        }
+
+        self.set_array(name, &array);
+    }
+
+    /// Stores the `value` as the nested object setting `name`, e.g. a group of advanced
+    /// settings:
+    ///
+    /// ```rs
+    /// let mut advanced = settings.get_obj(cstr!("advanced")).unwrap_or_default();
+    /// advanced.set_int(cstr!("samples"), 16);
+    /// settings.set_obj(cstr!("advanced"), &advanced);
+    /// ```
+    ///
+    /// The settings take their own reference to the object, which is shared, not copied, so
+    /// later modifications of `value` are reflected in the settings.
+    pub fn set_obj(&mut self, name: &CStr, value: &DataObject) {
+        #[cfg(feature = "mock")]
+        {
+            if self.mock.is_some() {
+                return;
+            }
+        }
+
+        if !self.settings.is_null() {
+            unsafe {
+                obs_data_set_obj(self.settings, name.as_ptr(), value.as_raw());
+            }
+        }
+
+        self.init_data = None;
+    }
+
+    /// Resolves the source selected in the source list property, e.g. in
+    /// `UpdateSource::update`:
+    ///
+    /// ```rs
+    /// let trigger = PropertyDescriptor {
+    ///     name: cstr!("trigger").to_owned(),
+    ///     description: cstr!("Trigger source").to_owned(),
+    ///     specialization: PropertyDescriptorSpecializationSourceList {
+    ///         required_flags: OutputFlags::AUDIO,
+    ///         allow_none: true,
+    ///     },
+    /// };
+    ///
+    /// data.trigger = settings.get_source_property(&trigger).map(|source| source.downgrade());
+    /// ```
+    ///
+    /// Returns `None` if no source is selected, or if the selected source no longer exists,
+    /// e.g. because it was removed or renamed.
+    pub fn get_source_property(
+        &mut self,
+        descriptor: &PropertyDescriptor<PropertyDescriptorSpecializationSourceList>,
+    ) -> Option<SourceRef> {
+        let name = self.get_property_value(descriptor, &CString::default());
+
+        if name.as_bytes().is_empty() {
+            None
+        } else {
+            crate::source::get_by_name(&name)
+        }
+    }
+
+    /// Sets the value used when the user has not set the property, typically called from
+    /// `GetDefaultsSource::get_defaults`.
+    pub fn set_property_default_value<T: ValuePropertyDescriptorSpecialization>(&mut self, descriptor: &PropertyDescriptor<T>, value: T::ValueType) {
+        #[cfg(feature = "mock")]
+        {
+            if let Some(mock) = &mut self.mock {
+                return mock.set_default(&descriptor.name.to_string_lossy(), T::to_json(value));
+            }
+        }
+
+        unsafe {
+            <T as ValuePropertyDescriptorSpecialization>::set_property_default_value(descriptor.name.as_ptr(), self.settings, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_to_hex() {
+        assert_eq!(Color([1.0, 0.5, 0.0, 1.0]).to_hex(), "#FF8000FF");
+        assert_eq!(Color([0.0, 0.0, 0.0, 0.0]).to_hex(), "#00000000");
+        // Out of range components are clamped.
+        assert_eq!(Color([2.0, -1.0, 1.0, 0.5]).to_hex(), "#FF00FF80");
+    }
+
+    #[test]
+    fn color_from_hex() {
+        assert_eq!(Color::from_hex("#FF8000FF").map(|color| color.to_hex()), Some("#FF8000FF".to_string()));
+        assert_eq!(Color::from_hex("ff8000ff").map(|color| color.to_hex()), Some("#FF8000FF".to_string()));
+        // The short form is opaque.
+        assert_eq!(Color::from_hex("#336699"), Some(Color([0.2, 0.4, 0.6, 1.0])));
+    }
+
+    #[test]
+    fn color_from_invalid_hex() {
+        for hex in &["", "#", "#FFF", "#FF8000F", "#FF8000FF00", "#GG8000FF", "#+F8000F", "##FF8000"] {
+            assert_eq!(Color::from_hex(hex), None, "`{}` should not parse", hex);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn color_serde_round_trip() {
+        let color = Color::from_hex("#FF8000C0").unwrap();
+        let json = serde_json::to_string(&color).unwrap();
+
+        assert_eq!(json, "\"#FF8000C0\"");
+        assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), color);
+        assert!(serde_json::from_str::<Color>("\"orange\"").is_err());
+    }
 }
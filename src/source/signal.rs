@@ -0,0 +1,179 @@
+//! Typed access to the standard signals emitted by sources.
+//!
+//! See [OBS documentation](https://obsproject.com/docs/reference-sources.html#source-signals)
+
+use super::SourceContext;
+use crate::calldata::Calldata;
+use cstr::cstr;
+use std::ffi::{c_void, CStr};
+use std::os::raw::c_char;
+use std::sync::{Mutex, PoisonError};
+
+use obs_sys::{
+    calldata_t, obs_source_get_signal_handler, obs_source_t, signal_handler_connect_global,
+    signal_handler_disconnect_global,
+};
+
+/// A signal emitted by a source, decoded from its call data.
+pub enum SourceSignal<'a> {
+    /// The source is about to be destroyed. No signals are received afterwards.
+    Destroy,
+    /// The source has been removed.
+    Remove,
+    /// The source is being saved.
+    Save,
+    /// The source is being loaded.
+    Load,
+    /// The source has become active, i.e. shown on the program output.
+    Activate,
+    /// The source is no longer active.
+    Deactivate,
+    /// The source has become visible, on the program output or a preview.
+    Show,
+    /// The source is no longer visible.
+    Hide,
+    /// The source has been muted or unmuted.
+    Mute { muted: bool },
+    /// The source has been enabled or disabled.
+    Enable { enabled: bool },
+    /// The source has been renamed.
+    Rename { prev: String, new: String },
+    /// The volume of the source has changed, as a linear multiplier.
+    VolumeChanged { volume: f64 },
+    /// The properties of the source need to be refreshed.
+    UpdateProperties,
+    /// The flags of the source have changed.
+    UpdateFlags { flags: i64 },
+    /// The audio sync offset of the source has changed, in nanoseconds.
+    AudioSync { offset: i64 },
+    /// The audio mixers the source outputs to have changed.
+    AudioMixers { mixers: i64 },
+    /// A filter has been added to the source.
+    FilterAdd { filter: SourceContext },
+    /// A filter has been removed from the source.
+    FilterRemove { filter: SourceContext },
+    /// The filters of the source have been reordered.
+    ReorderFilters,
+    /// Any other signal, such as the ones specific to transitions or media sources.
+    Raw { name: &'a CStr, calldata: &'a mut Calldata },
+}
+
+impl<'a> SourceSignal<'a> {
+    /// Decodes the call data of the signal `name`. Falls back to `SourceSignal::Raw`, if the
+    /// signal is unknown or its call data is missing expected parameters.
+    pub fn decode(name: &'a CStr, calldata: &'a mut Calldata) -> Self {
+        let decoded = match name.to_bytes() {
+            b"destroy" => Some(SourceSignal::Destroy),
+            b"remove" => Some(SourceSignal::Remove),
+            b"save" => Some(SourceSignal::Save),
+            b"load" => Some(SourceSignal::Load),
+            b"activate" => Some(SourceSignal::Activate),
+            b"deactivate" => Some(SourceSignal::Deactivate),
+            b"show" => Some(SourceSignal::Show),
+            b"hide" => Some(SourceSignal::Hide),
+            b"mute" => calldata.get_bool(cstr!("muted"))
+                .map(|muted| SourceSignal::Mute { muted }),
+            b"enable" => calldata.get_bool(cstr!("enabled"))
+                .map(|enabled| SourceSignal::Enable { enabled }),
+            b"rename" => calldata.get_string(cstr!("prev_name"))
+                .zip(calldata.get_string(cstr!("new_name")))
+                .map(|(prev, new)| SourceSignal::Rename {
+                    prev: prev.to_string_lossy().into_owned(),
+                    new: new.to_string_lossy().into_owned(),
+                }),
+            b"volume" => calldata.get_float(cstr!("volume"))
+                .map(|volume| SourceSignal::VolumeChanged { volume }),
+            b"update_properties" => Some(SourceSignal::UpdateProperties),
+            b"update_flags" => calldata.get_int(cstr!("flags"))
+                .map(|flags| SourceSignal::UpdateFlags { flags }),
+            b"audio_sync" => calldata.get_int(cstr!("offset"))
+                .map(|offset| SourceSignal::AudioSync { offset }),
+            b"audio_mixers" => calldata.get_int(cstr!("mixers"))
+                .map(|mixers| SourceSignal::AudioMixers { mixers }),
+            b"filter_add" => filter_param(calldata)
+                .map(|filter| SourceSignal::FilterAdd { filter }),
+            b"filter_remove" => filter_param(calldata)
+                .map(|filter| SourceSignal::FilterRemove { filter }),
+            b"reorder_filters" => Some(SourceSignal::ReorderFilters),
+            _ => None,
+        };
+
+        decoded.unwrap_or(SourceSignal::Raw { name, calldata })
+    }
+}
+
+fn filter_param(calldata: &Calldata) -> Option<SourceContext> {
+    calldata.get_ptr(cstr!("filter"))
+        .filter(|filter| !filter.is_null())
+        .map(|filter| SourceContext { source: filter as *mut obs_source_t })
+}
+
+type SignalCallback = Box<dyn FnMut(SourceSignal) + Send>;
+
+struct SignalConnection {
+    source: *mut obs_source_t,
+    /// Signals are emitted from multiple threads, e.g. the UI, graphics and audio threads, so
+    /// the calls of the callback are serialized.
+    callback: Mutex<SignalCallback>,
+}
+
+impl SourceContext {
+    /// Calls the `callback` with every signal emitted by the source, decoded into a
+    /// `SourceSignal`.
+    ///
+    /// ```rs
+    /// source.on_signal(|signal| match signal {
+    ///     SourceSignal::Rename { prev, new } => info!("Renamed from {} to {}", prev, new),
+    ///     SourceSignal::Mute { muted } => info!("Muted: {}", muted),
+    ///     _ => {}
+    /// });
+    /// ```
+    ///
+    /// The callback lives as long as the source and is dropped after receiving
+    /// `SourceSignal::Destroy`. Signals are emitted from multiple threads, so the callback must
+    /// be `Send`, and concurrent signals are delivered one at a time.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-libobs-callback.html#c.signal_handler_connect_global)
+    pub fn on_signal<F: FnMut(SourceSignal) + Send + 'static>(&mut self, callback: F) {
+        let connection = Box::new(SignalConnection {
+            source: self.source,
+            callback: Mutex::new(Box::new(callback)),
+        });
+
+        unsafe {
+            signal_handler_connect_global(
+                obs_source_get_signal_handler(self.source),
+                Some(global_signal_callback),
+                Box::into_raw(connection) as *mut _,
+            );
+        }
+    }
+}
+
+unsafe extern "C" fn global_signal_callback(
+    data: *mut c_void,
+    name: *const c_char,
+    calldata: *mut calldata_t,
+) {
+    let connection = &*(data as *const SignalConnection);
+    let name = CStr::from_ptr(name);
+    let mut calldata = Calldata::from_raw(calldata);
+
+    {
+        let mut callback = connection.callback.lock().unwrap_or_else(PoisonError::into_inner);
+
+        (callback)(SourceSignal::decode(name, &mut calldata));
+    }
+
+    if name.to_bytes() == b"destroy" {
+        // Disconnecting from within the callback is supported by OBS, the connection is
+        // removed once all callbacks of the signal have been called.
+        signal_handler_disconnect_global(
+            obs_source_get_signal_handler(connection.source),
+            Some(global_signal_callback),
+            data,
+        );
+
+        drop(Box::from_raw(data as *mut SignalConnection));
+    }
+}
@@ -1,9 +1,13 @@
 use super::properties::{Properties, SettingsContext};
-use super::{EnumActiveContext, EnumAllContext, SourceContext, SourceType};
+use super::{EnumActiveContext, EnumAllContext, SourceContext, SourceType, TypeData};
 use std::ffi::CStr;
 use std::ffi::c_void;
 use crate::source::ffi::DataWrapper;
 use crate::graphics::*;
+use crate::audio::{AudioMixRequest, FilterAudioData};
+use crate::video::VideoFrame;
+use crate::log::LogLevel;
+use obs_sys::obs_source_get_type_data;
 
 pub struct PluginContext<'a, D> {
     data_wrapper: &'a mut DataWrapper<D>,
@@ -26,6 +30,11 @@ impl<'a, D> PluginContext<'a, D> {
         &mut self.data_wrapper.data
     }
 
+    /// The source the callback was called for.
+    pub fn source(&self) -> SourceContext {
+        SourceContext { source: self.data_wrapper.source }
+    }
+
     pub fn settings(&self) -> &SettingsContext {
         self.data_wrapper.settings.as_ref()
             .expect("Settings were not initialized.")
@@ -36,6 +45,32 @@ impl<'a, D> PluginContext<'a, D> {
             .expect("Settings were not initialized.")
     }
 
+    /// The data shared by all instances of the source type, see
+    /// `SourceInfoBuilder::with_type_data`.
+    pub fn type_data(&self) -> TypeData {
+        unsafe { TypeData::from_raw(obs_source_get_type_data(self.data_wrapper.source)) }
+    }
+
+    /// Logs the `message`, prefixed with the name of the source, so that messages of
+    /// multiple instances of the same plugin can be told apart.
+    ///
+    /// The name is cached and only re-read when the source is renamed.
+    pub fn log(&mut self, level: LogLevel, message: impl AsRef<str>) {
+        let name = self.data_wrapper.name();
+
+        crate::log::log(level, &format!("[{}] {}", name, message.as_ref()));
+    }
+
+    /// Makes the wrapper call `GetWidthSource::get_width` and `GetHeightSource::get_height`
+    /// again the next time OBS requests the size of the source, instead of reporting the
+    /// cached size. Only relevant to sources registered with
+    /// `SourceInfoBuilder::enable_cached_size`.
+    ///
+    /// The size is marked dirty automatically after every `update`.
+    pub fn mark_size_dirty(&mut self) {
+        self.data_wrapper.size_hint.mark_dirty();
+    }
+
     pub fn data_settings_mut(&mut self) -> (&mut Option<D>, &mut SettingsContext) {
         (
             &mut self.data_wrapper.data,
@@ -66,6 +101,21 @@ pub trait CreatableSource<D> {
     fn create(settings: &mut SettingsContext, source: SourceContext) -> D;
 }
 
+pub trait GetDefaultsSource<D> {
+    fn get_defaults(settings: &mut SettingsContext);
+}
+
+/// A variant of `GetDefaultsSource` which also receives the type data the source was
+/// registered with.
+///
+/// Prefer this over `GetDefaultsSource` when the defaults depend on data shared across all
+/// instances of the source type, for example when a generic shader source is registered once
+/// per shader file, each registration with a different type data. When both are enabled,
+/// OBS only calls `get_defaults2`.
+pub trait GetDefaults2Source<D> {
+    fn get_defaults2(type_data: &TypeData, settings: &mut SettingsContext);
+}
+
 pub trait UpdateSource<D> {
     fn update(context: PluginContext<D>);
 }
@@ -77,14 +127,96 @@ pub trait VideoRenderSource<D> {
     );
 }
 
+/// Renders the audio of a source which mixes audio itself, e.g. from its child sources,
+/// rather than outputting it asynchronously.
+///
+/// Returns whether the audio has been rendered. If `false` is returned, or no valid timestamp
+/// has been set using `AudioMixRequest::set_timestamp`, OBS considers the audio of the source
+/// pending, and does not output it.
 pub trait AudioRenderSource<D> {
-    fn audio_render(context: PluginContext<D>);
+    fn audio_render(context: PluginContext<D>, request: &mut AudioMixRequest) -> bool;
+}
+
+/// Processes the audio of the source the filter is applied to. Enabling it makes the source
+/// an audio filter, see `SourceInfoBuilder::build`.
+pub trait FilterAudioSource<D> {
+    fn filter_audio(context: PluginContext<D>, audio: &mut FilterAudioData);
 }
 
+/// Processes the frames of the asynchronous video source the filter is applied to, such as
+/// a media source or a video capture device, on the CPU. Enabling it makes the source an
+/// asynchronous video filter, see `SourceInfoBuilder::build`.
+///
+/// The frame is modified in place, and then passed on to the next filter, or displayed if
+/// this is the last filter. The default implementation passes the frame on unmodified, which
+/// is useful for a filter that only processes some of the frames. Frames have their
+/// timestamp, format and flip available through `VideoFrame`. Inverting the luma of frames
+/// with a separate luma plane:
+///
+/// ```rs
+/// impl FilterVideoSource<Data> for InvertLumaFilter {
+///     fn filter_video(_context: PluginContext<Data>, frame: &mut VideoFrame) {
+///         match frame.get_format() {
+///             VideoFormatKind::I420 | VideoFormatKind::NV12 | VideoFormatKind::I444 => {
+///                 if let Some(luma) = frame.get_plane_mut(0) {
+///                     luma.iter_mut().for_each(|value| *value = 255 - *value);
+///                 }
+///             }
+///             _ => {}
+///         }
+///     }
+/// }
+/// ```
+pub trait FilterVideoSource<D> {
+    fn filter_video(_context: PluginContext<D>, _frame: &mut VideoFrame) {}
+}
+
+/// Creates the properties shown to the user.
+///
+/// OBS does not pass the settings to `get_properties`, but the settings the source was
+/// created with, or most recently updated with, are available via `PluginContext::settings`,
+/// e.g. to preselect list items or to populate properties depending on other settings.
+///
+/// Filters may also tailor their properties to the source they are applied to:
+///
+/// ```rs
+/// impl GetPropertiesSource<Data> for ChromaKeyFilter {
+///     fn get_properties(context: PluginContext<Data>) -> Properties {
+///         let mut properties = Properties::new();
+///
+///         properties.add_property(&property_color);
+///
+///         let is_camera = context.source()
+///             .do_with_parent(|parent| parent.get_type_id())
+///             .flatten()
+///             .map_or(false, |id| id == "v4l2_input" || id == "dshow_input");
+///
+///         if is_camera {
+///             properties.add_property(&property_camera_preset);
+///         }
+///
+///         properties
+///     }
+/// }
+/// ```
 pub trait GetPropertiesSource<D> {
     fn get_properties(context: PluginContext<D>) -> Properties;
 }
 
+/// Allows properties to reflect the current settings when the properties panel opens, for
+/// example to preselect the saved item of a dynamically populated list.
+///
+/// Enabled using `SourceInfoBuilder::enable_apply_settings`, which requires
+/// `GetPropertiesSource` to be implemented as well. The call order when OBS requests the
+/// properties is:
+/// 1. `GetPropertiesSource::get_properties`
+/// 1. `ApplySettingsProperties::apply_settings`
+/// 1. The modified callbacks of the individual properties, invoked by OBS via
+///    `obs_properties_apply_settings`
+pub trait ApplySettingsProperties<D> {
+    fn apply_settings(context: PluginContext<D>, properties: &mut Properties);
+}
+
 pub trait VideoTickSource<D> {
     fn video_tick(context: PluginContext<D>, seconds: f32);
 }
@@ -113,6 +245,49 @@ pub trait EnumAllSource<D> {
     fn enum_all_sources(context: PluginContext<D>, context: &EnumAllContext);
 }
 
+/// Marks a source which renders other sources, its children, such as a scene. Registered
+/// using `SourceInfoBuilder::enable_composite`.
+///
+/// The children are rendered within `VideoRenderSource::video_render`, and must be reported
+/// in `EnumActiveSource::enum_active_sources`:
+///
+/// ```rs
+/// impl CompositeSource<Data> for SideBySide {}
+///
+/// impl EnumActiveSource<Data> for SideBySide {
+///     fn enum_active_sources(context: PluginContext<Data>, enum_context: &EnumActiveContext) {
+///         if let Some(data) = context.data() {
+///             for child in &data.children {
+///                 enum_context.enum_child(child);
+///             }
+///         }
+///     }
+/// }
+///
+/// impl VideoRenderSource<Data> for SideBySide {
+///     fn video_render(mut context: PluginContext<Data>, graphics_context: &mut GraphicsContext) {
+///         if let Some(data) = context.data_mut() {
+///             let mut x = 0.0;
+///
+///             for child in &mut data.children {
+///                 unsafe {
+///                     gs_matrix_push();
+///                     gs_matrix_translate3f(x, 0.0, 0.0);
+///                 }
+///
+///                 x += child.get_width() as f32;
+///                 child.video_render(graphics_context);
+///
+///                 unsafe {
+///                     gs_matrix_pop();
+///                 }
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub trait CompositeSource<D>: Sourceable {}
+
 pub trait TransitionStartSource<D> {
     fn transition_start(context: PluginContext<D>);
 }
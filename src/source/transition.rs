@@ -0,0 +1,202 @@
+//! Transitions are sources of the type `SourceType::TRANSITION`, which render the transition
+//! between two scenes.
+//!
+//! A transition is registered just like any other source, additionally enabling the
+//! transition-specific callbacks:
+//!
+//! ```rs
+//! let source = load_context
+//!     .create_source_builder::<FadeTransition, FadeData>()
+//!     .enable_get_name()
+//!     .enable_transition_video_render()
+//!     .enable_transition_audio_render()
+//!     .build();
+//! ```
+
+use super::ffi::DataWrapper;
+use super::traits::{PluginContext, Sourceable};
+use super::{SourceContext, SourceInfoBuilder, SourceRef};
+use crate::context::*;
+use crate::graphics::*;
+use std::ffi::c_void;
+
+use obs_sys::{
+    gs_effect_t, gs_texture_t, obs_source_audio_mix, obs_transition_audio_render,
+    obs_transition_clear, obs_transition_get_source, obs_transition_get_time,
+    obs_transition_set, obs_transition_target, obs_transition_target_OBS_TRANSITION_SOURCE_A,
+    obs_transition_target_OBS_TRANSITION_SOURCE_B, obs_transition_video_render, size_t,
+    OBS_SOURCE_AUDIO,
+};
+
+/// One of the two sources a transition transitions between.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionTarget {
+    /// The source being transitioned from.
+    SourceA,
+    /// The source being transitioned to.
+    SourceB,
+}
+
+impl TransitionTarget {
+    pub fn as_raw(&self) -> obs_transition_target {
+        match self {
+            TransitionTarget::SourceA => obs_transition_target_OBS_TRANSITION_SOURCE_A,
+            TransitionTarget::SourceB => obs_transition_target_OBS_TRANSITION_SOURCE_B,
+        }
+    }
+}
+
+/// The state of the transition passed to `Transitionable::transition_video_render`.
+pub struct TransitionFrame<'a> {
+    /// The rendered source being transitioned from, if any.
+    pub a: Option<&'a Texture>,
+    /// The rendered source being transitioned to, if any.
+    pub b: Option<&'a Texture>,
+    /// The progress of the transition, from `0.0` to `1.0`.
+    pub t: f32,
+    /// The size of the transition, in pixels.
+    pub dimensions: [u32; 2],
+}
+
+/// Implemented by transitions, to render the transition between the two sources.
+pub trait Transitionable<D>: Sourceable {
+    fn transition_video_render(
+        context: PluginContext<D>,
+        graphics_context: &mut GraphicsContext,
+        frame: &TransitionFrame,
+    );
+}
+
+/// Implemented by transitions, to mix the audio of the two sources.
+///
+/// Defaults to a linear crossfade.
+pub trait TransitionAudioRenderSource<D>: Sourceable {
+    /// The volume multiplier of the source being transitioned from, at the progress `t`.
+    fn mix_a(_context: PluginContext<D>, t: f32) -> f32 {
+        1.0 - t
+    }
+
+    /// The volume multiplier of the source being transitioned to, at the progress `t`.
+    fn mix_b(_context: PluginContext<D>, t: f32) -> f32 {
+        t
+    }
+}
+
+impl SourceContext {
+    /// The progress of the transition, from `0.0` to `1.0`.
+    ///
+    /// Note: only works with sources that are transitions.
+    pub fn transition_get_time(&self) -> f32 {
+        unsafe { obs_transition_get_time(self.source) }
+    }
+
+    /// Returns one of the two sources the transition transitions between.
+    ///
+    /// Note: only works with sources that are transitions.
+    pub fn transition_get_source(&self, target: TransitionTarget) -> Option<SourceRef> {
+        unsafe { SourceRef::from_raw(obs_transition_get_source(self.source, target.as_raw())) }
+    }
+
+    /// Immediately sets the source shown by the transition, without transitioning.
+    ///
+    /// Note: only works with sources that are transitions.
+    pub fn transition_set(&mut self, source: &SourceContext) {
+        unsafe {
+            obs_transition_set(self.source, source.source);
+        }
+    }
+
+    /// Clears the sources of the transition.
+    ///
+    /// Note: only works with sources that are transitions.
+    pub fn transition_clear(&mut self) {
+        unsafe {
+            obs_transition_clear(self.source);
+        }
+    }
+}
+
+impl<D, T: Transitionable<D>> SourceInfoBuilder<T, D> {
+    pub fn enable_transition_video_render(mut self) -> Self {
+        self.info.video_render = Some(transition_video_render::<D, T>);
+        self
+    }
+}
+
+impl<D, T: TransitionAudioRenderSource<D>> SourceInfoBuilder<T, D> {
+    pub fn enable_transition_audio_render(mut self) -> Self {
+        self.info.audio_render = Some(transition_audio_render::<D, T>);
+        self.info.output_flags |= OBS_SOURCE_AUDIO;
+        self
+    }
+}
+
+unsafe extern "C" fn transition_video_render<D, F: Transitionable<D>>(
+    data: *mut c_void,
+    _effect: *mut gs_effect_t,
+) {
+    let wrapper: &DataWrapper<D> = &*(data as *const DataWrapper<D>);
+
+    obs_transition_video_render(wrapper.source, Some(transition_video_render_callback::<D, F>));
+}
+
+unsafe extern "C" fn transition_video_render_callback<D, F: Transitionable<D>>(
+    data: *mut c_void,
+    a: *mut gs_texture_t,
+    b: *mut gs_texture_t,
+    t: f32,
+    cx: u32,
+    cy: u32,
+) {
+    let context = PluginContext::<D>::from(data);
+    let mut graphics_context = GraphicsContext::get_current().unwrap();
+    GraphicsResourceGraveyard::global().drain(&graphics_context);
+    let a = if a.is_null() { None } else { Some(Texture::from_raw(a, 0)) };
+    let b = if b.is_null() { None } else { Some(Texture::from_raw(b, 0)) };
+    let frame = TransitionFrame {
+        a: a.as_ref(),
+        b: b.as_ref(),
+        t,
+        dimensions: [cx, cy],
+    };
+
+    F::transition_video_render(context, &mut graphics_context, &frame);
+}
+
+unsafe extern "C" fn transition_audio_render<D, F: TransitionAudioRenderSource<D>>(
+    data: *mut c_void,
+    ts_out: *mut u64,
+    audio_output: *mut obs_source_audio_mix,
+    mixers: u32,
+    channels: size_t,
+    sample_rate: size_t,
+) -> bool {
+    let wrapper: &DataWrapper<D> = &*(data as *const DataWrapper<D>);
+
+    obs_transition_audio_render(
+        wrapper.source,
+        ts_out,
+        audio_output,
+        mixers,
+        channels,
+        sample_rate,
+        Some(transition_mix_a::<D, F>),
+        Some(transition_mix_b::<D, F>),
+    )
+}
+
+unsafe extern "C" fn transition_mix_a<D, F: TransitionAudioRenderSource<D>>(
+    data: *mut c_void,
+    t: f32,
+) -> f32 {
+    let context = PluginContext::<D>::from(data);
+    F::mix_a(context, t)
+}
+
+unsafe extern "C" fn transition_mix_b<D, F: TransitionAudioRenderSource<D>>(
+    data: *mut c_void,
+    t: f32,
+) -> f32 {
+    let context = PluginContext::<D>::from(data);
+    F::mix_b(context, t)
+}
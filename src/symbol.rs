@@ -0,0 +1,160 @@
+//! Calling functions of OBS which may be missing in the version of OBS the plugin is loaded
+//! into.
+//!
+//! The bindings are generated from the headers of a single version of OBS, and a plugin calling
+//! a function which is missing in an older version of OBS fails to load. Functions introduced
+//! in later versions can instead be looked up at runtime, using `try_symbol!`, so that the
+//! features depending on them degrade gracefully:
+//!
+//! ```rs
+//! let enable_srgb = try_symbol!(gs_enable_framebuffer_srgb: unsafe extern "C" fn(bool));
+//!
+//! if let Some(enable_srgb) = enable_srgb {
+//!     unsafe { enable_srgb(true) };
+//! }
+//! ```
+//!
+//! Alternatively, `ObsVersion` can be used to check the version of OBS up front. The minimum
+//! versions of OBS for some of the commonly needed optional functions:
+//!
+//! | Function                                            | Minimum version |
+//! |-----------------------------------------------------|-----------------|
+//! | `gs_enable_framebuffer_srgb`, `gs_get_linear_srgb`  | 27.0            |
+//! | `gs_set_linear_srgb`, `gs_framebuffer_srgb_enabled` | 27.0            |
+//! | `obs_source_get_color_space`                        | 28.0            |
+//! | `obs_source_process_filter_begin_with_color_space`  | 28.0            |
+
+use obs_sys::{obs_get_version, obs_get_version_string};
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+
+/// The version of OBS the plugin is running in, which may differ from the version the
+/// bindings were generated for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObsVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ObsVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// The version of the running OBS.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-core.html#c.obs_get_version)
+    pub fn current() -> Self {
+        let version = unsafe { obs_get_version() };
+
+        Self::new(version >> 24, (version >> 16) & 0xFF, version & 0xFFFF)
+    }
+
+    /// The version of the running OBS, as shown to the user, e.g. `27.2.4`.
+    pub fn current_string() -> String {
+        unsafe {
+            let version = obs_get_version_string();
+
+            if version.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(version).to_string_lossy().into_owned()
+            }
+        }
+    }
+
+    /// Whether the version is `major.minor.patch` or newer.
+    pub fn at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        *self >= Self::new(major, minor, patch)
+    }
+}
+
+impl std::fmt::Display for ObsVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[cfg(unix)]
+extern "C" {
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+}
+
+#[cfg(windows)]
+extern "system" {
+    fn GetModuleHandleA(module_name: *const c_char) -> *mut c_void;
+    fn GetProcAddress(module: *mut c_void, proc_name: *const c_char) -> *mut c_void;
+}
+
+/// Looks up the function `name` exported by OBS, returning null if it does not exist, see
+/// `try_symbol!`.
+///
+/// # Safety
+/// The returned pointer must only be called with the signature of the function in the running
+/// version of OBS.
+pub unsafe fn lookup_symbol(name: &CStr) -> *mut c_void {
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    {
+        // `RTLD_DEFAULT`, searching all loaded images, which include libobs.
+        dlsym(-2isize as *mut c_void, name.as_ptr())
+    }
+
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios"))))]
+    {
+        // `RTLD_DEFAULT`, searching all loaded objects, which include libobs.
+        dlsym(std::ptr::null_mut(), name.as_ptr())
+    }
+
+    #[cfg(windows)]
+    {
+        let module = GetModuleHandleA(b"obs.dll\0".as_ptr() as *const c_char);
+
+        if module.is_null() {
+            std::ptr::null_mut()
+        } else {
+            GetProcAddress(module, name.as_ptr())
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = name;
+        std::ptr::null_mut()
+    }
+}
+
+/// Looks up a function of OBS by its name at runtime, returning `None` if the running version
+/// of OBS does not provide it, see the `symbol` module. The result is cached, so the lookup
+/// only happens on the first use:
+///
+/// ```rs
+/// let get_color_space = try_symbol!(
+///     obs_source_get_color_space: unsafe extern "C" fn(*mut obs_source_t, usize, *const u32) -> u32
+/// );
+/// ```
+///
+/// The type must be the `unsafe extern "C" fn` type matching the declaration of the function in
+/// the headers of OBS. Specifying a different signature is undefined behavior.
+#[macro_export]
+macro_rules! try_symbol {
+    ($name:ident: $type:ty) => {{
+        static ONCE: ::std::sync::Once = ::std::sync::Once::new();
+        static mut SYMBOL: *mut ::std::os::raw::c_void = ::std::ptr::null_mut();
+
+        unsafe {
+            ONCE.call_once(|| {
+                let name = ::std::ffi::CStr::from_bytes_with_nul(concat!(stringify!($name), "\0").as_bytes())
+                    .unwrap();
+
+                SYMBOL = $crate::symbol::lookup_symbol(name);
+            });
+
+            if SYMBOL.is_null() {
+                None
+            } else {
+                Some(::std::mem::transmute::<*mut ::std::os::raw::c_void, $type>(SYMBOL))
+            }
+        }
+    }};
+}
@@ -0,0 +1,241 @@
+//! Tools for testing the logic of plugins without a running OBS, enabled by the `mock` feature.
+//!
+//! Callbacks which only depend on the settings and the data of a source, such as
+//! `UpdateSource::update` or `VideoTickSource::video_tick`, can be called with a mocked
+//! `PluginContext`:
+//!
+//! ```rs
+//! #[test]
+//! fn update_applies_gain() {
+//!     let settings = MockSettings::new().with("gain", 6.0);
+//!     let mut mock = MockPluginContext::new(settings, Data { multiplier: 1.0 });
+//!
+//!     AudioGainFilter::update(mock.context());
+//!
+//!     assert!((mock.data().multiplier - db_to_mul(6.0)).abs() < 1e-6);
+//! }
+//! ```
+//!
+//! See the tests of the `audio-gain-filter` plugin, run using `cargo test --features mock` in
+//! its directory.
+//!
+//! Anything else requiring OBS, such as graphics or the `SourceContext` of the source, is not
+//! available.
+
+use crate::source::ffi::DataWrapper;
+use crate::source::properties::SettingsContext;
+use crate::source::traits::PluginContext;
+use serde_json::{Map, Value};
+use std::ffi::c_void;
+
+/// Settings stored in memory, as JSON values, in place of an OBS data object.
+///
+/// The values are represented as OBS stores them: numbers for integers, floats and colors,
+/// strings for text, paths and list items.
+#[derive(Clone, Debug, Default)]
+pub struct MockSettings {
+    values: Map<String, Value>,
+    defaults: Map<String, Value>,
+}
+
+impl MockSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of the setting `name`.
+    pub fn with(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Sets the value of the setting `name`.
+    pub fn set(&mut self, name: &str, value: impl Into<Value>) {
+        self.values.insert(name.to_string(), value.into());
+    }
+
+    /// Sets the value of the setting `name` used when no value has been set.
+    pub fn set_default(&mut self, name: &str, value: impl Into<Value>) {
+        self.defaults.insert(name.to_string(), value.into());
+    }
+
+    /// Removes the value of the setting `name`, so that its default is used.
+    pub fn erase(&mut self, name: &str) {
+        self.values.remove(name);
+    }
+
+    /// The value of the setting `name`, or its default value.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.get(name).or_else(|| self.defaults.get(name))
+    }
+
+    /// The names of all settings with a value or a default value.
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.values.keys().cloned().collect();
+
+        keys.extend(self.defaults.keys().filter(|key| !self.values.contains_key(*key)).cloned());
+        keys
+    }
+
+    /// Sets the values of all settings of `other`, see `SettingsContext::merge`.
+    pub fn merge(&mut self, other: &MockSettings) {
+        for key in other.keys() {
+            if let Some(value) = other.get(&key) {
+                self.values.insert(key, value.clone());
+            }
+        }
+    }
+
+    /// Uses the values of all settings of `other` as defaults, see
+    /// `SettingsContext::apply_defaults`.
+    pub fn apply_defaults(&mut self, other: &MockSettings) {
+        for key in other.keys() {
+            if let Some(value) = other.get(&key) {
+                self.defaults.insert(key, value.clone());
+            }
+        }
+    }
+
+    pub fn into_settings(self) -> SettingsContext {
+        SettingsContext::from_mock(self)
+    }
+}
+
+/// Owns the data and the mocked settings of a source, providing the `PluginContext` passed to
+/// its callbacks.
+pub struct MockPluginContext<D> {
+    wrapper: DataWrapper<D>,
+}
+
+impl<D> MockPluginContext<D> {
+    pub fn new(settings: MockSettings, data: D) -> Self {
+        Self {
+            wrapper: DataWrapper {
+                settings: Some(settings.into_settings()),
+                data: Some(data),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Creates the context to pass to the callbacks of the source.
+    pub fn context(&mut self) -> PluginContext<'_, D> {
+        unsafe { PluginContext::from(&mut self.wrapper as *mut DataWrapper<D> as *mut c_void) }
+    }
+
+    pub fn data(&self) -> &D {
+        self.wrapper.data.as_ref().expect("The data has been taken by a callback.")
+    }
+
+    pub fn data_mut(&mut self) -> &mut D {
+        self.wrapper.data.as_mut().expect("The data has been taken by a callback.")
+    }
+
+    /// The mocked settings, to change them between calls to the callbacks.
+    pub fn settings_mut(&mut self) -> &mut MockSettings {
+        self.wrapper.settings.as_mut()
+            .and_then(SettingsContext::mock_mut)
+            .expect("The settings have been replaced by a callback.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::ObsDataType;
+    use crate::source::properties::{PropertyDescriptor, PropertyDescriptorSpecializationF64, PropertyDescriptorSpecializationI32};
+    use std::ffi::CString;
+
+    fn gain() -> PropertyDescriptor<PropertyDescriptorSpecializationF64> {
+        PropertyDescriptor {
+            name: CString::new("gain").unwrap(),
+            description: CString::new("Gain").unwrap(),
+            specialization: PropertyDescriptorSpecializationF64 {
+                min: -30.0,
+                max: 30.0,
+                step: 0.5,
+                slider: true,
+            },
+        }
+    }
+
+    fn count() -> PropertyDescriptor<PropertyDescriptorSpecializationI32> {
+        PropertyDescriptor {
+            name: CString::new("count").unwrap(),
+            description: CString::new("Count").unwrap(),
+            specialization: PropertyDescriptorSpecializationI32 {
+                min: 0,
+                max: 10,
+                step: 1,
+                slider: false,
+            },
+        }
+    }
+
+    #[test]
+    fn values_take_precedence_over_defaults() {
+        let mut settings = MockSettings::new().with("gain", 6.0);
+
+        settings.set_default("gain", 0.0);
+        settings.set_default("count", 3);
+        assert_eq!(settings.get("gain"), Some(&Value::from(6.0)));
+        assert_eq!(settings.get("count"), Some(&Value::from(3)));
+        assert_eq!(settings.get("missing"), None);
+
+        settings.erase("gain");
+        assert_eq!(settings.get("gain"), Some(&Value::from(0.0)));
+        assert_eq!(settings.keys(), vec!["count".to_string(), "gain".to_string()]);
+    }
+
+    #[test]
+    fn merge_and_apply_defaults() {
+        let mut settings = MockSettings::new().with("gain", 6.0);
+        let other = MockSettings::new().with("gain", -6.0).with("count", 2);
+
+        settings.merge(&other);
+        assert_eq!(settings.get("gain"), Some(&Value::from(-6.0)));
+        assert_eq!(settings.get("count"), Some(&Value::from(2)));
+
+        let mut settings = MockSettings::new().with("gain", 6.0);
+
+        settings.apply_defaults(&other);
+        settings.erase("gain");
+        assert_eq!(settings.get("gain"), Some(&Value::from(-6.0)));
+    }
+
+    #[test]
+    fn settings_context_reads_and_writes_mock() {
+        let mut settings = MockSettings::new().with("gain", 6.0).into_settings();
+
+        assert_eq!(settings.get_property_value(&gain(), &0.0), 6.0);
+        assert_eq!(settings.get_property_value(&count(), &4), 4);
+        assert_eq!(settings.item_type(&CString::new("gain").unwrap()), Some(ObsDataType::Double));
+        assert_eq!(settings.item_type(&CString::new("count").unwrap()), None);
+
+        settings.set_property_value(&count(), 7);
+        assert_eq!(settings.get_property_value(&count(), &4), 7);
+        assert_eq!(settings.mock_mut().and_then(|mock| mock.get("count")), Some(&Value::from(7)));
+    }
+
+    #[test]
+    fn plugin_context_exposes_data_and_settings() {
+        let mut mock = MockPluginContext::new(MockSettings::new().with("gain", 6.0), 1.0f64);
+
+        {
+            let mut context = mock.context();
+            let (data, settings) = context.data_settings_mut();
+            let gain = settings.get_property_value(&gain(), &0.0);
+
+            if let Some(data) = data {
+                *data += gain;
+            }
+        }
+
+        assert_eq!(*mock.data(), 7.0);
+
+        mock.settings_mut().set("gain", -2.0);
+        *mock.data_mut() = 0.0;
+        assert_eq!(mock.context().settings_mut().get_property_value(&gain(), &0.0), -2.0);
+        assert_eq!(*mock.data(), 0.0);
+    }
+}
@@ -2,3 +2,299 @@
 /// Use `Box<dyn IteratorExactSizeIterator<I>>` instead.
 pub trait IteratorExactSizeIterator<I>: Iterator<Item=I> + ExactSizeIterator {}
 impl<I, J> IteratorExactSizeIterator<I> for J where J: Iterator<Item=I> + ExactSizeIterator {}
+
+/// Accumulates the time deltas passed to `VideoTickSource::video_tick` and counts how many
+/// fixed-length intervals have elapsed, so that animations can advance in fixed steps,
+/// independently of the rate at which the source is ticked.
+///
+/// ```rs
+/// // In `create`
+/// let timer = TickTimer::from_framerate(ObsVideoInfo::get().unwrap().framerate());
+///
+/// // In `video_tick`
+/// for _ in 0..data.timer.advance(seconds) {
+///     data.animation.step();
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TickTimer {
+    interval: f64,
+    accumulated: f64,
+}
+
+impl TickTimer {
+    /// Creates a timer firing every `interval_seconds` seconds.
+    ///
+    /// Panics if the interval is not positive.
+    pub fn new(interval_seconds: f64) -> Self {
+        assert!(interval_seconds > 0.0, "The interval of a `TickTimer` must be positive.");
+
+        Self {
+            interval: interval_seconds,
+            accumulated: 0.0,
+        }
+    }
+
+    /// Creates a timer firing once per frame at the given framerate.
+    pub fn from_framerate(framerate: crate::info::FramesPerSecond) -> Self {
+        Self::new(1.0 / framerate.as_f64())
+    }
+
+    pub fn interval(&self) -> f64 {
+        self.interval
+    }
+
+    /// Adds `seconds` to the accumulated time and returns the number of intervals that have
+    /// elapsed. The remainder is carried over to the next call.
+    pub fn advance(&mut self, seconds: f32) -> u32 {
+        self.accumulated += seconds.max(0.0) as f64;
+
+        let elapsed = (self.accumulated / self.interval).floor();
+        self.accumulated -= elapsed * self.interval;
+
+        elapsed as u32
+    }
+
+    /// How far the timer is into the current interval, from `0.0` to `1.0`, useful for
+    /// interpolating between steps.
+    pub fn fraction(&self) -> f64 {
+        self.accumulated / self.interval
+    }
+
+    /// Discards the accumulated time.
+    pub fn reset(&mut self) {
+        self.accumulated = 0.0;
+    }
+}
+
+/// The curve along which a `Tween` progresses from its start to its target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    /// Accelerates from the start.
+    EaseIn,
+    /// Decelerates towards the target.
+    EaseOut,
+    /// Accelerates from the start and decelerates towards the target, the smoothstep curve.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps the linear `progress`, from `0.0` to `1.0`, onto the curve.
+    pub fn apply(self, progress: f32) -> f32 {
+        let t = progress.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// A value which can be interpolated linearly, to be animated using a `Tween`.
+pub trait Interpolate: Clone {
+    /// Returns `self` for a `progress` of `0.0`, and `target` for `1.0`.
+    fn interpolate(&self, target: &Self, progress: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, target: &Self, progress: f32) -> Self {
+        self + (target - self) * progress
+    }
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, target: &Self, progress: f32) -> Self {
+        self + (target - self) * progress as f64
+    }
+}
+
+impl<const N: usize> Interpolate for [f32; N] {
+    fn interpolate(&self, target: &Self, progress: f32) -> Self {
+        let mut result = *self;
+
+        for (value, target) in result.iter_mut().zip(target.iter()) {
+            *value = value.interpolate(target, progress);
+        }
+
+        result
+    }
+}
+
+/// Animates a value towards a target over a duration, advanced by the time deltas passed to
+/// `VideoTickSource::video_tick`.
+///
+/// ```rs
+/// // In `update`
+/// data.zoom.set_target(settings.zoom, 0.3);
+///
+/// // In `video_tick`
+/// data.zoom.advance(seconds);
+///
+/// // In `video_render`
+/// let zoom = data.zoom.value();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tween<V: Interpolate> {
+    from: V,
+    to: V,
+    duration: f32,
+    elapsed: f32,
+    easing: Easing,
+}
+
+impl<V: Interpolate> Tween<V> {
+    /// Creates a tween resting at the `value`.
+    pub fn new(value: V, easing: Easing) -> Self {
+        Self {
+            from: value.clone(),
+            to: value,
+            duration: 0.0,
+            elapsed: 0.0,
+            easing,
+        }
+    }
+
+    /// Starts animating from the current value towards the `target`, over `duration` seconds.
+    /// A duration of 0 jumps to the target immediately.
+    pub fn set_target(&mut self, target: V, duration: f32) {
+        self.from = self.value();
+        self.to = target;
+        self.duration = duration.max(0.0);
+        self.elapsed = 0.0;
+    }
+
+    /// Jumps to the `value`, stopping the animation.
+    pub fn set_value(&mut self, value: V) {
+        self.from = value.clone();
+        self.to = value;
+        self.duration = 0.0;
+        self.elapsed = 0.0;
+    }
+
+    /// Advances the animation by `seconds`.
+    pub fn advance(&mut self, seconds: f32) {
+        self.elapsed = (self.elapsed + seconds.max(0.0)).min(self.duration);
+    }
+
+    /// The progress of the animation, from `0.0` to `1.0`, before applying the easing.
+    pub fn progress(&self) -> f32 {
+        if self.duration > 0.0 {
+            self.elapsed / self.duration
+        } else {
+            1.0
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    /// The current value of the animation.
+    pub fn value(&self) -> V {
+        self.from.interpolate(&self.to, self.easing.apply(self.progress()))
+    }
+
+    pub fn target(&self) -> &V {
+        &self.to
+    }
+}
+
+/// Access to the clock used by OBS for timestamps.
+pub mod time {
+    extern "C" {
+        fn os_gettime_ns() -> u64;
+    }
+
+    /// The current time of the monotonic clock used by OBS for timestamps, in nanoseconds.
+    ///
+    /// The clock is not affected by changes of the system time, and its origin is unspecified,
+    /// so its values are only meaningful relative to each other, e.g. as timestamps of frames
+    /// output by asynchronous sources.
+    pub fn now_ns() -> u64 {
+        unsafe { os_gettime_ns() }
+    }
+}
+
+/// Opens the `url` in the default web browser of the user, without waiting for it to open.
+pub fn open_url(url: &str) -> std::io::Result<()> {
+    use std::process::Command;
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        // `explorer` hands URLs to the default browser, unlike `cmd /C start`, it does not
+        // interpret the `&` characters of query strings.
+        let mut command = Command::new("explorer");
+        command.arg(url);
+        command
+    };
+
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("open");
+        command.arg(url);
+        command
+    };
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = {
+        let mut command = Command::new("xdg-open");
+        command.arg(url);
+        command
+    };
+
+    command.spawn().map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tick_timer_carries_the_remainder() {
+        let mut timer = TickTimer::new(0.5);
+
+        assert_eq!(timer.advance(0.375), 0);
+        assert_eq!(timer.fraction(), 0.75);
+        // 0.375 + 0.25 = 0.625, one interval with 0.125 left over.
+        assert_eq!(timer.advance(0.25), 1);
+        assert_eq!(timer.fraction(), 0.25);
+        assert_eq!(timer.advance(0.125), 0);
+        assert_eq!(timer.advance(0.25), 1);
+        assert_eq!(timer.fraction(), 0.0);
+    }
+
+    #[test]
+    fn tick_timer_fires_multiple_intervals() {
+        let mut timer = TickTimer::new(0.25);
+
+        assert_eq!(timer.advance(1.125), 4);
+        assert_eq!(timer.fraction(), 0.5);
+        assert_eq!(timer.advance(0.125), 1);
+        assert_eq!(timer.fraction(), 0.0);
+    }
+
+    #[test]
+    fn tick_timer_ignores_negative_time() {
+        let mut timer = TickTimer::new(0.5);
+
+        timer.advance(0.25);
+
+        assert_eq!(timer.advance(-1.0), 0);
+        assert_eq!(timer.fraction(), 0.5);
+
+        timer.reset();
+        assert_eq!(timer.fraction(), 0.0);
+    }
+
+    #[test]
+    fn tick_timer_at_framerate_fires_once_per_frame() {
+        let mut timer = TickTimer::new(1.0 / 60.0);
+        let fired: u32 = (0..600).map(|_| timer.advance(1.0 / 60.0)).sum();
+
+        // Rounding the deltas to `f32` may shift a single frame at the end.
+        assert!((599..=601).contains(&fired), "{}", fired);
+    }
+}
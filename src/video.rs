@@ -0,0 +1,477 @@
+use std::marker::PhantomData;
+use std::ops::Deref;
+use crate::util::time;
+use obs_sys::{
+    obs_source_frame, obs_source_frame2, obs_source_output_video2, obs_source_release_frame,
+    obs_source_t, video_format, video_format_get_parameters, video_colorspace_VIDEO_CS_DEFAULT,
+    video_range_type_VIDEO_RANGE_FULL, video_range_type_VIDEO_RANGE_PARTIAL,
+    video_format_VIDEO_FORMAT_AYUV, video_format_VIDEO_FORMAT_BGR3, video_format_VIDEO_FORMAT_BGRA,
+    video_format_VIDEO_FORMAT_BGRX, video_format_VIDEO_FORMAT_I40A, video_format_VIDEO_FORMAT_I420,
+    video_format_VIDEO_FORMAT_I422, video_format_VIDEO_FORMAT_I42A, video_format_VIDEO_FORMAT_I444,
+    video_format_VIDEO_FORMAT_NONE, video_format_VIDEO_FORMAT_NV12, video_format_VIDEO_FORMAT_RGBA,
+    video_format_VIDEO_FORMAT_UYVY, video_format_VIDEO_FORMAT_Y800, video_format_VIDEO_FORMAT_YUVA,
+    video_format_VIDEO_FORMAT_YUY2, video_format_VIDEO_FORMAT_YVYU,
+    obs_deinterlace_mode, obs_deinterlace_mode_OBS_DEINTERLACE_MODE_DISABLE,
+    obs_deinterlace_mode_OBS_DEINTERLACE_MODE_DISCARD, obs_deinterlace_mode_OBS_DEINTERLACE_MODE_RETRO,
+    obs_deinterlace_mode_OBS_DEINTERLACE_MODE_BLEND, obs_deinterlace_mode_OBS_DEINTERLACE_MODE_BLEND_2X,
+    obs_deinterlace_mode_OBS_DEINTERLACE_MODE_LINEAR, obs_deinterlace_mode_OBS_DEINTERLACE_MODE_LINEAR_2X,
+    obs_deinterlace_mode_OBS_DEINTERLACE_MODE_YADIF, obs_deinterlace_mode_OBS_DEINTERLACE_MODE_YADIF_2X,
+    obs_deinterlace_field_order, obs_deinterlace_field_order_OBS_DEINTERLACE_FIELD_ORDER_TOP,
+    obs_deinterlace_field_order_OBS_DEINTERLACE_FIELD_ORDER_BOTTOM,
+};
+
+/// The pixel format of a video frame.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VideoFormatKind {
+    None,
+    I420,
+    NV12,
+    YVYU,
+    YUY2,
+    UYVY,
+    RGBA,
+    BGRA,
+    BGRX,
+    Y800,
+    I444,
+    BGR3,
+    I422,
+    I40A,
+    I42A,
+    YUVA,
+    AYUV,
+}
+
+impl VideoFormatKind {
+    pub fn from_raw(raw: video_format) -> Self {
+        use VideoFormatKind::*;
+
+        #[allow(non_upper_case_globals)]
+        match raw {
+            video_format_VIDEO_FORMAT_I420 => I420,
+            video_format_VIDEO_FORMAT_NV12 => NV12,
+            video_format_VIDEO_FORMAT_YVYU => YVYU,
+            video_format_VIDEO_FORMAT_YUY2 => YUY2,
+            video_format_VIDEO_FORMAT_UYVY => UYVY,
+            video_format_VIDEO_FORMAT_RGBA => RGBA,
+            video_format_VIDEO_FORMAT_BGRA => BGRA,
+            video_format_VIDEO_FORMAT_BGRX => BGRX,
+            video_format_VIDEO_FORMAT_Y800 => Y800,
+            video_format_VIDEO_FORMAT_I444 => I444,
+            video_format_VIDEO_FORMAT_BGR3 => BGR3,
+            video_format_VIDEO_FORMAT_I422 => I422,
+            video_format_VIDEO_FORMAT_I40A => I40A,
+            video_format_VIDEO_FORMAT_I42A => I42A,
+            video_format_VIDEO_FORMAT_YUVA => YUVA,
+            video_format_VIDEO_FORMAT_AYUV => AYUV,
+            _ => None,
+        }
+    }
+
+    pub fn into_raw(self) -> video_format {
+        use VideoFormatKind::*;
+
+        match self {
+            None => video_format_VIDEO_FORMAT_NONE,
+            I420 => video_format_VIDEO_FORMAT_I420,
+            NV12 => video_format_VIDEO_FORMAT_NV12,
+            YVYU => video_format_VIDEO_FORMAT_YVYU,
+            YUY2 => video_format_VIDEO_FORMAT_YUY2,
+            UYVY => video_format_VIDEO_FORMAT_UYVY,
+            RGBA => video_format_VIDEO_FORMAT_RGBA,
+            BGRA => video_format_VIDEO_FORMAT_BGRA,
+            BGRX => video_format_VIDEO_FORMAT_BGRX,
+            Y800 => video_format_VIDEO_FORMAT_Y800,
+            I444 => video_format_VIDEO_FORMAT_I444,
+            BGR3 => video_format_VIDEO_FORMAT_BGR3,
+            I422 => video_format_VIDEO_FORMAT_I422,
+            I40A => video_format_VIDEO_FORMAT_I40A,
+            I42A => video_format_VIDEO_FORMAT_I42A,
+            YUVA => video_format_VIDEO_FORMAT_YUVA,
+            AYUV => video_format_VIDEO_FORMAT_AYUV,
+        }
+    }
+
+    /// The number of planes the pixel data is split into.
+    pub fn get_plane_count(self) -> usize {
+        use VideoFormatKind::*;
+
+        match self {
+            None => 0,
+            YVYU | YUY2 | UYVY | RGBA | BGRA | BGRX | Y800 | BGR3 | AYUV => 1,
+            NV12 => 2,
+            I420 | I444 | I422 => 3,
+            I40A | I42A | YUVA => 4,
+        }
+    }
+
+    /// The number of rows of the plane `plane`, for a frame of the given `height`.
+    pub fn get_plane_height(self, plane: usize, height: u32) -> u32 {
+        use VideoFormatKind::*;
+
+        if plane >= self.get_plane_count() {
+            return 0;
+        }
+
+        let subsampled = match self {
+            I420 | I40A => plane == 1 || plane == 2,
+            NV12 => plane == 1,
+            _ => false,
+        };
+
+        if subsampled {
+            (height + 1) / 2
+        } else {
+            height
+        }
+    }
+}
+
+/// A frame of video, as output by asynchronous video sources.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_frame)
+#[repr(transparent)]
+pub struct VideoFrame {
+    raw: obs_source_frame,
+}
+
+impl VideoFrame {
+    /// # Safety
+    /// The pointer must point to a valid frame, which outlives the lifetime `'a`.
+    pub unsafe fn from_raw<'a>(raw: *const obs_source_frame) -> &'a Self {
+        &*(raw as *const Self)
+    }
+
+    /// # Safety
+    /// The pointer must point to a valid frame, which outlives the lifetime `'a`, and which is
+    /// not accessed through any other pointer during it.
+    pub unsafe fn from_raw_mut<'a>(raw: *mut obs_source_frame) -> &'a mut Self {
+        &mut *(raw as *mut Self)
+    }
+
+    /// # Safety
+    /// Returns a pointer to the raw frame which if modified could cause UB.
+    pub unsafe fn as_raw(&self) -> *const obs_source_frame {
+        &self.raw
+    }
+
+    pub fn get_dimensions(&self) -> [u32; 2] {
+        [self.raw.width, self.raw.height]
+    }
+
+    pub fn get_format(&self) -> VideoFormatKind {
+        VideoFormatKind::from_raw(self.raw.format)
+    }
+
+    /// The timestamp of the frame, in nanoseconds.
+    pub fn get_timestamp(&self) -> u64 {
+        self.raw.timestamp
+    }
+
+    /// Whether the frame is upside down.
+    pub fn is_flipped(&self) -> bool {
+        self.raw.flip
+    }
+
+    pub fn is_full_range(&self) -> bool {
+        self.raw.full_range
+    }
+
+    /// The number of bytes per row of the plane `plane`.
+    pub fn get_linesize(&self, plane: usize) -> Option<u32> {
+        if plane < self.get_format().get_plane_count() {
+            Some(self.raw.linesize[plane])
+        } else {
+            None
+        }
+    }
+
+    /// The pixel data of the plane `plane`, including any padding at the end of each row.
+    pub fn get_plane(&self, plane: usize) -> Option<&[u8]> {
+        let linesize = self.get_linesize(plane)?;
+        let data = self.raw.data[plane];
+
+        if data.is_null() {
+            return None;
+        }
+
+        let rows = self.get_format().get_plane_height(plane, self.raw.height);
+
+        unsafe {
+            Some(std::slice::from_raw_parts(data, linesize as usize * rows as usize))
+        }
+    }
+
+    /// Like `get_plane`, but allows modifying the pixels in place, e.g. within
+    /// `FilterVideoSource::filter_video`. Returns `None` for planes not present in the format
+    /// of the frame.
+    pub fn get_plane_mut(&mut self, plane: usize) -> Option<&mut [u8]> {
+        let linesize = self.get_linesize(plane)?;
+        let data = self.raw.data[plane];
+
+        if data.is_null() {
+            return None;
+        }
+
+        let rows = self.get_format().get_plane_height(plane, self.raw.height);
+
+        unsafe {
+            Some(std::slice::from_raw_parts_mut(data, linesize as usize * rows as usize))
+        }
+    }
+}
+
+/// A frame retrieved from an asynchronous video source using `SourceContext::get_frame`,
+/// which is released back to the source when dropped.
+pub struct VideoFrameRef {
+    source: *mut obs_source_t,
+    frame: *mut obs_source_frame,
+}
+
+impl VideoFrameRef {
+    /// # Safety
+    /// The frame must have been retrieved from the `source` using `obs_source_get_frame`, and
+    /// must not be released by anything else.
+    pub(crate) unsafe fn from_raw(source: *mut obs_source_t, frame: *mut obs_source_frame) -> Option<Self> {
+        if frame.is_null() {
+            None
+        } else {
+            Some(Self { source, frame })
+        }
+    }
+}
+
+impl Deref for VideoFrameRef {
+    type Target = VideoFrame;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { VideoFrame::from_raw(self.frame) }
+    }
+}
+
+impl Drop for VideoFrameRef {
+    fn drop(&mut self) {
+        unsafe {
+            obs_source_release_frame(self.source, self.frame);
+        }
+    }
+}
+
+/// The rotation applied by OBS to the frames of an asynchronous video source, see
+/// `SourceContext::set_async_rotation`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VideoRotation {
+    None,
+    /// Rotated by 90 degrees clockwise.
+    Clockwise90,
+    Rotate180,
+    /// Rotated by 90 degrees counter-clockwise.
+    Clockwise270,
+}
+
+impl VideoRotation {
+    /// The clockwise rotation in degrees, as expected by OBS.
+    pub fn as_degrees(&self) -> i32 {
+        match self {
+            VideoRotation::None => 0,
+            VideoRotation::Clockwise90 => 90,
+            VideoRotation::Rotate180 => 180,
+            VideoRotation::Clockwise270 => 270,
+        }
+    }
+
+    /// Returns `None` for angles which are not a multiple of 90 degrees. Negative angles are
+    /// counter-clockwise.
+    pub fn from_degrees(degrees: i32) -> Option<Self> {
+        match degrees.rem_euclid(360) {
+            0 => Some(VideoRotation::None),
+            90 => Some(VideoRotation::Clockwise90),
+            180 => Some(VideoRotation::Rotate180),
+            270 => Some(VideoRotation::Clockwise270),
+            _ => None,
+        }
+    }
+}
+
+/// The deinterlacing OBS applies to the frames of an asynchronous video source, see
+/// `SourceContext::set_deinterlace_mode`. The `2x` variants output both fields as separate
+/// frames, doubling the frame rate.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DeinterlaceMode {
+    Disable,
+    Discard,
+    Retro,
+    Blend,
+    Blend2x,
+    Linear,
+    Linear2x,
+    Yadif,
+    Yadif2x,
+}
+
+impl DeinterlaceMode {
+    pub fn from_raw(raw: obs_deinterlace_mode) -> Self {
+        use DeinterlaceMode::*;
+
+        #[allow(non_upper_case_globals)]
+        match raw {
+            obs_deinterlace_mode_OBS_DEINTERLACE_MODE_DISCARD => Discard,
+            obs_deinterlace_mode_OBS_DEINTERLACE_MODE_RETRO => Retro,
+            obs_deinterlace_mode_OBS_DEINTERLACE_MODE_BLEND => Blend,
+            obs_deinterlace_mode_OBS_DEINTERLACE_MODE_BLEND_2X => Blend2x,
+            obs_deinterlace_mode_OBS_DEINTERLACE_MODE_LINEAR => Linear,
+            obs_deinterlace_mode_OBS_DEINTERLACE_MODE_LINEAR_2X => Linear2x,
+            obs_deinterlace_mode_OBS_DEINTERLACE_MODE_YADIF => Yadif,
+            obs_deinterlace_mode_OBS_DEINTERLACE_MODE_YADIF_2X => Yadif2x,
+            _ => Disable,
+        }
+    }
+
+    pub fn into_raw(self) -> obs_deinterlace_mode {
+        use DeinterlaceMode::*;
+
+        match self {
+            Disable => obs_deinterlace_mode_OBS_DEINTERLACE_MODE_DISABLE,
+            Discard => obs_deinterlace_mode_OBS_DEINTERLACE_MODE_DISCARD,
+            Retro => obs_deinterlace_mode_OBS_DEINTERLACE_MODE_RETRO,
+            Blend => obs_deinterlace_mode_OBS_DEINTERLACE_MODE_BLEND,
+            Blend2x => obs_deinterlace_mode_OBS_DEINTERLACE_MODE_BLEND_2X,
+            Linear => obs_deinterlace_mode_OBS_DEINTERLACE_MODE_LINEAR,
+            Linear2x => obs_deinterlace_mode_OBS_DEINTERLACE_MODE_LINEAR_2X,
+            Yadif => obs_deinterlace_mode_OBS_DEINTERLACE_MODE_YADIF,
+            Yadif2x => obs_deinterlace_mode_OBS_DEINTERLACE_MODE_YADIF_2X,
+        }
+    }
+}
+
+/// Which field of an interlaced frame comes first, see
+/// `SourceContext::set_deinterlace_field_order`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DeinterlaceFieldOrder {
+    Top,
+    Bottom,
+}
+
+impl DeinterlaceFieldOrder {
+    pub fn from_raw(raw: obs_deinterlace_field_order) -> Self {
+        #[allow(non_upper_case_globals)]
+        match raw {
+            obs_deinterlace_field_order_OBS_DEINTERLACE_FIELD_ORDER_BOTTOM => DeinterlaceFieldOrder::Bottom,
+            _ => DeinterlaceFieldOrder::Top,
+        }
+    }
+
+    pub fn into_raw(self) -> obs_deinterlace_field_order {
+        match self {
+            DeinterlaceFieldOrder::Top => obs_deinterlace_field_order_OBS_DEINTERLACE_FIELD_ORDER_TOP,
+            DeinterlaceFieldOrder::Bottom => obs_deinterlace_field_order_OBS_DEINTERLACE_FIELD_ORDER_BOTTOM,
+        }
+    }
+}
+
+/// A frame of video to be output by an asynchronous video source, using
+/// `SourceContext::output_video`.
+///
+/// The timestamp defaults to the time the frame was created at, see `time::now_ns`. Sources
+/// that know when a frame was captured, e.g. from a camera, should use that time instead, as
+/// OBS paces the frames according to their timestamps.
+///
+/// ```rs
+/// let frame = OutputVideoFrame::new(VideoFormatKind::RGBA, [width, height])
+///     .with_plane(0, &pixels, width * 4);
+///
+/// source.output_video(&frame);
+/// ```
+pub struct OutputVideoFrame<'a> {
+    raw: obs_source_frame2,
+    __marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> OutputVideoFrame<'a> {
+    pub fn new(format: VideoFormatKind, dimensions: [u32; 2]) -> Self {
+        Self {
+            raw: obs_source_frame2 {
+                data: [std::ptr::null_mut(); 8],
+                linesize: [0; 8],
+                width: dimensions[0],
+                height: dimensions[1],
+                timestamp: time::now_ns(),
+                format: format.into_raw(),
+                range: video_range_type_VIDEO_RANGE_PARTIAL,
+                color_matrix: [0.0; 16],
+                color_range_min: [0.0; 3],
+                color_range_max: [0.0; 3],
+                flip: false,
+            },
+            __marker: PhantomData,
+        }
+    }
+
+    /// Sets the pixel data of the plane `plane`, with `linesize` bytes per row. The data is
+    /// copied by OBS when the frame is output.
+    ///
+    /// Panics if the plane does not exist in the format of the frame, or if the data is too
+    /// short for the dimensions of the frame.
+    pub fn with_plane(mut self, plane: usize, data: &'a [u8], linesize: u32) -> Self {
+        let format = VideoFormatKind::from_raw(self.raw.format);
+
+        assert!(plane < format.get_plane_count(), "The plane {} does not exist in {:?}.", plane, format);
+        assert!(
+            data.len() >= linesize as usize * format.get_plane_height(plane, self.raw.height) as usize,
+            "The data of the plane {} is too short.",
+            plane,
+        );
+
+        self.raw.data[plane] = data.as_ptr() as *mut u8;
+        self.raw.linesize[plane] = linesize;
+        self
+    }
+
+    /// Sets the timestamp of the frame, in nanoseconds of the clock of `time::now_ns`.
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.raw.timestamp = timestamp;
+        self
+    }
+
+    /// Whether the YUV values of the frame use the full range, instead of the partial range.
+    pub fn with_full_range(mut self, full_range: bool) -> Self {
+        self.raw.range = if full_range {
+            video_range_type_VIDEO_RANGE_FULL
+        } else {
+            video_range_type_VIDEO_RANGE_PARTIAL
+        };
+        self
+    }
+
+    /// Whether the frame is upside down, i.e. its rows are stored bottom to top, as is common
+    /// for frames of capture APIs based on OpenGL or on Windows bitmaps. OBS flips the frame
+    /// while rendering it, which is cheaper than copying the rows in reverse order.
+    ///
+    /// The flip is applied to the frame before the rotation of the source, see
+    /// `SourceContext::set_async_rotation`, so a frame that is both flipped and rotated is
+    /// first flipped vertically, and then rotated.
+    pub fn with_flip(mut self, flip: bool) -> Self {
+        self.raw.flip = flip;
+        self
+    }
+
+    pub fn get_timestamp(&self) -> u64 {
+        self.raw.timestamp
+    }
+
+    /// # Safety
+    /// The `source` must be a valid asynchronous video source.
+    pub(crate) unsafe fn output(&self, source: *mut obs_source_t) {
+        let mut raw = self.raw;
+
+        video_format_get_parameters(
+            video_colorspace_VIDEO_CS_DEFAULT,
+            raw.range,
+            raw.color_matrix.as_mut_ptr(),
+            raw.color_range_min.as_mut_ptr(),
+            raw.color_range_max.as_mut_ptr(),
+        );
+
+        obs_source_output_video2(source, &raw);
+    }
+}